@@ -28,7 +28,7 @@ export interface ValidationIssue {
 }
 
 export type ValidateResult =
-  | { ok: true; errors?: ValidationIssue[] }
+  | { ok: true; errors?: ValidationIssue[]; expanded_pages?: unknown[] }
   | { ok: false; errors: ValidationIssue[] };
 
 export interface ObsScene {
@@ -41,6 +41,10 @@ fn err_response(err: ProfileError) -> Response {
             StatusCode::CONFLICT,
             serde_json::json!({ "error": "conflicting_write" }),
         ),
+        InvalidInclude(path) => (
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({ "error": "invalid_include", "path": path }),
+        ),
         Io(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             serde_json::json!({ "error": "io", "message": e.to_string() }),
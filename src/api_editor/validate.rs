@@ -8,7 +8,7 @@ use axum::{extract::State, response::IntoResponse, response::Response, Json};
 use serde::{Deserialize, Serialize};
 
 use super::EditorState;
-use crate::config::AppConfig;
+use crate::config::{control_ranges, AppConfig, PageConfig, ProcessConfig};
 
 /// Audit #72: explicit pre-parse cap on YAML bodies. `serde_yaml` is
 /// exponential on deeply nested input; the 1 MB axum body limit applied
@@ -33,6 +33,10 @@ pub struct ValidationIssue {
 pub enum ValidateResponse {
     Ok {
         ok: bool,
+        /// Pages after expansion (control ranges, `template`, `extends`),
+        /// when the profile uses any (what the router will actually load).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expanded_pages: Option<Vec<PageConfig>>,
     },
     Errors {
         ok: bool,
@@ -41,7 +45,7 @@ pub enum ValidateResponse {
 }
 
 pub async fn validate(
-    State(_s): State<Arc<EditorState>>,
+    State(s): State<Arc<EditorState>>,
     Json(req): Json<ValidateRequest>,
 ) -> Response {
     if req.body.len() > VALIDATE_BODY_LIMIT_BYTES {
@@ -63,11 +67,29 @@ pub async fn validate(
         )
             .into_response();
     }
-    match serde_yaml::from_str::<AppConfig>(&req.body) {
-        Ok(cfg) => {
+    // Merge `include:` fragments from the profile root first
+    let body = match s.profiles.resolve_includes(&req.body) {
+        Ok(resolved) => resolved.unwrap_or(req.body),
+        Err(e) => return single_error("include", e.to_string()),
+    };
+    let (body, ranges) = match control_ranges::expand_yaml(&body) {
+        Ok(Some(expanded)) => (expanded, true),
+        Ok(None) => (body, false),
+        Err(e) => return single_error("controls", format!("{:#}", e)),
+    };
+    match serde_yaml::from_str::<AppConfig>(&body) {
+        Ok(mut cfg) => {
+            let expanded = match cfg.expand_pages() {
+                Ok(expanded) => expanded || ranges,
+                Err(e) => return single_error("pages", format!("{:#}", e)),
+            };
             let issues = cross_field_checks(&cfg);
             if issues.is_empty() {
-                Json(ValidateResponse::Ok { ok: true }).into_response()
+                Json(ValidateResponse::Ok {
+                    ok: true,
+                    expanded_pages: expanded.then_some(cfg.pages),
+                })
+                .into_response()
             } else {
                 Json(ValidateResponse::Errors {
                     ok: false,
@@ -95,6 +117,19 @@ pub async fn validate(
     }
 }
 
+/// A failed validation with one issue.
+fn single_error(field_path: &str, message: String) -> Response {
+    Json(ValidateResponse::Errors {
+        ok: false,
+        errors: vec![ValidationIssue {
+            field_path: field_path.into(),
+            level: "error",
+            message,
+        }],
+    })
+    .into_response()
+}
+
 /// Cross-field validation: runs only after a successful parse.
 ///
 /// Currently checks:
@@ -146,5 +181,17 @@ pub fn cross_field_checks(cfg: &AppConfig) -> Vec<ValidationIssue> {
         }
     }
 
+    // the process allowlist is never read from a profile
+    if cfg.process.is_some() {
+        issues.push(ValidationIssue {
+            field_path: "process".into(),
+            level: "error",
+            message: format!(
+                "`process` can't be set in a profile: move it to {} next to config.yaml",
+                ProcessConfig::FILE_NAME
+            ),
+        });
+    }
+
     issues
 }
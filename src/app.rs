@@ -37,6 +37,7 @@ pub async fn run_app(
     tray_update_tx: crossbeam::channel::Sender<crate::tray::TrayUpdate>,
     profile_store: Arc<crate::config::profiles::ProfileStore>,
     live_tx: crate::event_bus::LiveEventTx,
+    process_allowlist: std::path::PathBuf,
 ) -> Result<()> {
     debug!("Starting main application loop...");
 
@@ -226,9 +227,63 @@ pub async fn run_app(
     // Register the Windows audio driver (no-op on non-Windows, gated on config).
     driver_setup::register_winaudio_driver(&config, &router, &feedback_tx, &led_tx).await;
 
+    // Register the Linux audio driver (PulseAudio / PipeWire master + per-app streams).
+    driver_setup::register_pulseaudio_driver(&config, &router, &feedback_tx, &led_tx).await;
+
     // Register the Windows media transport driver (play/pause/next/previous).
     driver_setup::register_winmedia_driver(&config, &router, &feedback_tx, &control_db).await;
 
+    // Register the Linux media driver (MPRIS transport + now-playing signals).
+    driver_setup::register_mpris_driver(
+        &config,
+        &router,
+        &feedback_tx,
+        &control_db,
+        &led_tx,
+        &api_state,
+    )
+    .await;
+
+    // Register the vMix TCP driver (gated on config / page references).
+    driver_setup::register_vmix_driver(
+        &config,
+        &router,
+        &feedback_tx,
+        &control_db,
+        &led_tx,
+        &api_state,
+        &tray_handler,
+    )
+    .await;
+
+    // Register the MMC / MTC transport driver (gated on the `mmc` section).
+    driver_setup::register_mmc_driver(
+        &config,
+        &router,
+        &control_db,
+        &led_tx,
+        &api_state,
+        &tray_handler,
+    )
+    .await;
+
+    // Register the process launcher driver (gated on the allowlist file).
+    driver_setup::register_process_driver(
+        &config,
+        &process_allowlist,
+        &router,
+        &control_db,
+        &led_tx,
+        &api_state,
+    )
+    .await;
+
+    // Register the user variables driver (`vars.*` signals).
+    driver_setup::register_vars_driver(&config, &router, &control_db, &led_tx, &api_state).await;
+
+    // Register the macro driver (`macros` step sequences).
+    driver_setup::register_macro_driver(&config, &router, &control_db, &led_tx, &api_state).await;
+
     // `feedback_tx` is kept alive for late driver registration on profile
     // switches; receiver lives in the main loop until shutdown.
     debug!("All drivers registered and initialized");
@@ -325,6 +380,15 @@ pub async fn run_app(
     let xtouch_out_failed = Arc::new(std::sync::atomic::AtomicBool::new(false));
     let mut xtouch_link_down = false;
 
+    // Screen saver: enters idle after `xtouch.idle.timeout_s` and keeps the
+    // idle text (clock) current. Unchanged LCD cells are dropped by the
+    // X-Touch output cache, so polling every second is cheap.
+    let mut idle_tick = tokio::time::interval(std::time::Duration::from_secs(1));
+    idle_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // LCD scrolling: finer than any sensible scroll interval
+    let mut scroll_tick = tokio::time::interval(std::time::Duration::from_millis(50));
+    scroll_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
     loop {
         tokio::select! {
             // Apply fader setpoints (from FaderSetpoint async tasks)
@@ -364,6 +428,8 @@ pub async fn run_app(
                     let active_page_name = router.get_active_page_name().await;
                     debug!("Display updated for page: {}", active_page_name);
                 }
+                // Surface updates caused by the input itself (encoder rings)
+                display::flush_pending_midi(&router, &xtouch, "input").await;
             }
 
             // Handle out-of-band display refresh requests (editor API page
@@ -390,6 +456,7 @@ pub async fn run_app(
                     obs_driver: obs_driver.as_ref(),
                     control_db: &control_db,
                     led_tx: &led_tx,
+                    process_allowlist: &process_allowlist,
                 };
                 handle_config_reload(
                     &router,
@@ -414,6 +481,23 @@ pub async fn run_app(
                 }
             }
 
+            // Screen saver
+            _ = idle_tick.tick() => {
+                for msg in router.poll_idle().await {
+                    if let Err(e) = xtouch.send_raw(&msg).await {
+                        warn!("Failed to send screen saver update: {}", e);
+                    }
+                }
+            }
+
+            _ = scroll_tick.tick() => {
+                for msg in router.poll_lcd_scroll().await {
+                    if let Err(e) = xtouch.send_raw(&msg).await {
+                        warn!("Failed to send LCD scroll update: {}", e);
+                    }
+                }
+            }
+
             // Handle tray commands
             Some(cmd) = tray_cmd_rx.recv() => {
                 if handle_tray_command(&router, &profile_store, cmd).await {
@@ -751,6 +835,8 @@ struct ReloadDeps<'a> {
     obs_driver: Option<&'a Arc<ObsDriver>>,
     control_db: &'a Arc<crate::control_mapping::ControlMappingDB>,
     led_tx: &'a mpsc::Sender<Vec<u8>>,
+    /// `process` allowlist file, next to the watched config.
+    process_allowlist: &'a std::path::Path,
 }
 
 /// Handle configuration file reload, updating display, gamepad, and API state.
@@ -764,6 +850,14 @@ async fn handle_config_reload(
 ) {
     info!("Configuration file changed, reloading...");
 
+    let device = new_config.device();
+    if device != crate::control_mapping::active_device() {
+        warn!(
+            "xtouch.device changed to {:?}: restart to switch the control layout",
+            device
+        );
+    }
+
     // Extract gamepad config before moving new_config into update_config
     let new_gamepad_config = new_config.gamepad.clone();
 
@@ -778,8 +872,63 @@ async fn handle_config_reload(
     .await;
     driver_setup::register_winaudio_driver(&new_config, router, deps.feedback_tx, deps.led_tx)
         .await;
+    driver_setup::register_pulseaudio_driver(&new_config, router, deps.feedback_tx, deps.led_tx)
+        .await;
     driver_setup::register_winmedia_driver(&new_config, router, deps.feedback_tx, deps.control_db)
         .await;
+    driver_setup::register_mpris_driver(
+        &new_config,
+        router,
+        deps.feedback_tx,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+    )
+    .await;
+    driver_setup::register_vmix_driver(
+        &new_config,
+        router,
+        deps.feedback_tx,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+        deps.tray_handler,
+    )
+    .await;
+    driver_setup::register_mmc_driver(
+        &new_config,
+        router,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+        deps.tray_handler,
+    )
+    .await;
+    driver_setup::register_process_driver(
+        &new_config,
+        deps.process_allowlist,
+        router,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+    )
+    .await;
+    driver_setup::register_vars_driver(
+        &new_config,
+        router,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+    )
+    .await;
+    driver_setup::register_macro_driver(
+        &new_config,
+        router,
+        deps.control_db,
+        deps.led_tx,
+        api_state,
+    )
+    .await;
     if let Some(obs_driver) = deps.obs_driver {
         if new_config.references_app(crate::state::AppKey::Obs.as_str()) {
             driver_setup::register_obs_driver(
@@ -0,0 +1,441 @@
+//! Control ranges: one `controls` entry for a run of similar controls.
+//!
+//! ```yaml
+//! controls:
+//!   "fader{1..8}": { app: voicemeeter, midi: { type: cc, channel: 1, cc: "{0+i}" } }
+//!   "{function}": { app: obs, action: changeScene, params: ["Scene {n}"] }
+//! ```
+//!
+//! A key holding `{a..b}` expands to one control per number; a key holding
+//! `{<group>}` to every control of that group in the control CSV of the
+//! configured `xtouch.device` (`function`, `transport`, `view`, ...). In
+//! the entry's strings, `{expr}` is replaced by an integer expression over
+//! `i` (0-based index in the range) and `n` (the number from the range;
+//! `i + 1` for a group) with `+ - *`. A string that is exactly `{expr}`
+//! becomes a number, so it fits `cc`, `channel`, ... `${...}` placeholders
+//! are left alone.
+//!
+//! Expansion runs on the raw YAML, before parsing. An entry written out
+//! next to a range overrides the generated one.
+
+use super::{ControlMapping, DeviceProfile};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+/// Upper bound on the controls generated by one range.
+const MAX_RANGE_LEN: i64 = 128;
+
+/// Expand the control ranges of every page, `pages_global` and
+/// `page_templates`. Returns whether any range was found.
+pub fn expand(config: &mut Value) -> Result<bool> {
+    let Some(root) = config.as_mapping_mut() else {
+        return Ok(false);
+    };
+    // Groups come from the configured surface's layout
+    let device: DeviceProfile = match root.get("xtouch").and_then(|x| x.get("device")) {
+        Some(device) => serde_yaml::from_value(device.clone()).context("Invalid xtouch.device")?,
+        None => DeviceProfile::default(),
+    };
+    let mut expanded = false;
+
+    if let Some(pages) = root.get_mut("pages").and_then(Value::as_sequence_mut) {
+        for (idx, page) in pages.iter_mut().enumerate() {
+            let label = match page.get("name").and_then(Value::as_str) {
+                Some(name) => format!("page '{}'", name),
+                None => format!("page {}", idx),
+            };
+            expanded |=
+                expand_controls(page, device, true).with_context(|| format!("In {}", label))?;
+        }
+    }
+    if let Some(global) = root.get_mut("pages_global") {
+        expanded |= expand_controls(global, device, true).context("In pages_global")?;
+    }
+    if let Some(templates) = root
+        .get_mut("page_templates")
+        .and_then(Value::as_mapping_mut)
+    {
+        for (name, template) in templates.iter_mut() {
+            // Template parameters aren't substituted yet: skip the type check
+            expanded |= expand_controls(template, device, false).with_context(|| {
+                format!("In page template '{}'", name.as_str().unwrap_or_default())
+            })?;
+        }
+    }
+    Ok(expanded)
+}
+
+/// [`expand`] on a YAML body: the expanded YAML, or `None` when it has no
+/// range (or doesn't parse).
+pub fn expand_yaml(body: &str) -> Result<Option<String>> {
+    let Ok(mut value) = serde_yaml::from_str::<Value>(body) else {
+        return Ok(None);
+    };
+    if !expand(&mut value)? {
+        return Ok(None);
+    }
+    Ok(Some(serde_yaml::to_string(&value)?))
+}
+
+/// Expand the ranges of `holder.controls`. With `check`, each generated
+/// entry must parse as a control mapping.
+fn expand_controls(holder: &mut Value, device: DeviceProfile, check: bool) -> Result<bool> {
+    let Some(controls) = holder.get_mut("controls").and_then(Value::as_mapping_mut) else {
+        return Ok(false);
+    };
+
+    let mut out = Mapping::new();
+    let mut generated: Vec<(String, Value)> = Vec::new();
+    let mut origins: HashMap<String, String> = HashMap::new();
+    for (key, entry) in std::mem::take(controls) {
+        let ids = match key.as_str().and_then(|key| range_ids(key, device)) {
+            Some(ids) => ids?,
+            None => {
+                out.insert(key, entry);
+                continue;
+            },
+        };
+        let pattern = key.as_str().unwrap_or_default();
+        for (idx, (id, n)) in ids.into_iter().enumerate() {
+            if let Some(other) = origins.insert(id.clone(), pattern.to_string()) {
+                bail!(
+                    "control '{}' is generated by both '{}' and '{}'",
+                    id,
+                    other,
+                    pattern
+                );
+            }
+            let entry = substitute(entry.clone(), idx as i64, n)
+                .and_then(|entry| {
+                    if check {
+                        serde_yaml::from_value::<ControlMapping>(entry.clone())?;
+                    }
+                    Ok(entry)
+                })
+                .with_context(|| {
+                    format!("Invalid control '{}' (generated from '{}')", id, pattern)
+                })?;
+            generated.push((id, entry));
+        }
+    }
+    if origins.is_empty() {
+        *controls = out;
+        return Ok(false);
+    }
+
+    for (id, entry) in generated {
+        // Written-out entries win
+        if !out.contains_key(id.as_str()) {
+            out.insert(Value::String(id), entry);
+        }
+    }
+    *controls = out;
+    Ok(true)
+}
+
+/// Control ids (with their `n`) for a range key; `None` for a plain
+/// control id.
+fn range_ids(key: &str, device: DeviceProfile) -> Option<Result<Vec<(String, i64)>>> {
+    let start = key.find('{')?;
+    let len = key[start..].find('}')?;
+    let (prefix, inner, suffix) = (
+        &key[..start],
+        &key[start + 1..start + len],
+        &key[start + len + 1..],
+    );
+    Some(
+        range_ids_of(prefix, inner.trim(), suffix, device)
+            .with_context(|| format!("Invalid control range '{}'", key)),
+    )
+}
+
+fn range_ids_of(
+    prefix: &str,
+    inner: &str,
+    suffix: &str,
+    device: DeviceProfile,
+) -> Result<Vec<(String, i64)>> {
+    if let Some((first, last)) = inner.split_once("..") {
+        let first: i64 = first
+            .trim()
+            .parse()
+            .context("range start is not a number")?;
+        let last: i64 = last.trim().parse().context("range end is not a number")?;
+        if first > last {
+            bail!("range start {} is after its end {}", first, last);
+        }
+        if last - first >= MAX_RANGE_LEN {
+            bail!("ranges are limited to {} controls", MAX_RANGE_LEN);
+        }
+        return Ok((first..=last)
+            .map(|n| (format!("{}{}{}", prefix, n, suffix), n))
+            .collect());
+    }
+
+    let controls = device_controls(device)?;
+    let group: Vec<&str> = controls
+        .iter()
+        .filter(|(_, group)| group == inner)
+        .map(|(id, _)| id.as_str())
+        .collect();
+    if group.is_empty() {
+        let mut groups: Vec<&str> = controls.iter().map(|(_, group)| group.as_str()).collect();
+        groups.sort_unstable();
+        groups.dedup();
+        bail!(
+            "'{}' is neither a range (a..b) nor a control group of {:?} ({})",
+            inner,
+            device,
+            groups.join(", ")
+        );
+    }
+    Ok(group
+        .into_iter()
+        .enumerate()
+        .map(|(idx, id)| (format!("{}{}{}", prefix, id, suffix), idx as i64 + 1))
+        .collect())
+}
+
+/// `(control_id, group)` rows of the device's embedded control CSV, in
+/// file order.
+fn device_controls(device: DeviceProfile) -> Result<Vec<(String, String)>> {
+    let mut reader = csv::Reader::from_reader(device.control_csv().1.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            let record = record.context("Failed to parse control CSV row")?;
+            match (record.get(0), record.get(1)) {
+                (Some(id), Some(group)) => Ok((id.to_string(), group.to_string())),
+                _ => Err(anyhow!("control CSV row without a group")),
+            }
+        })
+        .collect()
+}
+
+/// Replace the `{expr}` of every string in `value`.
+fn substitute(value: Value, i: i64, n: i64) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => substitute_str(s, i, n)?,
+        Value::Sequence(items) => Value::Sequence(
+            items
+                .into_iter()
+                .map(|item| substitute(item, i, n))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Mapping(map) => Value::Mapping(
+            map.into_iter()
+                .map(|(k, v)| Ok((k, substitute(v, i, n)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other,
+    })
+}
+
+fn substitute_str(s: String, i: i64, n: i64) -> Result<Value> {
+    if !s.contains('{') {
+        return Ok(Value::String(s));
+    }
+    // Whole-string expression: a number
+    if let Some(expr) = s.strip_prefix('{').and_then(|r| r.strip_suffix('}')) {
+        if is_expression(expr) {
+            return Ok(Value::from(eval(expr, i, n)?));
+        }
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s.as_str();
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let end = start + len;
+        let expr = &rest[start + 1..end];
+        out.push_str(&rest[..start]);
+        if rest[..start].ends_with('$') || !is_expression(expr) {
+            out.push_str(&rest[start..=end]);
+        } else {
+            out.push_str(&eval(expr, i, n)?.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+/// Only braces holding `i`, `n`, digits and operators are expressions;
+/// anything else (JSON, text) is kept as is.
+fn is_expression(expr: &str) -> bool {
+    !expr.trim().is_empty()
+        && expr
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, 'i' | 'n' | '+' | '-' | '*' | ' '))
+}
+
+fn eval(expr: &str, i: i64, n: i64) -> Result<i64> {
+    let mut parser = Expr {
+        tokens: expr.chars().filter(|c| !c.is_whitespace()).collect(),
+        pos: 0,
+        i,
+        n,
+    };
+    match parser.sum() {
+        Some(value) if parser.pos == parser.tokens.len() => Ok(value),
+        _ => bail!("invalid expression '{{{}}}'", expr),
+    }
+}
+
+/// Recursive descent over `sum := product (('+' | '-') product)*`,
+/// `product := atom ('*' atom)*`, `atom := '-'? (integer | i | n)`.
+struct Expr {
+    tokens: Vec<char>,
+    pos: usize,
+    i: i64,
+    n: i64,
+}
+
+impl Expr {
+    fn sum(&mut self) -> Option<i64> {
+        let mut acc = self.product()?;
+        while let Some(&op) = self.tokens.get(self.pos) {
+            if op != '+' && op != '-' {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.product()?;
+            acc = if op == '+' {
+                acc.checked_add(rhs)?
+            } else {
+                acc.checked_sub(rhs)?
+            };
+        }
+        Some(acc)
+    }
+
+    fn product(&mut self) -> Option<i64> {
+        let mut acc = self.atom()?;
+        while self.tokens.get(self.pos) == Some(&'*') {
+            self.pos += 1;
+            acc = acc.checked_mul(self.atom()?)?;
+        }
+        Some(acc)
+    }
+
+    fn atom(&mut self) -> Option<i64> {
+        let token = *self.tokens.get(self.pos)?;
+        self.pos += 1;
+        match token {
+            '-' => self.atom()?.checked_neg(),
+            'i' => Some(self.i),
+            'n' => Some(self.n),
+            digit if digit.is_ascii_digit() => {
+                let mut value = i64::from(digit.to_digit(10)?);
+                while let Some(digit) = self.tokens.get(self.pos).and_then(|c| c.to_digit(10)) {
+                    value = value.checked_mul(10)?.checked_add(i64::from(digit))?;
+                    self.pos += 1;
+                }
+                Some(value)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_str(yaml: &str) -> Result<Value> {
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        expand(&mut value)?;
+        Ok(value)
+    }
+
+    #[test]
+    fn ranges_and_groups_expand() {
+        let value = expand_str(
+            r#"
+pages:
+  - name: Mix
+    controls:
+      "fader{1..8}": { app: voicemeeter, midi: { type: cc, channel: 1, cc: "{0+i}" } }
+      "mute{1..8}": { app: voicemeeter, action: toggle, params: ["Strip[{i}].Mute", "${value}"] }
+      fader3: { app: qlc }
+      "{function}": { app: obs, action: changeScene, params: ["Scene {n}", "{json}"] }
+"#,
+        )
+        .unwrap();
+        let controls = &value["pages"][0]["controls"];
+        assert_eq!(controls.as_mapping().unwrap().len(), 24);
+        assert_eq!(controls["fader1"]["midi"]["cc"], Value::from(0));
+        assert_eq!(controls["fader8"]["midi"]["cc"], Value::from(7));
+        assert_eq!(controls["fader3"]["app"], Value::from("qlc"));
+        assert_eq!(
+            controls["mute2"]["params"],
+            serde_yaml::from_str::<Value>(r#"["Strip[1].Mute", "${value}"]"#).unwrap()
+        );
+        assert_eq!(controls["f8"]["params"][0], Value::from("Scene 8"));
+        assert_eq!(controls["f8"]["params"][1], Value::from("{json}"));
+    }
+
+    #[test]
+    fn errors_name_the_generated_entry() {
+        let err = expand_str(
+            r#"
+pages:
+  - name: Mix
+    controls:
+      "fader{1..8}": { app: qlc, midi: { type: cc, cc: "{200+i*20}" } }
+"#,
+        )
+        .unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(
+            message.contains("'fader4' (generated from 'fader{1..8}')"),
+            "{}",
+            message
+        );
+        assert!(message.contains("page 'Mix'"), "{}", message);
+
+        for bad in [
+            "pages: [{controls: {\"f{8..1}\": {app: qlc}}}]",
+            "pages: [{controls: {\"f{nope}\": {app: qlc}}}]",
+            "pages: [{controls: {\"f{1..3}\": {app: qlc, params: [\"{i+}\"]}}}]",
+            "pages: [{controls: {\"f{1..8}\": {app: qlc}, \"{function}\": {app: qlc}}}]",
+        ] {
+            assert!(expand_str(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn groups_follow_the_configured_device() {
+        let value = expand_str(
+            r#"
+xtouch: { device: xtouch_one }
+pages:
+  - name: One
+    controls:
+      "{transport}": { app: obs, action: changeScene, params: ["Scene {n}"] }
+"#,
+        )
+        .unwrap();
+        let controls = value["pages"][0]["controls"].as_mapping().unwrap();
+        assert_eq!(controls.len(), 5);
+        assert_eq!(controls["record"]["params"][0], Value::from("Scene 5"));
+
+        let err = expand_str(
+            "xtouch: { device: xtouch_one }\npages: [{controls: {\"{function}\": {app: qlc}}}]",
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", err).contains("XtouchOne"), "{:#}", err);
+    }
+
+    #[test]
+    fn expressions() {
+        assert_eq!(eval("0+i", 3, 4).unwrap(), 3);
+        assert_eq!(eval("n * 2 - 1", 0, 5).unwrap(), 9);
+        assert_eq!(eval("-i+16", 2, 3).unwrap(), 14);
+        assert!(eval("i i", 0, 1).is_err());
+        assert!(eval("*", 0, 1).is_err());
+    }
+}
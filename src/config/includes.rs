@@ -0,0 +1,237 @@
+//! `include:` directives: merge YAML fragments from other files into a
+//! config.
+//!
+//! ```yaml
+//! include: [shared/cameras.yaml, shared/global-controls.yaml]
+//! ```
+//!
+//! Paths are relative to the including file (absolute paths work too).
+//! Fragments are merged in order, then the including file on top: mappings
+//! merge key by key, lists are appended (fragment items first), any other
+//! value replaces the fragment's. Fragments may include other fragments;
+//! cycles are an error.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Top-level key holding the include list.
+pub const INCLUDE_KEY: &str = "include";
+
+/// Nesting limit, well past any sane layout.
+const MAX_DEPTH: usize = 16;
+
+/// A config with its includes merged in.
+#[derive(Debug)]
+pub struct Bundle {
+    pub value: Value,
+    /// Every file the bundle was read from, the top-level one first.
+    pub files: Vec<PathBuf>,
+}
+
+/// Paths listed under `include:` (a string or a list), as written.
+pub fn include_paths(value: &Value) -> Result<Vec<String>> {
+    let Some(include) = value.as_mapping().and_then(|m| m.get(INCLUDE_KEY)) else {
+        return Ok(Vec::new());
+    };
+    match include {
+        Value::Null => Ok(Vec::new()),
+        Value::String(path) => Ok(vec![path.clone()]),
+        Value::Sequence(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("`include` entries must be file paths"))
+            })
+            .collect(),
+        _ => bail!("`include` must be a file path or a list of file paths"),
+    }
+}
+
+/// Whether `path` (relative) stays inside its base directory, e.g. not
+/// `../other.yaml` or `/etc/file.yaml`.
+pub fn is_contained(path: &str) -> bool {
+    let mut depth: usize = 0;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {},
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+/// Merge the includes of `value`, read from `file`. With `root`, every
+/// included file must be inside that directory.
+pub fn resolve(value: Value, file: &Path, root: Option<&Path>) -> Result<Bundle> {
+    let root = root
+        .map(|root| {
+            fs::canonicalize(root).with_context(|| format!("Failed to resolve {}", root.display()))
+        })
+        .transpose()?;
+    let mut files = vec![file.to_path_buf()];
+    let mut stack = vec![fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf())];
+    let value = resolve_in(value, file, root.as_deref(), &mut stack, &mut files)?;
+    Ok(Bundle { value, files })
+}
+
+fn resolve_in(
+    mut value: Value,
+    file: &Path,
+    root: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<Value> {
+    let paths = include_paths(&value).with_context(|| format!("In {}", file.display()))?;
+    if let Value::Mapping(mapping) = &mut value {
+        mapping.remove(INCLUDE_KEY);
+    }
+    if paths.is_empty() {
+        return Ok(value);
+    }
+    if stack.len() > MAX_DEPTH {
+        bail!("includes nested more than {} levels deep", MAX_DEPTH);
+    }
+
+    let base = file.parent().unwrap_or_else(|| Path::new(""));
+    let mut merged = Value::Mapping(Default::default());
+    for path in paths {
+        let target = fs::canonicalize(base.join(&path))
+            .with_context(|| format!("Failed to find include '{}' of {}", path, file.display()))?;
+        if let Some(root) = root {
+            if !target.starts_with(root) {
+                bail!(
+                    "include '{}' of {} is outside of {}",
+                    path,
+                    file.display(),
+                    root.display()
+                );
+            }
+        }
+        if stack.contains(&target) {
+            bail!(
+                "include cycle: {} includes '{}' again",
+                file.display(),
+                path
+            );
+        }
+
+        let contents = fs::read_to_string(&target)
+            .with_context(|| format!("Failed to read include {}", target.display()))?;
+        let fragment: Value = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse YAML include: {}", target.display()))?;
+        if !matches!(fragment, Value::Mapping(_) | Value::Null) {
+            bail!("include {} must be a YAML mapping", target.display());
+        }
+        if !files.contains(&target) {
+            files.push(target.clone());
+        }
+
+        stack.push(target.clone());
+        let fragment = resolve_in(fragment, &target, root, stack, files)?;
+        stack.pop();
+        merge(&mut merged, fragment);
+    }
+    merge(&mut merged, value);
+    Ok(merged)
+}
+
+/// `over` on top of `base`: mappings merge, lists append, `null` keeps the
+/// base value, anything else replaces it.
+fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Mapping(base), Value::Mapping(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    },
+                }
+            }
+        },
+        (Value::Sequence(base), Value::Sequence(over)) => base.extend(over),
+        (_, Value::Null) => {},
+        (base, over) => *base = over,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, path: &str, contents: &str) -> PathBuf {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load(path: &Path, root: Option<&Path>) -> Result<Bundle> {
+        let value = serde_yaml::from_str(&fs::read_to_string(path)?)?;
+        resolve(value, path, root)
+    }
+
+    #[test]
+    fn fragments_merge_under_the_including_file() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "shared/cams.yaml",
+            "include: base.yaml\nobs:\n  camera_control:\n    cameras: [{id: jardin}]\n",
+        );
+        write(
+            dir.path(),
+            "shared/base.yaml",
+            "midi: {input_port: in, output_port: out}\npages: [{name: Shared}]\n",
+        );
+        let main = write(
+            dir.path(),
+            "main.yaml",
+            "include: [shared/cams.yaml]\nmidi: {output_port: main}\npages: [{name: Main}]\n",
+        );
+
+        let bundle = load(&main, Some(dir.path())).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            "midi: {input_port: in, output_port: main}\n\
+             pages: [{name: Shared}, {name: Main}]\n\
+             obs:\n  camera_control:\n    cameras: [{id: jardin}]\n",
+        )
+        .unwrap();
+        assert_eq!(bundle.value, expected);
+        assert_eq!(bundle.files.len(), 3);
+        assert_eq!(bundle.files[0], main);
+    }
+
+    #[test]
+    fn cycles_and_escapes_are_errors() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("profiles");
+        write(&root, "a.yaml", "include: b.yaml\n");
+        write(&root, "b.yaml", "include: a.yaml\n");
+        write(dir.path(), "secret.yaml", "midi: {}\n");
+        let escape = write(&root, "escape.yaml", "include: ../secret.yaml\n");
+
+        let err = load(&root.join("a.yaml"), None).unwrap_err();
+        assert!(format!("{:#}", err).contains("cycle"), "{:#}", err);
+        assert!(load(&escape, None).is_ok());
+        assert!(load(&escape, Some(&root)).is_err());
+    }
+
+    #[test]
+    fn contained_paths() {
+        assert!(is_contained("shared/cams.yaml"));
+        assert!(is_contained("./a/../b.yaml"));
+        assert!(!is_contained("../b.yaml"));
+        assert!(!is_contained("a/../../b.yaml"));
+        assert!(!is_contained("/etc/b.yaml"));
+    }
+}
@@ -2,15 +2,22 @@
 //!
 //! Handles loading, parsing, and hot-reloading of YAML configuration files.
 
+pub mod control_ranges;
+pub mod includes;
+pub mod page_templates;
 pub mod profiles;
+pub mod value_transform;
 pub mod watcher;
 
 use anyhow::{Context, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+pub use value_transform::ValueTransform;
+
 /// Root configuration structure
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct AppConfig {
@@ -30,6 +37,34 @@ pub struct AppConfig {
     /// Windows audio (master + per-app session) configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub winaudio: Option<WinAudioConfig>,
+    /// Linux audio (PulseAudio / PipeWire master + per-app stream) configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pulseaudio: Option<PulseAudioConfig>,
+    /// vMix TCP API connection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmix: Option<VmixConfig>,
+    /// Linux media player control over D-Bus MPRIS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mpris: Option<MprisConfig>,
+    /// MIDI Machine Control transport + MIDI timecode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mmc: Option<MmcConfig>,
+    /// Rejected: the `app: "process"` allowlist lives in its own file (see
+    /// [`ProcessConfig::FILE_NAME`]), out of reach of the editor and of
+    /// `include:` fragments. Only read to point at the move.
+    #[serde(default, skip_serializing)]
+    #[schemars(skip)]
+    pub process: Option<serde_yaml::Value>,
+    /// User variables set from `app: "vars"` mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vars: Option<VarsConfig>,
+    /// Named step sequences run from `app: "macro"` mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macros: Option<HashMap<String, MacroConfig>>,
+    /// Reusable pages instantiated with `template: <name>` (see
+    /// [`page_templates`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_templates: Option<HashMap<String, PageConfig>>,
     pub pages: Vec<PageConfig>,
 }
 
@@ -41,6 +76,36 @@ pub struct WinAudioConfig {
     pub pinned_apps: Vec<PinnedApp>,
 }
 
+/// Linux audio driver configuration. Speaks the PulseAudio native
+/// protocol, so it works with PulseAudio and with PipeWire's
+/// `pipewire-pulse` server alike.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct PulseAudioConfig {
+    /// Server socket path (e.g. "/run/user/1000/pulse/native"). Defaults to
+    /// `$PULSE_SERVER`, then `$XDG_RUNTIME_DIR/pulse/native`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Apps pinned to specific fader slots. Faders 1..=8. `process_name` is
+    /// matched against the stream's `application.process.binary` (e.g.
+    /// "firefox").
+    #[serde(default)]
+    pub pinned_apps: Vec<PinnedApp>,
+}
+
+/// Linux media player driver configuration (D-Bus MPRIS).
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct MprisConfig {
+    /// Players tried first when several are running, by bus-name suffix
+    /// (e.g. "spotify", "vlc"). Match is case-insensitive.
+    #[serde(default)]
+    pub preferred_players: Vec<String>,
+    /// LCD strip 1..=8 showing title (upper line) and artist (lower line)
+    /// while the active page uses `app: "mpris"`. Unset = no LCD output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1, max = 8))]
+    pub now_playing_strip: Option<u8>,
+}
+
 /// A pinned audio session: a process name fixed on a specific fader slot.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct PinnedApp {
@@ -90,6 +155,184 @@ pub struct ObsConfig {
     pub camera_control: Option<CameraControlConfig>,
 }
 
+/// vMix TCP API configuration
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct VmixConfig {
+    #[serde(default = "default_vmix_host")]
+    pub host: String,
+    #[serde(default = "default_vmix_port")]
+    pub port: u16,
+}
+
+impl Default for VmixConfig {
+    fn default() -> Self {
+        Self {
+            host: default_vmix_host(),
+            port: default_vmix_port(),
+        }
+    }
+}
+
+/// MIDI Machine Control / MIDI timecode configuration
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MmcConfig {
+    /// Output port (substring match) receiving MMC commands and generated MTC.
+    pub output_port: String,
+    /// Input port (substring match) read for incoming MTC (`mtc: receive`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_port: Option<String>,
+    /// MMC device ID 0..=127 (127 = all devices).
+    #[serde(default = "default_mmc_device_id")]
+    #[schemars(range(max = 127))]
+    pub device_id: u8,
+    /// MIDI timecode handling.
+    #[serde(default)]
+    pub mtc: MtcMode,
+    /// Timecode frame rate: 24, 25 or 30.
+    #[serde(default = "default_mtc_fps")]
+    pub fps: u8,
+    /// 29.97 drop-frame timecode (requires `fps: 30`).
+    #[serde(default)]
+    pub drop_frame: bool,
+    /// Show the MTC position on the 7-segment timecode display.
+    #[serde(default = "default_true")]
+    pub display: bool,
+}
+
+/// MIDI timecode mode
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MtcMode {
+    /// MMC commands only.
+    #[default]
+    Off,
+    /// Run an MTC clock following the MMC transport and send quarter frames.
+    Generate,
+    /// Read quarter frames / full frames from `input_port`.
+    Receive,
+}
+
+/// Process launcher configuration
+///
+/// Read from [`ProcessConfig::FILE_NAME`] next to `config.yaml`, never from
+/// a profile: the editor writes profiles and fragments, so an allowlist
+/// there would let it launch any program.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ProcessConfig {
+    /// Allowlist of launchable commands, by name. Mappings only reference
+    /// these names (`params: [name, ...]`); nothing else can be run.
+    #[serde(default)]
+    pub commands: HashMap<String, ProcessCommandConfig>,
+}
+
+impl ProcessConfig {
+    /// Allowlist file name, in the directory of `config.yaml`.
+    pub const FILE_NAME: &'static str = "process.yaml";
+
+    /// Read and validate the allowlist; `None` when the file doesn't exist.
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        let contents = match fs::read_to_string(path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let process: Self = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        validate_process(&process).with_context(|| format!("Invalid {}", path.display()))?;
+        Ok(Some(process))
+    }
+}
+
+/// One allowlisted command
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ProcessCommandConfig {
+    /// Executable path (or name looked up in `PATH`). Run directly, no shell.
+    pub program: String,
+    /// Arguments. `{1}`..`{9}` are replaced by the action's extra params.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Working directory (default: the gateway's).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// `run` kills the process if it is still running after this many
+    /// milliseconds (0 = wait forever).
+    #[serde(default = "default_process_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Publish the last non-empty stdout line as `process.<name>.stdout`.
+    #[serde(default)]
+    pub capture_stdout: bool,
+}
+
+/// User variables configuration
+///
+/// Variables don't need to be declared: `set` / `toggle` / ... create them.
+/// Declaring one gives it an initial value and a `cycle` list.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct VarsConfig {
+    /// Declared variables, by name (published as `vars.<name>`).
+    #[serde(default)]
+    pub variables: HashMap<String, VarConfig>,
+}
+
+/// One declared user variable
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct VarConfig {
+    /// Value on first start and after `reset` (default: null).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<serde_json::Value>,
+    /// Values stepped through by `cycle` when the action lists none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<serde_json::Value>,
+    /// Keep the value across restarts. When false, every start begins
+    /// from `initial`.
+    #[serde(default = "default_true")]
+    pub persist: bool,
+}
+
+/// Named macro: a sequence of steps with pauses, run in the background
+///
+/// Started with `app: "macro"`, `params: [name]`. Starting a macro that is
+/// already running cancels the current run first.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MacroConfig {
+    pub steps: Vec<MacroStep>,
+    /// Passes through `steps` (0 = loop until stopped, which needs a
+    /// `delay_ms` step). Default: 1.
+    #[serde(default = "default_macro_repeat")]
+    pub repeat: u32,
+}
+
+fn default_macro_repeat() -> u32 {
+    1
+}
+
+/// One macro step: `delay_ms`, then `wait_for`, then the action (any of the
+/// three may be omitted, e.g. a pure pause).
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MacroStep {
+    /// Pause before the step, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    /// Wait for a signal condition before the step's action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<MacroWaitConfig>,
+    /// Action dispatched like an `also` step (`app`, `action`, `params`,
+    /// `when`, ...).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub action: Option<ActionStep>,
+}
+
+/// Signal condition a macro waits for (indicator semantics)
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MacroWaitConfig {
+    #[serde(flatten)]
+    pub condition: IndicatorConfig,
+    /// Give up after this many milliseconds, which aborts the macro.
+    /// Default: wait until the condition holds or the macro is stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
 /// Camera control configuration for OBS split views
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct CameraControlConfig {
@@ -122,6 +365,9 @@ pub struct SplitConfig {
 /// X-Touch specific configuration
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct XTouchConfig {
+    /// Control surface model. See [`DeviceProfile`].
+    #[serde(default)]
+    pub device: DeviceProfile,
     #[serde(default = "default_xtouch_mode")]
     pub mode: XTouchMode,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -134,9 +380,41 @@ pub struct XTouchConfig {
     /// BUG-008 FIX: Prevents stale snapshot values from overriding fresh app feedback.
     #[serde(default = "default_startup_refresh_delay")]
     pub startup_refresh_delay_ms: u64,
+    /// Screen saver after a period without input. See [`IdleConfig`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<IdleConfig>,
+}
+
+/// Screen saver
+///
+/// After `timeout_s` without X-Touch or gamepad input the surface goes
+/// idle: LCD backlights off (`dim`) and `text` shown across the strips.
+/// The next input restores the page display. The state is published as the
+/// `surface.idle` signal (`true` while idle).
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct IdleConfig {
+    /// Seconds without input before going idle.
+    pub timeout_s: u64,
+    /// Turn the LCD backlights off while idle. Default: true.
+    #[serde(default = "default_true")]
+    pub dim: bool,
+    /// Text shown while idle, centered across the strips (upper and lower
+    /// line separated by a newline), e.g. `"ON AIR"`. `${time}` is the
+    /// current time (HH:MM:SS); other `${...}` placeholders read signals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
 }
 
 /// X-Touch operation mode
+///
+/// `mcu` speaks Mackie Control. `ctrl` is the X-Touch's plain MIDI mode,
+/// using the `ctrl_message` column of the control table: button LEDs and
+/// faders take the control's CTRL note / CC (faders in 7 bits), LCD text and
+/// colors go out as the per-strip Behringer SysEx, and paging notes
+/// (`paging.prev_note` / `next_note`, F1-F8) keep their MCU numbers.
+///
+/// Fallbacks: V-Pot rings show the plain value (no ring `mode`, no center
+/// LED), and a page refresh drops what has no CTRL counterpart.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum XTouchMode {
@@ -144,6 +422,76 @@ pub enum XTouchMode {
     Ctrl,
 }
 
+/// Control surface model
+///
+/// Selects the control layout (which control ids exist and their MIDI
+/// messages) and what the surface can show. Writes to an LCD or strip
+/// colors the device lacks are dropped. Read at startup.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceProfile {
+    /// Behringer X-Touch: 8 strips, LCDs with colors.
+    #[default]
+    Xtouch,
+    /// Behringer X-Touch One: a single strip, transport and jog wheel.
+    XtouchOne,
+    /// Behringer X-Touch Compact in MC mode: 8 strips, no LCD.
+    XtouchCompact,
+    /// Any Mackie Control Universal surface: 8 strips, monochrome LCD.
+    Mcu,
+    /// iCON Platform M+: 8 strips, no LCD.
+    PlatformM,
+}
+
+impl DeviceProfile {
+    /// Channel strips (the master fader aside).
+    pub fn strips(self) -> u8 {
+        match self {
+            Self::XtouchOne => 1,
+            _ => 8,
+        }
+    }
+
+    /// Whether the surface has the MCU LCD (scribble strips).
+    pub fn has_lcd(self) -> bool {
+        matches!(self, Self::Xtouch | Self::XtouchOne | Self::Mcu)
+    }
+
+    /// Whether the LCD backlight colors can be set.
+    pub fn has_lcd_colors(self) -> bool {
+        self == Self::Xtouch
+    }
+
+    /// Whether the surface has the X-Touch CTRL (plain MIDI) mode.
+    pub fn has_ctrl_mode(self) -> bool {
+        self == Self::Xtouch
+    }
+
+    /// Control CSV: path of the editable file (relative to the working
+    /// directory) and its embedded copy. Generic MCU surfaces share the
+    /// X-Touch MCU layout.
+    pub fn control_csv(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Xtouch | Self::Mcu => (
+                "docs/xtouch-matching.csv",
+                include_str!("../../docs/xtouch-matching.csv"),
+            ),
+            Self::XtouchOne => (
+                "docs/devices/xtouch-one.csv",
+                include_str!("../../docs/devices/xtouch-one.csv"),
+            ),
+            Self::XtouchCompact => (
+                "docs/devices/xtouch-compact.csv",
+                include_str!("../../docs/devices/xtouch-compact.csv"),
+            ),
+            Self::PlatformM => (
+                "docs/devices/platform-m.csv",
+                include_str!("../../docs/devices/platform-m.csv"),
+            ),
+        }
+    }
+}
+
 /// LCD overlay configuration
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct OverlayConfig {
@@ -277,8 +625,14 @@ pub struct GlobalPageDefaults {
 }
 
 /// Page configuration
+///
+/// `extends` / `template` are expanded at load time (see
+/// [`page_templates`]): the router only ever sees plain pages.
 #[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema)]
 pub struct PageConfig {
+    /// Page name, unique across pages. May be omitted on a `template` page
+    /// (the template's, with its parameters substituted, is used).
+    #[serde(default)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub controls: Option<HashMap<String, ControlMapping>>,
@@ -288,11 +642,48 @@ pub struct PageConfig {
     pub passthrough: Option<PassthroughConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub passthroughs: Option<Vec<PassthroughConfig>>,
+    /// Start from the page with this name, then apply this page's own
+    /// `controls` (per control), `lcd` and passthroughs on top.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Instantiate this entry of `page_templates`; this page's own fields
+    /// apply on top, like `extends`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Template parameters, substituted for `${param}` in the template's
+    /// strings. On a template itself: default values.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub with: HashMap<String, serde_json::Value>,
+    /// Bank this page's strips over more than eight channels with the
+    /// BANK / CHANNEL buttons.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bank: Option<BankConfig>,
 }
 
-/// LED indicator configuration
+/// Fader banking
+///
+/// BANK ◄/► shift the page's strips by eight channels, CHANNEL ◄/► by one,
+/// within `channels`. At offset `k`, strip `N` drives channel `N + k`: the
+/// `midi:` addresses of its controls (CC and note numbers, pitch bend
+/// channel) and `discovered:` / `auto` session slots move by `k`, and LCD
+/// labels and colors are taken from entry `N + k`. Controls are written for
+/// the first bank. Passthrough controls are not shifted.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct BankConfig {
+    /// Number of channels (more than 8).
+    pub channels: u8,
+}
+
+/// LED indicator configuration
+///
+/// Also the condition syntax of `when:` and macro `wait_for`. A condition
+/// tests `signal` and/or combines sub-conditions (`all`/`any`/`not`); every
+/// part that is set must hold.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct IndicatorConfig {
+    /// Signal read by the value tests. Optional when the condition only
+    /// combines sub-conditions.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub signal: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub equals: Option<serde_json::Value>,
@@ -301,6 +692,59 @@ pub struct IndicatorConfig {
     #[serde(rename = "in")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub in_array: Option<Vec<serde_json::Value>>,
+    /// Numeric value (or numeric string) strictly greater than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gt: Option<f64>,
+    /// Numeric value (or numeric string) strictly less than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lt: Option<f64>,
+    /// Numeric value (or numeric string) within `[min, max]`, inclusive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub between: Option<[f64; 2]>,
+    /// Every sub-condition holds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub all: Vec<IndicatorConfig>,
+    /// At least one sub-condition holds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub any: Vec<IndicatorConfig>,
+    /// The sub-condition does not hold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not: Option<Box<IndicatorConfig>>,
+    /// Indicators only: the LED blinks while this condition holds and the
+    /// main one doesn't (solid on wins).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blink: Option<Box<IndicatorConfig>>,
+}
+
+impl IndicatorConfig {
+    /// Whether `signal` is read anywhere in the condition, `blink` included.
+    pub fn reads_signal(&self, signal: &str) -> bool {
+        self.signal == signal || self.subconditions().any(|c| c.reads_signal(signal))
+    }
+
+    /// Whether the condition tests its `signal`'s value.
+    pub fn has_value_test(&self) -> bool {
+        self.equals.is_some()
+            || self.truthy.is_some()
+            || self.in_array.is_some()
+            || self.gt.is_some()
+            || self.lt.is_some()
+            || self.between.is_some()
+    }
+
+    /// Whether the condition combines sub-conditions (`all`/`any`/`not`).
+    pub fn is_compound(&self) -> bool {
+        !self.all.is_empty() || !self.any.is_empty() || self.not.is_some()
+    }
+
+    /// Nested conditions: `all`, `any`, `not` and `blink`.
+    fn subconditions(&self) -> impl Iterator<Item = &IndicatorConfig> {
+        self.all
+            .iter()
+            .chain(&self.any)
+            .chain(self.not.as_deref())
+            .chain(self.blink.as_deref())
+    }
 }
 
 /// Control mapping
@@ -313,6 +757,10 @@ pub struct ControlMapping {
     pub params: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub midi: Option<MidiSpec>,
+    /// Transformation de la valeur (plages, inversion, courbe, pas, zone morte),
+    /// appliquée à l'envoi et inversée sur le feedback. Voir [`ValueTransform`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<ValueTransform>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub overlay: Option<OverlayConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -324,6 +772,45 @@ pub struct ControlMapping {
     /// état réel), et non par l'appui bouton. Voir [`ToggleConfig`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub toggle: Option<ToggleConfig>,
+    /// Condition d'exécution, même sémantique qu'un `indicator` (`signal` +
+    /// `equals`/`truthy`/`in`), évaluée au dispatch sur la dernière valeur du
+    /// signal. Fausse : ni l'effet primaire ni `also` ne partent, `else` à la place.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<IndicatorConfig>,
+    /// Étapes déclenchées quand `when` est faux.
+    #[serde(rename = "else", default, skip_serializing_if = "Vec::is_empty")]
+    pub else_steps: Vec<ActionStep>,
+    /// App dont le feedback positionne le contrôle (moteur, LED, anneau)
+    /// quand il pilote plusieurs apps : `app` (défaut) ou l'app d'une étape
+    /// `also`. Le feedback des autres cibles ne touche pas la surface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_from: Option<String>,
+    /// Mode lié pour un contrôle continu à plusieurs cibles : un mouvement
+    /// décale chaque cible du même écart depuis sa propre dernière valeur,
+    /// au lieu de leur envoyer à toutes la position absolue, ce qui conserve
+    /// leurs écarts relatifs. Une seule cible par app.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link: bool,
+    /// Encodeur en valeur absolue : les crans relatifs s'accumulent en une
+    /// valeur 0..1 envoyée à l'app, affichée sur l'anneau LED et recalée
+    /// par le feedback. Voir [`EncoderConfig`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoder: Option<EncoderConfig>,
+    /// Fader : étapes déclenchées quand la main touche le fader (sélection
+    /// de caméra, overlay, solo...). Le moteur reste figé tant qu'il est touché.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_touch: Vec<ActionStep>,
+    /// Fader : étapes déclenchées quand la main lâche le fader.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_release: Vec<ActionStep>,
+    /// Reprise douce (soft takeover) : les mouvements du fader sont ignorés
+    /// tant qu'il n'a pas rejoint (ou franchi) la dernière valeur connue de
+    /// l'app, au lieu de la faire sauter à la position physique. Des flèches
+    /// sur le LCD indiquent le sens à suivre. Pour un moteur non fiable
+    /// (snapshot périmé, app hors ligne, cible non motorisée). La valeur de
+    /// l'app vient de son feedback MIDI : la cible doit avoir un `midi:`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pickup: bool,
 }
 
 impl ControlMapping {
@@ -338,8 +825,39 @@ impl ControlMapping {
             action: self.action.clone(),
             params: self.params.clone(),
             midi: self.midi.clone(),
+            value: self.value.clone(),
+            // La condition du contrôle porte sur l'ensemble (primaire + `also`),
+            // elle est évaluée par le routeur avant la sélection des étapes.
+            when: None,
+            else_steps: Vec::new(),
         }
     }
+
+    /// L'app dont le feedback positionne le contrôle : `feedback_from`, sinon `app`.
+    pub fn feedback_app(&self) -> &str {
+        self.feedback_from.as_deref().unwrap_or(&self.app)
+    }
+
+    /// Les cibles du contrôle `(app, midi, value)` : l'effet primaire puis
+    /// chaque étape `also`.
+    pub fn targets(
+        &self,
+    ) -> impl Iterator<Item = (&str, Option<&MidiSpec>, Option<&ValueTransform>)> {
+        std::iter::once((self.app.as_str(), self.midi.as_ref(), self.value.as_ref())).chain(
+            self.also
+                .iter()
+                .flatten()
+                .map(|step| (step.app.as_str(), step.midi.as_ref(), step.value.as_ref())),
+        )
+    }
+
+    /// La cible source du feedback (voir [`Self::feedback_app`]).
+    pub fn feedback_target(&self) -> (&str, Option<&MidiSpec>, Option<&ValueTransform>) {
+        let app = self.feedback_app();
+        self.targets()
+            .find(|(target, _, _)| *target == app)
+            .unwrap_or((self.app.as_str(), self.midi.as_ref(), self.value.as_ref()))
+    }
 }
 
 /// Une étape de dispatch : un effet unique (action driver OU envoi MIDI direct).
@@ -356,6 +874,16 @@ pub struct ActionStep {
     pub params: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub midi: Option<MidiSpec>,
+    /// Transformation de la valeur propre à cette étape. Voir [`ValueTransform`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<ValueTransform>,
+    /// Condition d'exécution de cette étape (sémantique d'un `indicator`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<IndicatorConfig>,
+    /// Étapes déclenchées à la place quand `when` est faux (elles peuvent
+    /// elles-mêmes porter un `when`).
+    #[serde(rename = "else", default, skip_serializing_if = "Vec::is_empty")]
+    pub else_steps: Vec<ActionStep>,
 }
 
 /// Toggle piloté par le feedback d'un app.
@@ -385,6 +913,49 @@ pub struct ToggleConfig {
     pub off: Vec<ActionStep>,
 }
 
+/// V-Pot utilisé comme paramètre absolu.
+///
+/// En mode MCU, chaque cran (relatif) déplace une valeur 0..1 mémorisée par
+/// le routeur de `step` ; en mode CTRL, le X-Touch envoie déjà la valeur
+/// absolue, qui la remplace. Le contrôle envoie alors cette valeur (comme un
+/// CC 0-127) à ses cibles, et l'anneau LED l'affiche selon `ring`. Le
+/// feedback de l'app (`feedback_from`) met à jour la valeur et l'anneau.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct EncoderConfig {
+    /// Affichage sur l'anneau LED (mode MCU).
+    #[serde(default)]
+    pub ring: RingMode,
+    /// Déplacement par cran, en fraction de la course (défaut 0.02, soit 50
+    /// crans). Mode MCU seulement.
+    #[serde(default = "default_encoder_step")]
+    pub step: f64,
+    /// Allume la LED centrale sous l'anneau.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub center_led: bool,
+    /// Valeur de départ, tant qu'aucun cran ni feedback n'est arrivé.
+    #[serde(default)]
+    pub initial: f64,
+}
+
+fn default_encoder_step() -> f64 {
+    0.02
+}
+
+/// Mode d'affichage de l'anneau LED d'un V-Pot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RingMode {
+    /// Une seule LED à la position.
+    #[default]
+    Dot,
+    /// Du centre vers la position (pan, gain ±).
+    BoostCut,
+    /// De la gauche jusqu'à la position (niveau).
+    Wrap,
+    /// Largeur symétrique autour du centre (largeur stéréo, Q).
+    Spread,
+}
+
 /// MIDI control specification
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct MidiSpec {
@@ -415,6 +986,50 @@ pub struct LcdConfig {
     pub labels: Option<Vec<LcdLabel>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub colors: Option<Vec<LcdColor>>,
+    /// Lines longer than their area scroll instead of being cut. See
+    /// [`LcdScrollConfig`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scroll: Option<LcdScrollConfig>,
+    /// Adjacent strips showing one wider text, in place of their labels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged: Option<Vec<LcdMergedText>>,
+}
+
+/// Scrolling of long LCD lines
+///
+/// A strip shows 7 characters per line (a merged area 7 per strip). Longer
+/// lines move one step every `interval_ms`, starting over on page changes.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct LcdScrollConfig {
+    #[serde(default)]
+    pub mode: LcdScrollMode,
+    /// Milliseconds per step. Default: 400.
+    #[serde(default = "default_lcd_scroll_interval")]
+    pub interval_ms: u64,
+}
+
+/// How a long LCD line scrolls
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LcdScrollMode {
+    /// Slide one character per step, wrapping around.
+    #[default]
+    Marquee,
+    /// Show the line in area-wide segments, one per step.
+    Segments,
+}
+
+/// Strips `from` to `to` (1-8) joined into one text area
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct LcdMergedText {
+    pub from: u8,
+    pub to: u8,
+    /// Upper line; `${...}` placeholders as in labels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper: Option<String>,
+    /// Lower line; `${...}` placeholders as in labels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lower: Option<String>,
 }
 
 /// LCD label (string or structured)
@@ -430,19 +1045,45 @@ pub enum LcdLabel {
     },
 }
 
-/// LCD color (numeric or string)
+/// LCD color (numeric, string, or chosen by a signal condition)
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum LcdColor {
     Numeric(u32),
     Named(String),
+    Bound(Box<LcdColorBinding>),
+}
+
+/// Color chosen by a condition (indicator syntax): `then` while it holds,
+/// `else` otherwise (black when unset). Re-evaluated when its signals change.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct LcdColorBinding {
+    #[serde(flatten)]
+    pub when: IndicatorConfig,
+    pub then: LcdColor,
+    #[serde(rename = "else", default, skip_serializing_if = "Option::is_none")]
+    pub otherwise: Option<LcdColor>,
 }
 
 impl LcdColor {
     /// Convert LCD color to X-Touch color value (0-7)
     /// Colors: 0=black, 1=red, 2=green, 3=yellow, 4=blue, 5=magenta, 6=cyan, 7=white
+    ///
+    /// A bound color takes its `else` branch here; see [`Self::resolve`].
     pub fn to_u8(&self) -> u8 {
+        self.resolve(&|_| false)
+    }
+
+    /// Like [`Self::to_u8`], with `holds` deciding the conditions of bound colors.
+    pub fn resolve(&self, holds: &dyn Fn(&IndicatorConfig) -> bool) -> u8 {
         match self {
+            LcdColor::Bound(binding) => {
+                if holds(&binding.when) {
+                    binding.then.resolve(holds)
+                } else {
+                    binding.otherwise.as_ref().map_or(0, |c| c.resolve(holds))
+                }
+            },
             LcdColor::Numeric(n) => (*n as u8).min(7),
             LcdColor::Named(name) => match name.to_lowercase().as_str() {
                 "black" | "off" => 0,
@@ -460,6 +1101,21 @@ impl LcdColor {
             },
         }
     }
+
+    /// Whether the color depends on `signal`.
+    pub fn reads_signal(&self, signal: &str) -> bool {
+        match self {
+            LcdColor::Bound(binding) => {
+                binding.when.reads_signal(signal)
+                    || binding.then.reads_signal(signal)
+                    || binding
+                        .otherwise
+                        .as_ref()
+                        .is_some_and(|c| c.reads_signal(signal))
+            },
+            _ => false,
+        }
+    }
 }
 
 /// MIDI passthrough configuration
@@ -525,6 +1181,11 @@ impl AppConfig {
             .unwrap_or(true)
     }
 
+    /// The configured control surface (X-Touch by default).
+    pub fn device(&self) -> DeviceProfile {
+        self.xtouch.as_ref().map(|x| x.device).unwrap_or_default()
+    }
+
     /// Collect every app name referenced by control mappings or
     /// passthrough configs on any page (including `pages_global`). Used
     /// to decide which drivers a profile requires.
@@ -607,19 +1268,40 @@ impl AppConfig {
             || global.passthroughs.as_ref().is_some_and(|pts| any_pt(pts))
     }
 
-    /// Load configuration from file with validation
-    pub async fn load(path: &str) -> Result<Self> {
+    /// Load configuration from file with validation, also returning every
+    /// file the config was read from: `path` then its [`includes`] fragments.
+    pub async fn load_bundle(path: &str) -> Result<(Self, Vec<PathBuf>)> {
         let contents = fs::read_to_string(path)
             .await
             .with_context(|| format!("Failed to read config file: {}", path))?;
 
-        let config: AppConfig = serde_yaml::from_str(&contents)
+        let mut value: serde_yaml::Value = serde_yaml::from_str(&contents)
             .with_context(|| format!("Failed to parse YAML config: {}", path))?;
+        let files = if includes::include_paths(&value)?.is_empty() {
+            vec![PathBuf::from(path)]
+        } else {
+            let bundle = includes::resolve(value, Path::new(path), None)
+                .with_context(|| format!("Failed to resolve includes of {}", path))?;
+            value = bundle.value;
+            bundle.files
+        };
+        let ranges = control_ranges::expand(&mut value)
+            .with_context(|| format!("Failed to expand control ranges: {}", path))?;
+
+        let mut config: AppConfig = if files.len() > 1 || ranges {
+            serde_yaml::from_value(value)
+                .with_context(|| format!("Invalid config once expanded: {}", path))?
+        } else {
+            // Parse the text itself so errors keep their line numbers
+            serde_yaml::from_str(&contents)
+                .with_context(|| format!("Failed to parse YAML config: {}", path))?
+        };
 
-        // Validate the loaded configuration
+        // Expand `template` / `extends` pages, then validate the result
+        config.expand_pages()?;
         config.validate()?;
 
-        Ok(config)
+        Ok((config, files))
     }
 
     /// Save configuration to file. Currently uncalled; kept as the
@@ -683,10 +1365,15 @@ impl AppConfig {
             anyhow::bail!("At least one page must be defined");
         }
 
+        let mut page_names = std::collections::HashSet::new();
         for (page_idx, page) in self.pages.iter().enumerate() {
             if page.name.is_empty() {
                 anyhow::bail!("Page {} name cannot be empty", page_idx);
             }
+            // An `extends` page without its own name inherits its base's.
+            if !page_names.insert(page.name.as_str()) {
+                anyhow::bail!("Page {} duplicates page name '{}'", page_idx, page.name);
+            }
 
             // Validate controls in this page
             if let Some(controls) = &page.controls {
@@ -698,22 +1385,29 @@ impl AppConfig {
                 }
             }
 
+            if let Some(bank) = &page.bank {
+                let strips = self.device().strips();
+                if bank.channels <= strips {
+                    anyhow::bail!(
+                        "Page '{}': bank.channels must be greater than {}, got {}",
+                        page.name,
+                        strips,
+                        bank.channels
+                    );
+                }
+            }
+
             // Validate LCD colors (should be 0-7 for X-Touch)
             if let Some(lcd) = &page.lcd {
                 if let Some(colors) = &lcd.colors {
                     for (idx, color) in colors.iter().enumerate() {
-                        if let LcdColor::Numeric(num) = color {
-                            if *num > 7 {
-                                anyhow::bail!(
-                                    "LCD color {} in page '{}' strip {} is invalid (must be 0-7)",
-                                    num,
-                                    page.name,
-                                    idx
-                                );
-                            }
-                        }
+                        validate_lcd_color(color).with_context(|| {
+                            format!("LCD color in page '{}' strip {} is invalid", page.name, idx)
+                        })?;
                     }
                 }
+                validate_lcd_layout(lcd)
+                    .with_context(|| format!("Invalid LCD in page '{}'", page.name))?;
             }
         }
 
@@ -727,44 +1421,67 @@ impl AppConfig {
             }
         }
 
-        // Validate winaudio pinned_apps slot range and uniqueness.
+        // Validate winaudio / pulseaudio pinned_apps slot range and uniqueness.
         if let Some(winaudio) = &self.winaudio {
-            let mut seen = std::collections::HashSet::new();
-            for pin in &winaudio.pinned_apps {
-                if !(1..=8).contains(&pin.fader) {
-                    anyhow::bail!(
-                        "winaudio.pinned_apps: fader slot {} for '{}' must be in 1..=8",
-                        pin.fader,
-                        pin.process_name
-                    );
-                }
-                if !seen.insert(pin.fader) {
-                    anyhow::bail!(
-                        "winaudio.pinned_apps: fader slot {} is pinned more than once",
-                        pin.fader
-                    );
-                }
-                if pin.process_name.trim().is_empty() {
-                    anyhow::bail!(
-                        "winaudio.pinned_apps: process_name cannot be empty (fader {})",
-                        pin.fader
-                    );
-                }
+            validate_pinned_apps("winaudio", &winaudio.pinned_apps)?;
+        }
+        if let Some(pulseaudio) = &self.pulseaudio {
+            validate_pinned_apps("pulseaudio", &pulseaudio.pinned_apps)?;
+        }
+
+        if let Some(strip) = self.mpris.as_ref().and_then(|m| m.now_playing_strip) {
+            if !(1..=8).contains(&strip) {
+                anyhow::bail!("mpris.now_playing_strip must be in 1..=8, got {}", strip);
+            }
+        }
+
+        if let Some(mmc) = &self.mmc {
+            validate_mmc(mmc)?;
+        }
+
+        if let Some(xtouch) = &self.xtouch {
+            if xtouch.mode == XTouchMode::Ctrl && !xtouch.device.has_ctrl_mode() {
+                anyhow::bail!(
+                    "xtouch.mode 'ctrl' is not available on device {:?}",
+                    xtouch.device
+                );
             }
         }
 
-        // Validate winaudio session-target params (e.g. `pinned:1`,
-        // `discovered:3`, `auto`) at config-load. Typos previously
-        // surfaced only when the user pressed the button (#38).
+        if let Some(idle) = self.xtouch.as_ref().and_then(|x| x.idle.as_ref()) {
+            if idle.timeout_s == 0 {
+                anyhow::bail!("xtouch.idle.timeout_s must be greater than 0");
+            }
+        }
+
+        if self.process.is_some() {
+            anyhow::bail!(
+                "`process` can't be set in a profile: move it to {} next to config.yaml",
+                ProcessConfig::FILE_NAME
+            );
+        }
+
+        if let Some(vars) = &self.vars {
+            validate_vars(vars)?;
+        }
+
+        if let Some(macros) = &self.macros {
+            self.validate_macros(macros, &midi_app_names)?;
+        }
+
+        // Validate winaudio / pulseaudio session-target params (e.g.
+        // `pinned:1`, `discovered:3`, `auto`) at config-load. Typos
+        // previously surfaced only when the user pressed the button (#38).
         self.validate_winaudio_session_targets()?;
 
         Ok(())
     }
 
     /// Iterate every page (and `pages_global`) for control mappings
-    /// bound to `app: "winaudio"` and a session-target action; parse
-    /// their first param via [`parse_session_target`]. Errors carry
-    /// `page` + `control_id` context so the user can fix the YAML.
+    /// bound to `app: "winaudio"` (or `"pulseaudio"`) and a session-target
+    /// action; parse their first param via [`parse_session_target`].
+    /// Errors carry `page` + `control_id` context so the user can fix the
+    /// YAML.
     fn validate_winaudio_session_targets(&self) -> Result<()> {
         for page in &self.pages {
             if let Some(controls) = page.controls.as_ref() {
@@ -800,12 +1517,90 @@ impl AppConfig {
             }
         }
 
+        if let Some(indicator) = &mapping.indicator {
+            validate_expression(indicator, true)
+                .with_context(|| format!("Control '{}' has an invalid `indicator`", control_id))?;
+        }
+
+        // Feedback fan-out: the source must be one of the targets.
+        if let Some(source) = &mapping.feedback_from {
+            if !mapping.targets().any(|(app, _, _)| app == source.as_str()) {
+                anyhow::bail!(
+                    "Control '{}' takes feedback from '{}', which is neither its app nor an `also` app",
+                    control_id,
+                    source
+                );
+            }
+        }
+        if mapping.link {
+            let apps: Vec<&str> = mapping.targets().map(|(app, _, _)| app).collect();
+            if apps.len() < 2 {
+                anyhow::bail!(
+                    "Control '{}' uses `link` without `also` targets",
+                    control_id
+                );
+            }
+            if (1..apps.len()).any(|i| apps[..i].contains(&apps[i])) {
+                anyhow::bail!(
+                    "Control '{}' uses `link` with several targets on the same app",
+                    control_id
+                );
+            }
+        }
+
+        // Pickup compares against the app's MIDI feedback state.
+        if mapping.pickup {
+            let (app, midi, _) = mapping.feedback_target();
+            if midi.is_none() {
+                anyhow::bail!(
+                    "Control '{}' uses `pickup`, but its feedback target '{}' has no `midi:` to read the app's value from",
+                    control_id,
+                    app
+                );
+            }
+        }
+
+        for (field, steps) in [
+            ("on_touch", &mapping.on_touch),
+            ("on_release", &mapping.on_release),
+        ] {
+            for (idx, step) in steps.iter().enumerate() {
+                self.validate_action_step(control_id, step, midi_app_names)
+                    .with_context(|| {
+                        format!("in `{}` step {} of control '{}'", field, idx, control_id)
+                    })?;
+            }
+        }
+
+        if let Some(encoder) = &mapping.encoder {
+            if !(encoder.step > 0.0 && encoder.step <= 1.0) {
+                anyhow::bail!(
+                    "Control '{}' has an encoder `step` of {} (must be in (0, 1])",
+                    control_id,
+                    encoder.step
+                );
+            }
+            if !(0.0..=1.0).contains(&encoder.initial) {
+                anyhow::bail!(
+                    "Control '{}' has an encoder `initial` of {} (must be in [0, 1])",
+                    control_id,
+                    encoder.initial
+                );
+            }
+        }
+
         // Feedback-driven toggle (source app, watched address, on/off steps).
         if let Some(toggle) = &mapping.toggle {
             self.validate_toggle(control_id, toggle, midi_app_names)?;
         }
 
-        Ok(())
+        // Control-level condition (gates primary + `also`, `else` otherwise).
+        self.validate_condition(
+            control_id,
+            mapping.when.as_ref(),
+            &mapping.else_steps,
+            midi_app_names,
+        )
     }
 
     /// Validate one dispatch step (the inline primary effect or one `also`
@@ -904,6 +1699,11 @@ impl AppConfig {
             }
         }
 
+        // Validate the value transform (ranges, curve parameters)
+        if let Some(value) = &step.value {
+            value.validate(&format!("Control '{}'", control_id))?;
+        }
+
         // Validate that action OR midi is specified (not both empty, unless passthrough)
         if step.action.is_none() && step.midi.is_none() {
             anyhow::bail!(
@@ -912,6 +1712,36 @@ impl AppConfig {
             );
         }
 
+        self.validate_condition(
+            control_id,
+            step.when.as_ref(),
+            &step.else_steps,
+            midi_app_names,
+        )
+    }
+
+    /// Validate a `when:` condition and its `else` steps (recursively).
+    /// An `else` without `when` would never run, and a condition without
+    /// any test is always false: both are configuration mistakes.
+    fn validate_condition(
+        &self,
+        control_id: &str,
+        when: Option<&IndicatorConfig>,
+        else_steps: &[ActionStep],
+        midi_app_names: &std::collections::HashSet<&String>,
+    ) -> Result<()> {
+        let Some(when) = when else {
+            if !else_steps.is_empty() {
+                anyhow::bail!("Control '{}' has `else` steps but no `when`", control_id);
+            }
+            return Ok(());
+        };
+        validate_expression(when, false)
+            .with_context(|| format!("Control '{}' has an invalid `when`", control_id))?;
+        for (idx, step) in else_steps.iter().enumerate() {
+            self.validate_action_step(control_id, step, midi_app_names)
+                .with_context(|| format!("in `else` step {} of control '{}'", idx, control_id))?;
+        }
         Ok(())
     }
 
@@ -967,6 +1797,73 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Validate the `macros` section: names (used in signal names), at least
+    /// one step, something to do in each step, and no endless loop without a
+    /// pause. Like toggle steps, macro steps run from a synthetic trigger, so
+    /// `midi` steps are rejected.
+    fn validate_macros(
+        &self,
+        macros: &HashMap<String, MacroConfig>,
+        midi_app_names: &std::collections::HashSet<&String>,
+    ) -> Result<()> {
+        for (name, macro_config) in macros {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                anyhow::bail!(
+                    "macros: invalid name '{}' (letters, digits, '_' and '-' only)",
+                    name
+                );
+            }
+            if macro_config.steps.is_empty() {
+                anyhow::bail!("macros.{} has no steps", name);
+            }
+            let context = format!("macros.{}", name);
+            for (idx, step) in macro_config.steps.iter().enumerate() {
+                if step.delay_ms.is_none() && step.wait_for.is_none() && step.action.is_none() {
+                    anyhow::bail!(
+                        "macros.{} step {} needs `app` + `action`, `delay_ms` or `wait_for`",
+                        name,
+                        idx
+                    );
+                }
+                if let Some(wait) = &step.wait_for {
+                    self.validate_condition(&context, Some(&wait.condition), &[], midi_app_names)
+                        .with_context(|| {
+                            format!("in `wait_for` of macros.{} step {}", name, idx)
+                        })?;
+                }
+                if let Some(action) = &step.action {
+                    if action.midi.is_some() {
+                        anyhow::bail!(
+                            "macros.{} step {} uses `midi`, which macros cannot dispatch \
+                             (the trigger message is synthetic)",
+                            name,
+                            idx
+                        );
+                    }
+                    self.validate_action_step(&context, action, midi_app_names)
+                        .with_context(|| format!("in macros.{} step {}", name, idx))?;
+                }
+            }
+            // A `wait_for` that already holds doesn't pause, so only a delay
+            // keeps an endless loop from spinning.
+            let pauses = macro_config
+                .steps
+                .iter()
+                .any(|step| step.delay_ms.is_some_and(|ms| ms > 0));
+            if macro_config.repeat == 0 && !pauses {
+                anyhow::bail!(
+                    "macros.{} loops forever (`repeat: 0`) without a `delay_ms` step",
+                    name
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Validate a `toggle.watch` MIDI address: the field actually required to
     /// match feedback (`note` for note, `cc` for cc), value ranges, and that the
     /// type is a watchable address (`passthrough` never matches).
@@ -1031,27 +1928,233 @@ impl AppConfig {
 
 /// Apps that don't need a MIDI `midi.apps` port entry — they're validated by
 /// name only. Shared by `validate_action_step` and `validate_toggle`.
-const NON_MIDI_APPS: &[&str] = &["obs", "winaudio", "winmedia"];
+const NON_MIDI_APPS: &[&str] = &[
+    "macro",
+    "mmc",
+    "mpris",
+    "obs",
+    "process",
+    "pulseaudio",
+    "vars",
+    "vmix",
+    "winaudio",
+    "winmedia",
+];
+
+/// Driver names that own audio session control (Windows and PulseAudio).
+/// Duplicated here (and kept in sync with `drivers::winaudio::DRIVER_NAME`
+/// / `drivers::pulseaudio::DRIVER_NAME`) so the lib crate can validate
+/// session-target YAML without depending on the bin crate's `drivers`
+/// module.
+pub(crate) const SESSION_DRIVER_NAMES: &[&str] = &["winaudio", "pulseaudio"];
+
+/// Actions on `app: "winaudio"` / `"pulseaudio"` that consume a session
+/// target as their first param. Used by
+/// `validate_winaudio_session_targets` so config-load rejects typos like
+/// `"pined:1"` early (#38).
+pub(crate) const WINAUDIO_SESSION_ACTIONS: &[&str] = &["session_volume", "session_mute"];
+
+/// Validate the `mmc` section: port, device ID and frame rate.
+fn validate_mmc(mmc: &MmcConfig) -> Result<()> {
+    if mmc.output_port.trim().is_empty() {
+        anyhow::bail!("mmc.output_port cannot be empty");
+    }
+    if mmc.device_id > 127 {
+        anyhow::bail!("mmc.device_id must be in 0..=127, got {}", mmc.device_id);
+    }
+    if ![24, 25, 30].contains(&mmc.fps) {
+        anyhow::bail!("mmc.fps must be 24, 25 or 30, got {}", mmc.fps);
+    }
+    if mmc.drop_frame && mmc.fps != 30 {
+        anyhow::bail!("mmc.drop_frame requires fps: 30");
+    }
+    if mmc.mtc == MtcMode::Receive && mmc.input_port.as_deref().unwrap_or("").trim().is_empty() {
+        anyhow::bail!("mmc.mtc: receive requires mmc.input_port");
+    }
+    Ok(())
+}
+
+/// Validate the `process` allowlist: command names (used in signal names),
+/// programs and `{N}` argument placeholders.
+fn validate_process(process: &ProcessConfig) -> Result<()> {
+    for (name, command) in &process.commands {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!(
+                "process.commands: invalid name '{}' (letters, digits, '_' and '-' only)",
+                name
+            );
+        }
+        if command.program.trim().is_empty() {
+            anyhow::bail!("process.commands.{}.program cannot be empty", name);
+        }
+        for arg in &command.args {
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find('{') {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                // Only `{N}` is a placeholder; other braces are literal.
+                let placeholder = &rest[start + 1..start + len];
+                let numeric =
+                    !placeholder.is_empty() && placeholder.bytes().all(|b| b.is_ascii_digit());
+                if numeric && !matches!(placeholder.parse::<u8>(), Ok(1..=9)) {
+                    anyhow::bail!(
+                        "process.commands.{}.args: placeholder '{{{}}}' in '{}' out of range (use {{1}}..{{9}})",
+                        name,
+                        placeholder,
+                        arg
+                    );
+                }
+                rest = &rest[start + len + 1..];
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate the `vars` section: variable names (used in signal names).
+fn validate_vars(vars: &VarsConfig) -> Result<()> {
+    for name in vars.variables.keys() {
+        if !is_valid_var_name(name) {
+            anyhow::bail!(
+                "vars.variables: invalid name '{}' (letters, digits, '_' and '-' only)",
+                name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Variable names end up in `vars.<name>` signal names: keep them to
+/// letters, digits, `_` and `-`. Shared with the `vars` driver, which
+/// creates undeclared variables at runtime.
+pub fn is_valid_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
 
-/// Driver name that owns Windows audio session control. Duplicated here
-/// (and kept in sync with `drivers::winaudio::DRIVER_NAME`) so the lib
-/// crate can validate session-target YAML without depending on the bin
-/// crate's `drivers` module.
-const WINAUDIO_DRIVER_NAME: &str = "winaudio";
+/// Validate scrolling and merged areas: strips 1-8, areas not overlapping.
+fn validate_lcd_layout(lcd: &LcdConfig) -> Result<()> {
+    if lcd.scroll.as_ref().is_some_and(|s| s.interval_ms == 0) {
+        anyhow::bail!("scroll.interval_ms must be greater than 0");
+    }
+    let mut taken = [false; 8];
+    for area in lcd.merged.iter().flatten() {
+        if !(1 <= area.from && area.from <= area.to && area.to <= 8) {
+            anyhow::bail!(
+                "merged strips {}-{} must be in 1-8, first to last",
+                area.from,
+                area.to
+            );
+        }
+        for strip in area.from..=area.to {
+            if std::mem::replace(&mut taken[usize::from(strip - 1)], true) {
+                anyhow::bail!("strip {} is in two merged areas", strip);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate an LCD color: 0-7 when numeric, a valid condition and branches
+/// when bound.
+fn validate_lcd_color(color: &LcdColor) -> Result<()> {
+    match color {
+        LcdColor::Numeric(num) if *num > 7 => {
+            anyhow::bail!("color {} must be 0-7", num)
+        },
+        LcdColor::Bound(binding) => {
+            validate_expression(&binding.when, false)?;
+            validate_lcd_color(&binding.then)?;
+            binding
+                .otherwise
+                .as_ref()
+                .map_or(Ok(()), validate_lcd_color)
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Validate a condition tree (`indicator`, `when`, `wait_for`): every node
+/// tests a signal or combines sub-conditions, ranges are ordered, and
+/// `blink` only appears at the top of an indicator.
+fn validate_expression(cond: &IndicatorConfig, is_indicator: bool) -> Result<()> {
+    if cond.signal.trim().is_empty() {
+        if cond.has_value_test() {
+            anyhow::bail!("value tests need a `signal`");
+        }
+        // An indicator may only blink (its solid condition left out).
+        if !(cond.is_compound() || is_indicator && cond.blink.is_some()) {
+            anyhow::bail!("condition needs a `signal` or one of `all`, `any` or `not`");
+        }
+    } else if !cond.has_value_test() {
+        anyhow::bail!(
+            "condition on '{}' needs one of `equals`, `truthy`, `in`, `gt`, `lt` or `between`",
+            cond.signal
+        );
+    }
+    if let Some([min, max]) = cond.between {
+        if min > max {
+            anyhow::bail!("`between` needs [min, max], got [{}, {}]", min, max);
+        }
+    }
+    if cond.blink.is_some() && !is_indicator {
+        anyhow::bail!("`blink` only applies to an indicator");
+    }
+
+    let nested = cond.all.iter().chain(&cond.any).chain(cond.not.as_deref());
+    for sub in nested.chain(cond.blink.as_deref()) {
+        validate_expression(sub, false)?;
+    }
+    Ok(())
+}
 
-/// Actions on `app: "winaudio"` that consume a session target as their
-/// first param. Used by `validate_winaudio_session_targets` so config-load
-/// rejects typos like `"pined:1"` early (#38).
-const WINAUDIO_SESSION_ACTIONS: &[&str] = &["session_volume", "session_mute"];
+/// Validate the slot range, uniqueness and process name of a driver's
+/// `pinned_apps`. `section` is the YAML key used in error messages.
+fn validate_pinned_apps(section: &str, pinned_apps: &[PinnedApp]) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    for pin in pinned_apps {
+        if !(1..=8).contains(&pin.fader) {
+            anyhow::bail!(
+                "{}.pinned_apps: fader slot {} for '{}' must be in 1..=8",
+                section,
+                pin.fader,
+                pin.process_name
+            );
+        }
+        if !seen.insert(pin.fader) {
+            anyhow::bail!(
+                "{}.pinned_apps: fader slot {} is pinned more than once",
+                section,
+                pin.fader
+            );
+        }
+        if pin.process_name.trim().is_empty() {
+            anyhow::bail!(
+                "{}.pinned_apps: process_name cannot be empty (fader {})",
+                section,
+                pin.fader
+            );
+        }
+    }
+    Ok(())
+}
 
-/// Validate the session-target param of every winaudio session-action
-/// mapping in `controls`. Errors carry the page/control context.
+/// Validate the session-target param of every winaudio / pulseaudio
+/// session-action mapping in `controls`. Errors carry the page/control
+/// context.
 fn validate_winaudio_controls_in(
     controls: &HashMap<String, ControlMapping>,
     page_name: &str,
 ) -> Result<()> {
     for (control_id, mapping) in controls {
-        if mapping.app != WINAUDIO_DRIVER_NAME {
+        if !SESSION_DRIVER_NAMES.contains(&mapping.app.as_str()) {
             continue;
         }
         let Some(action) = mapping.action.as_deref() else {
@@ -1063,9 +2166,10 @@ fn validate_winaudio_controls_in(
         let params = mapping.params.as_deref().unwrap_or(&[]);
         parse_winaudio_session_target_str(params).map_err(|e| {
             anyhow::anyhow!(
-                "page '{}' control '{}' (winaudio.{}): {}",
+                "page '{}' control '{}' ({}.{}): {}",
                 page_name,
                 control_id,
+                mapping.app,
                 action,
                 e
             )
@@ -1133,6 +2237,24 @@ fn default_obs_host() -> String {
 fn default_obs_port() -> u16 {
     4455
 }
+fn default_vmix_host() -> String {
+    "localhost".to_string()
+}
+fn default_vmix_port() -> u16 {
+    8099
+}
+fn default_mmc_device_id() -> u8 {
+    127
+}
+fn default_mtc_fps() -> u8 {
+    25
+}
+fn default_process_timeout_ms() -> u64 {
+    30_000
+}
+fn default_lcd_scroll_interval() -> u64 {
+    400
+}
 fn default_xtouch_mode() -> XTouchMode {
     XTouchMode::Mcu
 }
@@ -1272,8 +2394,20 @@ mod tests {
                     action: Some("x".into()),
                     params: None,
                     midi: None,
+                    value: None,
+                    when: None,
+                    else_steps: Vec::new(),
                 }]),
                 toggle: None,
+                value: None,
+                when: None,
+                else_steps: Vec::new(),
+                feedback_from: None,
+                link: false,
+                encoder: None,
+                on_touch: Vec::new(),
+                on_release: Vec::new(),
+                pickup: false,
             },
         );
         cfg.pages.push(PageConfig {
@@ -1322,9 +2456,21 @@ mod tests {
                             cc: None,
                             note: Some(60),
                         }),
+                        value: None,
+                        when: None,
+                        else_steps: Vec::new(),
                     }],
                     off: vec![],
                 }),
+                value: None,
+                when: None,
+                else_steps: Vec::new(),
+                feedback_from: None,
+                link: false,
+                encoder: None,
+                on_touch: Vec::new(),
+                on_release: Vec::new(),
+                pickup: false,
             },
         );
         cfg.pages.push(PageConfig {
@@ -1356,11 +2502,25 @@ mod tests {
             gamepad: None,
             pages_global: None,
             winaudio: None,
+            pulseaudio: None,
+            vmix: None,
+            mpris: None,
+            mmc: None,
+            process: None,
+            vars: None,
+            macros: None,
+            page_templates: None,
             pages: vec![],
             tray: None,
         }
     }
 
+    fn config_with_page(page: PageConfig) -> AppConfig {
+        let mut cfg = empty_config();
+        cfg.pages.push(page);
+        cfg
+    }
+
     fn control(app: &str) -> ControlMapping {
         ControlMapping {
             app: app.to_string(),
@@ -1371,6 +2531,15 @@ mod tests {
             overlay: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         }
     }
 
@@ -1473,6 +2642,15 @@ mod tests {
             overlay: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         }
     }
 
@@ -1555,6 +2733,15 @@ mod tests {
                 overlay: None,
                 also: None,
                 toggle: None,
+                value: None,
+                when: None,
+                else_steps: Vec::new(),
+                feedback_from: None,
+                link: false,
+                encoder: None,
+                on_touch: Vec::new(),
+                on_release: Vec::new(),
+                pickup: false,
             },
         );
         cfg.midi.apps = Some(vec![MidiAppConfig {
@@ -1569,4 +2756,400 @@ mod tests {
         });
         cfg.validate().expect("non-winaudio params not validated");
     }
+
+    #[test]
+    fn validate_mpris_now_playing_strip_range() {
+        let mut cfg = cfg_with_winaudio_control("master_volume", serde_json::json!(null));
+        for (strip, ok) in [(1, true), (8, true), (0, false), (9, false)] {
+            cfg.mpris = Some(MprisConfig {
+                preferred_players: vec![],
+                now_playing_strip: Some(strip),
+            });
+            assert_eq!(cfg.validate().is_ok(), ok, "strip {strip}");
+        }
+    }
+
+    #[test]
+    fn validate_mmc_section() {
+        let mut cfg = cfg_with_winaudio_control("master_volume", serde_json::json!(null));
+        let yaml = "output_port: DAW\nmtc: generate\nfps: 30\ndrop_frame: true\n";
+        let mmc: MmcConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(mmc.device_id, 127);
+        assert!(mmc.display);
+        cfg.mmc = Some(mmc.clone());
+        cfg.validate().expect("valid mmc section");
+
+        for bad in [
+            MmcConfig {
+                fps: 29,
+                drop_frame: false,
+                ..mmc.clone()
+            },
+            MmcConfig {
+                fps: 25,
+                ..mmc.clone()
+            },
+            MmcConfig {
+                mtc: MtcMode::Receive,
+                input_port: None,
+                ..mmc.clone()
+            },
+            MmcConfig {
+                output_port: " ".into(),
+                ..mmc.clone()
+            },
+        ] {
+            cfg.mmc = Some(bad.clone());
+            assert!(cfg.validate().is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn validate_process_allowlist() {
+        let yaml = "commands:\n  capture:\n    program: obs-capture\n    args: [\"--scene={1}\", \"{json}\"]\n";
+        let process: ProcessConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(process.commands["capture"].timeout_ms, 30_000);
+        validate_process(&process).expect("valid allowlist");
+
+        // Never read from a profile
+        let mut cfg = cfg_with_winaudio_control("master_volume", serde_json::json!(null));
+        cfg.process = Some(serde_yaml::from_str(yaml).unwrap());
+        assert!(cfg.validate().is_err());
+
+        let base = process.commands["capture"].clone();
+        for (name, command) in [
+            ("cap.ture", base.clone()),
+            (
+                "capture",
+                ProcessCommandConfig {
+                    program: "".into(),
+                    ..base.clone()
+                },
+            ),
+            (
+                "capture",
+                ProcessCommandConfig {
+                    args: vec!["{0}".into()],
+                    ..base.clone()
+                },
+            ),
+        ] {
+            let process = ProcessConfig {
+                commands: HashMap::from([(name.to_string(), command)]),
+            };
+            assert!(
+                validate_process(&process).is_err(),
+                "{name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_when_else() {
+        let yaml = "app: obs\naction: TriggerStudioModeTransition\nwhen: {signal: obs.studioMode, truthy: true}\nelse:\n  - {app: obs, action: changeScene, params: [Live]}\n";
+        let mapping: ControlMapping = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(mapping.else_steps.len(), 1);
+        let with_mapping = |mapping: ControlMapping| {
+            config_with_page(PageConfig {
+                name: "Studio".into(),
+                controls: Some(HashMap::from([("f1".to_string(), mapping)])),
+                ..PageConfig::default()
+            })
+        };
+        with_mapping(mapping.clone())
+            .validate()
+            .expect("valid when/else");
+
+        let mut no_when = mapping.clone();
+        no_when.when = None;
+        assert!(
+            with_mapping(no_when).validate().is_err(),
+            "else without when"
+        );
+
+        let mut no_test = mapping;
+        no_test.when.as_mut().unwrap().truthy = None;
+        assert!(
+            with_mapping(no_test).validate().is_err(),
+            "when without test"
+        );
+    }
+
+    #[test]
+    fn validate_macros() {
+        let with_macros = |yaml: &str| {
+            let mut cfg = config_with_page(PageConfig {
+                name: "Main".into(),
+                ..PageConfig::default()
+            });
+            cfg.macros = Some(serde_yaml::from_str(yaml).unwrap());
+            cfg
+        };
+        let valid = "intro:\n  repeat: 0\n  steps:\n    - {app: obs, action: changeScene, params: [Intro]}\n    - delay_ms: 500\n    - wait_for: {signal: obs.streaming, truthy: true, timeout_ms: 5000}\n      app: obs\n      action: changeScene\n      params: [Live]\n";
+        let cfg = with_macros(valid);
+        let steps = &cfg.macros.as_ref().unwrap()["intro"].steps;
+        assert_eq!(steps[2].wait_for.as_ref().unwrap().timeout_ms, Some(5000));
+        assert_eq!(steps[2].action.as_ref().unwrap().app, "obs");
+        assert!(steps[1].action.is_none());
+        cfg.validate().expect("valid macro");
+
+        for (yaml, why) in [
+            ("bad.name:\n  steps: [{delay_ms: 1}]\n", "name"),
+            ("m:\n  steps: []\n", "no steps"),
+            ("m:\n  steps: [{ap: obs, action: x}]\n", "empty step"),
+            (
+                "m:\n  steps: [{wait_for: {signal: obs.x}}]\n",
+                "wait without test",
+            ),
+            (
+                "m:\n  repeat: 0\n  steps: [{app: obs, action: x}]\n",
+                "endless loop without pause",
+            ),
+            (
+                "m:\n  repeat: 0\n  steps: [{wait_for: {signal: obs.x, truthy: true}, app: obs, action: x}]\n",
+                "endless loop only waiting on a signal",
+            ),
+        ] {
+            assert!(with_macros(yaml).validate().is_err(), "{}", why);
+        }
+    }
+
+    #[test]
+    fn validate_pickup() {
+        let with_fader = |yaml: &str| {
+            let mut cfg = config_with_page(PageConfig {
+                name: "Mix".into(),
+                controls: Some(HashMap::from([(
+                    "fader1".to_string(),
+                    serde_yaml::from_str(yaml).unwrap(),
+                )])),
+                ..PageConfig::default()
+            });
+            cfg.midi.apps = Some(vec![MidiAppConfig {
+                name: "voicemeeter".into(),
+                output_port: Some("vm-out".into()),
+                input_port: Some("vm-in".into()),
+            }]);
+            cfg
+        };
+        with_fader("app: voicemeeter\nmidi: {type: cc, channel: 1, cc: 0}\npickup: true\n")
+            .validate()
+            .expect("pickup on a MIDI target");
+        assert!(with_fader("app: obs\naction: setVolume\npickup: true\n")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn validate_feedback_fanout() {
+        let with_fader = |yaml: &str| {
+            config_with_page(PageConfig {
+                name: "Mix".into(),
+                controls: Some(HashMap::from([(
+                    "fader1".to_string(),
+                    serde_yaml::from_str(yaml).unwrap(),
+                )])),
+                ..PageConfig::default()
+            })
+        };
+        let fader =
+            "app: obs\naction: setVolume\nalso:\n  - {app: winaudio, action: master_volume}\n";
+        let cfg = with_fader(&format!("{}feedback_from: winaudio\nlink: true\n", fader));
+        cfg.validate().expect("valid fan-out");
+        let mapping = &cfg.pages[0].controls.as_ref().unwrap()["fader1"];
+        assert_eq!(mapping.feedback_target().0, "winaudio");
+        assert_eq!(mapping.targets().count(), 2);
+
+        for (yaml, why) in [
+            (format!("{}feedback_from: qlc\n", fader), "unknown source"),
+            (
+                "app: obs\naction: setVolume\nlink: true\n".to_string(),
+                "link without also",
+            ),
+            (
+                format!("{}  - {{app: obs, action: other}}\nlink: true\n", fader),
+                "link with two targets on one app",
+            ),
+        ] {
+            assert!(with_fader(&yaml).validate().is_err(), "{}", why);
+        }
+    }
+
+    #[test]
+    fn validate_encoder() {
+        let with_vpot = |yaml: &str| {
+            config_with_page(PageConfig {
+                name: "Mix".into(),
+                controls: Some(HashMap::from([(
+                    "vpot1_rotate".to_string(),
+                    serde_yaml::from_str(yaml).unwrap(),
+                )])),
+                ..PageConfig::default()
+            })
+        };
+        let cfg = with_vpot(
+            "app: obs
+action: setPan
+encoder: {ring: spread}
+",
+        );
+        cfg.validate().expect("valid encoder");
+        let encoder = cfg.pages[0].controls.as_ref().unwrap()["vpot1_rotate"]
+            .encoder
+            .clone()
+            .unwrap();
+        assert_eq!(encoder.ring, RingMode::Spread);
+        assert_eq!(encoder.step, 0.02);
+
+        for yaml in [
+            "app: obs
+action: setPan
+encoder: {step: 0}
+",
+            "app: obs
+action: setPan
+encoder: {initial: 1.5}
+",
+        ] {
+            assert!(with_vpot(yaml).validate().is_err(), "{}", yaml);
+        }
+    }
+
+    #[test]
+    fn validate_indicator_expressions() {
+        let with_indicator = |yaml: &str| {
+            let mut mapping: ControlMapping =
+                serde_yaml::from_str("app: obs\naction: toggleStudioMode\n").unwrap();
+            mapping.indicator = Some(serde_yaml::from_str(yaml).unwrap());
+            config_with_page(PageConfig {
+                name: "Live".into(),
+                controls: Some(HashMap::from([("play".to_string(), mapping)])),
+                ..PageConfig::default()
+            })
+        };
+        with_indicator(
+            "signal: obs.streaming\ntruthy: true\nblink: {any: [{signal: obs.transition, truthy: true}, {signal: vars.level, gt: 3}]}\n",
+        )
+        .validate()
+        .expect("valid compound indicator");
+        with_indicator("not: {signal: mic.level, between: [-20, 0]}\n")
+            .validate()
+            .expect("signal-less compound indicator");
+
+        for (yaml, why) in [
+            ("equals: 1\n", "test without signal"),
+            ("signal: obs.streaming\n", "signal without test"),
+            ("all: [{signal: a}]\n", "nested signal without test"),
+            ("signal: a\nbetween: [5, 1]\n", "reversed range"),
+            (
+                "signal: a\ntruthy: true\nblink: {signal: b, truthy: true, blink: {signal: c, truthy: true}}\n",
+                "nested blink",
+            ),
+        ] {
+            assert!(with_indicator(yaml).validate().is_err(), "{}", why);
+        }
+
+        // `blink` is an LED state, not a condition for `when`.
+        let mut cfg = with_indicator("signal: a\ntruthy: true\n");
+        let play = cfg.pages[0]
+            .controls
+            .as_mut()
+            .unwrap()
+            .get_mut("play")
+            .unwrap();
+        play.when = Some(
+            serde_yaml::from_str("signal: a\ntruthy: true\nblink: {signal: b, truthy: true}\n")
+                .unwrap(),
+        );
+        assert!(cfg.validate().is_err(), "blink in when");
+    }
+
+    #[test]
+    fn validate_bound_lcd_colors() {
+        let with_colors = |yaml: &str| {
+            config_with_page(PageConfig {
+                name: "Live".into(),
+                lcd: Some(serde_yaml::from_str(yaml).unwrap()),
+                ..PageConfig::default()
+            })
+        };
+        let cfg = with_colors("colors: [{signal: obs.scene, equals: Cour, then: red}, 2]\n");
+        cfg.validate().expect("valid bound color");
+        let color = &cfg.pages[0].lcd.as_ref().unwrap().colors.as_ref().unwrap()[0];
+        assert!(color.reads_signal("obs.scene"));
+        assert_eq!(color.resolve(&|_| true), 1);
+        assert_eq!(color.to_u8(), 0);
+
+        for (yaml, why) in [
+            (
+                "colors: [{signal: obs.scene, equals: Cour, then: 9}]\n",
+                "then out of range",
+            ),
+            (
+                "colors: [{signal: obs.scene, then: red}]\n",
+                "condition without test",
+            ),
+            ("colors: [8]\n", "numeric out of range"),
+        ] {
+            assert!(with_colors(yaml).validate().is_err(), "{}", why);
+        }
+    }
+
+    #[test]
+    fn validate_bank() {
+        let with_bank = |channels: u8| {
+            config_with_page(PageConfig {
+                name: "Mix".into(),
+                bank: Some(BankConfig { channels }),
+                ..PageConfig::default()
+            })
+        };
+        with_bank(24).validate().expect("valid bank");
+        assert!(with_bank(8).validate().is_err());
+    }
+
+    #[test]
+    fn validate_lcd_layout() {
+        let with_lcd = |yaml: &str| {
+            config_with_page(PageConfig {
+                name: "Now playing".into(),
+                lcd: Some(serde_yaml::from_str(yaml).unwrap()),
+                ..PageConfig::default()
+            })
+        };
+        with_lcd("scroll: { mode: segments }\nmerged: [{ from: 1, to: 4, upper: x }]\n")
+            .validate()
+            .expect("valid layout");
+        assert!(with_lcd("scroll: { interval_ms: 0 }\n").validate().is_err());
+        assert!(with_lcd("merged: [{ from: 3, to: 9 }]\n")
+            .validate()
+            .is_err());
+        assert!(
+            with_lcd("merged: [{ from: 1, to: 4 }, { from: 4, to: 5 }]\n")
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn validate_device() {
+        let with_xtouch = |yaml: &str| {
+            let mut cfg = config_with_page(PageConfig {
+                name: "Main".into(),
+                ..PageConfig::default()
+            });
+            cfg.xtouch = Some(serde_yaml::from_str(yaml).unwrap());
+            cfg
+        };
+        let cfg = with_xtouch("device: xtouch_one\n");
+        cfg.validate().expect("valid device");
+        assert_eq!(cfg.device(), DeviceProfile::XtouchOne);
+        assert_eq!(empty_config().device(), DeviceProfile::Xtouch);
+        with_xtouch("mode: ctrl\n")
+            .validate()
+            .expect("ctrl on X-Touch");
+        assert!(with_xtouch("device: platform_m\nmode: ctrl\n")
+            .validate()
+            .is_err());
+    }
 }
@@ -0,0 +1,318 @@
+//! Page templates and inheritance, expanded at load time.
+//!
+//! - `extends: <page name>`: start from a copy of another page, then apply
+//!   this page's own fields on top: `controls` per control id, `lcd` and
+//!   passthroughs when set. The page needs its own `name`: page names are
+//!   unique.
+//! - `template: <name>` + `with: { param: value }`: instantiate an entry of
+//!   `page_templates`, substituting `${param}` in every string of the
+//!   template (page name, labels, params, ...). The template's own `with`
+//!   gives defaults; this page's fields apply on top like `extends`.
+//!
+//! A string that is exactly one `${param}` takes the parameter's YAML type
+//! (number, bool, ...); inside a longer string it is formatted as text.
+//! Placeholders that aren't template parameters (`${value}`, signals) are
+//! left for dispatch-time interpolation.
+//!
+//! Expanded pages carry no `extends` / `template` / `with`, so the router,
+//! validation and the editor API all see plain pages.
+
+use super::{AppConfig, PageConfig};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl AppConfig {
+    /// Expand `template` then `extends` pages in place. Returns whether
+    /// any page was expanded.
+    pub fn expand_pages(&mut self) -> Result<bool> {
+        let uses_templates = self
+            .pages
+            .iter()
+            .any(|page| page.template.is_some() || page.extends.is_some());
+        if !uses_templates {
+            return Ok(false);
+        }
+
+        let templates = self.page_templates.clone().unwrap_or_default();
+        let mut pages = Vec::with_capacity(self.pages.len());
+        for (idx, page) in self.pages.drain(..).enumerate() {
+            let page = match &page.template {
+                Some(_) => instantiate(&templates, page)
+                    .with_context(|| format!("Failed to expand template of page {}", idx))?,
+                None => page,
+            };
+            pages.push(page);
+        }
+
+        let mut resolved: Vec<Option<PageConfig>> = vec![None; pages.len()];
+        for idx in 0..pages.len() {
+            resolve_extends(&pages, idx, &mut resolved, &mut Vec::new())?;
+        }
+        self.pages = resolved.into_iter().flatten().collect();
+        Ok(true)
+    }
+}
+
+/// Instantiate `page.template` with the merged parameters, then apply
+/// `page` on top.
+fn instantiate(templates: &HashMap<String, PageConfig>, page: PageConfig) -> Result<PageConfig> {
+    let name = page.template.as_deref().unwrap_or_default();
+    if page.extends.is_some() {
+        bail!("page uses both `template` and `extends`");
+    }
+    let template = templates
+        .get(name)
+        .ok_or_else(|| anyhow!("unknown page template '{}'", name))?;
+    if template.template.is_some() || template.extends.is_some() {
+        bail!(
+            "page template '{}' cannot use `template` or `extends`",
+            name
+        );
+    }
+
+    let mut params = template.with.clone();
+    params.extend(page.with.clone());
+    let value = serde_json::to_value(template)
+        .with_context(|| format!("Failed to serialize page template '{}'", name))?;
+    let mut expanded: PageConfig = serde_json::from_value(substitute(value, &params))
+        .with_context(|| format!("Page template '{}' is invalid once expanded", name))?;
+    expanded.with.clear();
+    Ok(overlay(expanded, page))
+}
+
+/// Resolve the `extends` chain of page `idx` (memoized in `resolved`).
+/// `chain` holds the pages being resolved, to report cycles.
+fn resolve_extends(
+    pages: &[PageConfig],
+    idx: usize,
+    resolved: &mut [Option<PageConfig>],
+    chain: &mut Vec<usize>,
+) -> Result<PageConfig> {
+    if let Some(page) = &resolved[idx] {
+        return Ok(page.clone());
+    }
+    let page = &pages[idx];
+    let Some(base_name) = &page.extends else {
+        resolved[idx] = Some(page.clone());
+        return Ok(page.clone());
+    };
+    if chain.contains(&idx) {
+        let names: Vec<&str> = chain.iter().map(|&i| pages[i].name.as_str()).collect();
+        bail!(
+            "pages extend each other in a cycle: {} -> {}",
+            names.join(" -> "),
+            page.name
+        );
+    }
+    let base_idx = pages
+        .iter()
+        .position(|p| &p.name == base_name)
+        .ok_or_else(|| anyhow!("page '{}' extends unknown page '{}'", page.name, base_name))?;
+
+    chain.push(idx);
+    let base = resolve_extends(pages, base_idx, resolved, chain)?;
+    chain.pop();
+
+    let expanded = overlay(base, page.clone());
+    resolved[idx] = Some(expanded.clone());
+    Ok(expanded)
+}
+
+/// `page`'s own fields on top of `base`. Controls merge per control id;
+/// the other fields replace the base's when set.
+fn overlay(base: PageConfig, page: PageConfig) -> PageConfig {
+    let controls = match (base.controls, page.controls) {
+        (Some(mut controls), Some(own)) => {
+            controls.extend(own);
+            Some(controls)
+        },
+        (base, own) => own.or(base),
+    };
+    PageConfig {
+        name: if page.name.is_empty() {
+            base.name
+        } else {
+            page.name
+        },
+        controls,
+        lcd: page.lcd.or(base.lcd),
+        passthrough: page.passthrough.or(base.passthrough),
+        passthroughs: page.passthroughs.or(base.passthroughs),
+        bank: page.bank.or(base.bank),
+        extends: None,
+        template: None,
+        with: HashMap::new(),
+    }
+}
+
+/// Substitute template parameters in every string of `value`.
+fn substitute(value: Value, params: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(s) => substitute_str(s, params),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| substitute(item, params))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, substitute(v, params)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn substitute_str(s: String, params: &HashMap<String, Value>) -> Value {
+    if !s.contains("${") {
+        return Value::String(s);
+    }
+    // Whole-string parameter: keep its type.
+    if let Some(name) = s.strip_prefix("${").and_then(|r| r.strip_suffix('}')) {
+        if let Some(value) = params.get(name.trim()) {
+            if !name.contains(['{', '}']) {
+                return value.clone();
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s.as_str();
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let end = start + 2 + len + 1;
+        match params.get(rest[start + 2..end - 1].trim()) {
+            Some(value) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&to_text(value));
+            },
+            // Not a template parameter: keep for runtime interpolation
+            None => out.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    Value::String(out)
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> AppConfig {
+        let yaml = format!("midi: {{input_port: in, output_port: out}}\n{}", yaml);
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn template_pages_substitute_parameters() {
+        let mut cfg = config(
+            r#"
+page_templates:
+  cam_page:
+    name: "Cam ${camera}"
+    with: { input: 1 }
+    lcd: { labels: ["${camera}", "${obs.scene}"] }
+    controls:
+      f1: { app: obs, action: changeScene, params: ["Cam ${camera}", "${input}", "${value}"] }
+pages:
+  - template: cam_page
+    with: { camera: Jardin }
+  - template: cam_page
+    with: { camera: Cour, input: 2 }
+    controls:
+      f2: { app: obs, action: toggleStudioMode }
+"#,
+        );
+        assert!(cfg.expand_pages().unwrap());
+
+        let names: Vec<&str> = cfg.pages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Cam Jardin", "Cam Cour"]);
+        let f1 = &cfg.pages[1].controls.as_ref().unwrap()["f1"];
+        assert_eq!(
+            f1.params.as_ref().unwrap(),
+            &vec![
+                Value::from("Cam Cour"),
+                Value::from(2),
+                Value::from("${value}")
+            ]
+        );
+        assert!(cfg.pages[1].controls.as_ref().unwrap().contains_key("f2"));
+        assert!(!cfg.pages[0].controls.as_ref().unwrap().contains_key("f2"));
+        assert!(cfg
+            .pages
+            .iter()
+            .all(|p| p.template.is_none() && p.with.is_empty()));
+        cfg.validate().expect("expanded config is valid");
+    }
+
+    #[test]
+    fn extends_merges_controls_and_follows_chains() {
+        let mut cfg = config(
+            r#"
+pages:
+  - name: Cour
+    extends: Base
+    controls:
+      f2: { app: obs, action: changeScene, params: [Cour] }
+  - name: Base
+    lcd: { labels: [A] }
+    controls:
+      f1: { app: obs, action: toggleStudioMode }
+      f2: { app: obs, action: changeScene, params: [Base] }
+  - name: Cour bis
+    extends: Cour
+"#,
+        );
+        cfg.expand_pages().unwrap();
+
+        let cour = &cfg.pages[2];
+        let controls = cour.controls.as_ref().unwrap();
+        assert!(controls.contains_key("f1"));
+        assert_eq!(
+            controls["f2"].params.as_ref().unwrap(),
+            &vec![Value::from("Cour")]
+        );
+        assert!(cour.lcd.is_some());
+        assert_eq!(cour.name, "Cour bis");
+    }
+
+    #[test]
+    fn expansion_errors() {
+        for (yaml, why) in [
+            ("pages:\n  - {name: A, extends: B}\n  - {name: B, extends: A}\n", "cycle"),
+            ("pages:\n  - {name: A, extends: Missing}\n", "unknown base"),
+            ("pages:\n  - {template: missing}\n", "unknown template"),
+            (
+                "page_templates:\n  t: {name: T}\npages:\n  - {name: B}\n  - {template: t, extends: B}\n",
+                "template and extends",
+            ),
+        ] {
+            assert!(config(yaml).expand_pages().is_err(), "{}", why);
+        }
+    }
+
+    #[test]
+    fn extends_without_name_is_a_duplicate_page() {
+        let mut cfg = config("pages:\n  - {name: Base}\n  - {extends: Base}\n");
+        cfg.expand_pages().unwrap();
+        let err = cfg.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("duplicates page name 'Base'"),
+            "{}",
+            err
+        );
+    }
+}
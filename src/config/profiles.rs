@@ -15,6 +15,15 @@
 //! `config.yaml`) that the gateway's existing config watcher monitors. This
 //! module never modifies the watcher itself; it just keeps the watched file
 //! in sync with the selected profile.
+//!
+//! Profiles may `include:` shared fragments (see [`super::includes`]) kept
+//! under the root, e.g. `profiles/shared/cameras.yaml` (files in
+//! subdirectories aren't listed as profiles). For such a profile:
+//! - include paths must stay inside the root;
+//! - the watched file becomes a stub including the profile by absolute
+//!   path, so the watcher follows the profile and its fragments;
+//! - history snapshots hold the resolved bundle, so restoring one brings
+//!   back the whole config even after the shared fragments changed.
 
 use std::fs::{self, File};
 use std::io::Write;
@@ -24,6 +33,8 @@ use chrono::Utc;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 
+use super::includes;
+
 const ACTIVE_FILE: &str = "_active.txt";
 const DEFAULT_PROFILE: &str = "default";
 const DEFAULT_RETENTION: usize = 50;
@@ -42,6 +53,8 @@ pub enum ProfileError {
     Active(String),
     #[error("conflicting write: on-disk hash does not match expected hash")]
     ConflictingWrite,
+    #[error("include outside the profile root: {0}")]
+    InvalidInclude(String),
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("yaml error: {0}")]
@@ -160,6 +173,7 @@ impl ProfileStore {
         expected_hash: Option<&str>,
     ) -> Result<ProfileMeta> {
         validate_name(name)?;
+        validate_includes(body)?;
         let path = self.profile_path(name);
         let existed = path.exists();
 
@@ -171,7 +185,8 @@ impl ProfileStore {
                     return Err(ProfileError::ConflictingWrite);
                 }
             }
-            self.snapshot_bytes(name, &on_disk)?;
+            let snapshot = self.snapshot_body(&path, &on_disk);
+            self.snapshot_bytes(name, &snapshot)?;
         } else if expected_hash.is_some() {
             return Err(ProfileError::ConflictingWrite);
         }
@@ -196,6 +211,7 @@ impl ProfileStore {
     /// Create a new profile. Fails if it already exists.
     pub fn create(&self, name: &str, body: &str) -> Result<ProfileMeta> {
         validate_name(name)?;
+        validate_includes(body)?;
         if self.profile_path(name).exists() {
             return Err(ProfileError::AlreadyExists(name.to_string()));
         }
@@ -339,8 +355,49 @@ impl ProfileStore {
         self.write(name, &body, None)
     }
 
+    /// `body` with its includes (relative to the root) merged in, as
+    /// YAML; `None` when it has no `include:` (or doesn't parse).
+    pub fn resolve_includes(&self, body: &str) -> Result<Option<String>> {
+        // The file name only matters for error messages
+        self.resolve_body(&self.root.join("(body).yaml"), body)
+    }
+
     // -------------------- internal helpers --------------------
 
+    fn resolve_body(&self, path: &Path, body: &str) -> Result<Option<String>> {
+        // Invalid YAML is left for the config parser to report
+        let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(body) else {
+            return Ok(None);
+        };
+        if includes::include_paths(&value)?.is_empty() {
+            return Ok(None);
+        }
+        let bundle = includes::resolve(value, path, Some(&self.root))?;
+        let root = fs::canonicalize(&self.root)?;
+        let fragments: Vec<String> = bundle.files[1..]
+            .iter()
+            .map(|f| f.strip_prefix(&root).unwrap_or(f).display().to_string())
+            .collect();
+        Ok(Some(format!(
+            "# Resolved with include(s): {}\n{}",
+            fragments.join(", "),
+            serde_yaml::to_string(&bundle.value)?
+        )))
+    }
+
+    /// What history keeps for the `bytes` of the profile at `path`: the
+    /// resolved bundle when it uses includes, else the bytes as is (also
+    /// when the includes no longer resolve).
+    fn snapshot_body(&self, path: &Path, bytes: &[u8]) -> Vec<u8> {
+        let Ok(body) = std::str::from_utf8(bytes) else {
+            return bytes.to_vec();
+        };
+        match self.resolve_body(path, body) {
+            Ok(Some(resolved)) => resolved.into_bytes(),
+            _ => bytes.to_vec(),
+        }
+    }
+
     fn profile_path(&self, name: &str) -> PathBuf {
         self.root.join(format!("{}.yaml", name))
     }
@@ -431,7 +488,25 @@ impl ProfileStore {
             }
         }
         let body = fs::read(&src)?;
-        atomic_write(&self.watched_path, &body)?;
+        let uses_includes = serde_yaml::from_slice::<serde_yaml::Value>(&body)
+            .ok()
+            .and_then(|value| includes::include_paths(&value).ok())
+            .is_some_and(|paths| !paths.is_empty());
+        if uses_includes {
+            // Include the profile itself: its fragments then resolve (and
+            // get watched) from the profile root.
+            let src = fs::canonicalize(&src)?;
+            let stub = format!(
+                "# Generated from profile '{}' (uses include)\n{}",
+                name,
+                serde_yaml::to_string(&serde_json::json!({
+                    includes::INCLUDE_KEY: src.display().to_string(),
+                }))?
+            );
+            atomic_write(&self.watched_path, stub.as_bytes())?;
+        } else {
+            atomic_write(&self.watched_path, &body)?;
+        }
         Ok(())
     }
 }
@@ -477,6 +552,20 @@ fn validate_name(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Reject `include:` paths that leave the profile root. Bodies that aren't
+/// valid YAML are left for the config loader to report.
+fn validate_includes(body: &str) -> Result<()> {
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(body) else {
+        return Ok(());
+    };
+    for path in includes::include_paths(&value)? {
+        if !includes::is_contained(&path) {
+            return Err(ProfileError::InvalidInclude(path));
+        }
+    }
+    Ok(())
+}
+
 fn validate_timestamp(ts: &str) -> Result<()> {
     if ts.is_empty() || ts.len() > 64 {
         return Err(ProfileError::InvalidName(ts.to_string()));
@@ -768,4 +857,52 @@ mod tests {
         let mirrored = std::fs::read_to_string(&fx.watched).unwrap();
         assert_eq!(mirrored, "live: true\n");
     }
+
+    #[test]
+    fn include_profiles_mirror_a_stub_and_snapshot_the_bundle() {
+        let fx = fixture(50, Some(SAMPLE_YAML));
+        fx.store.ensure_initialized().unwrap();
+        std::fs::create_dir_all(fx.root.join("shared")).unwrap();
+        std::fs::write(fx.root.join("shared/midi.yaml"), SAMPLE_YAML).unwrap();
+
+        let body = "include: shared/midi.yaml\npages: []\n";
+        fx.store.write("default", body, None).unwrap();
+        let mirrored = std::fs::read_to_string(&fx.watched).unwrap();
+        assert!(mirrored.contains("include:"), "{}", mirrored);
+        assert!(mirrored.contains("default.yaml"), "{}", mirrored);
+        assert_eq!(
+            fx.store.list().unwrap().len(),
+            1,
+            "fragments aren't profiles"
+        );
+
+        // Snapshot of the include-based body: the merged config
+        fx.store.write("default", SAMPLE_YAML, None).unwrap();
+        let history = fx.store.list_history("default").unwrap();
+        let snapshot = fx
+            .store
+            .read_snapshot("default", &history[0].timestamp)
+            .unwrap();
+        assert!(snapshot.contains("shared/midi.yaml"), "{}", snapshot);
+        assert!(!snapshot.contains("include:"), "{}", snapshot);
+        assert!(snapshot.contains("input_port"), "{}", snapshot);
+
+        std::fs::write(fx.root.join("shared/midi.yaml"), "midi: {}\n").unwrap();
+        let resolved = fx.store.resolve_includes(body).unwrap().unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&resolved).unwrap();
+        assert!(value["midi"].as_mapping().unwrap().is_empty());
+        assert!(fx.store.resolve_includes(SAMPLE_YAML).unwrap().is_none());
+    }
+
+    #[test]
+    fn includes_outside_the_root_rejected() {
+        let fx = fixture(50, Some(SAMPLE_YAML));
+        fx.store.ensure_initialized().unwrap();
+        for bad in ["include: ../secret.yaml\n", "include: [/etc/passwd]\n"] {
+            match fx.store.write("default", bad, None) {
+                Err(ProfileError::InvalidInclude(_)) => {},
+                other => panic!("expected InvalidInclude for {:?}, got {:?}", bad, other),
+            }
+        }
+    }
 }
@@ -0,0 +1,319 @@
+//! Per-mapping value transforms (`value:` on a control or an action step).
+//!
+//! Values are normalized (0.0..=1.0) on both sides. On the way out the
+//! hardware value goes through: input range -> invert -> curve -> step
+//! quantization -> output range. [`ValueTransform::reverse`] runs the same
+//! chain backwards on feedback, so a motorized fader lands where the app
+//! actually is.
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Value transform applied to a mapping.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct ValueTransform {
+    /// Start of the useful hardware travel (normalized). Values below are
+    /// clamped.
+    #[serde(default)]
+    pub in_min: f64,
+    /// End of the useful hardware travel (normalized). Values above are
+    /// clamped.
+    #[serde(default = "default_max")]
+    pub in_max: f64,
+    /// Value sent at the bottom of the (transformed) travel.
+    #[serde(default)]
+    pub out_min: f64,
+    /// Value sent at the top of the (transformed) travel.
+    #[serde(default = "default_max")]
+    pub out_max: f64,
+    /// Reverse the direction of travel.
+    #[serde(default)]
+    pub invert: bool,
+    /// Response curve.
+    #[serde(default)]
+    pub curve: ValueCurve,
+    /// Strength of the `exp` / `log` curves (> 0, 1 = linear).
+    #[serde(default = "default_exponent")]
+    pub exponent: f64,
+    /// `db` curve: attenuation at the bottom of travel, in dB below unity.
+    #[serde(default = "default_db_range")]
+    pub db_range: f64,
+    /// Snap to this many evenly spaced values (0 = continuous).
+    #[serde(default)]
+    pub steps: u32,
+    /// Ignore input moves smaller than this (normalized). The ends of the
+    /// travel always go through.
+    #[serde(default)]
+    pub deadband: f64,
+}
+
+/// Response curve of a [`ValueTransform`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueCurve {
+    /// Straight line.
+    #[default]
+    Linear,
+    /// `t^exponent`: fine control at the bottom of travel.
+    Exp,
+    /// `1 - (1 - t)^exponent`: fine control at the top of travel.
+    Log,
+    /// Audio taper: the travel is linear in dB from `-db_range` to 0 dB,
+    /// the output is the matching linear gain (0 at the very bottom).
+    Db,
+}
+
+impl Default for ValueTransform {
+    fn default() -> Self {
+        Self {
+            in_min: 0.0,
+            in_max: 1.0,
+            out_min: 0.0,
+            out_max: 1.0,
+            invert: false,
+            curve: ValueCurve::Linear,
+            exponent: default_exponent(),
+            db_range: default_db_range(),
+            steps: 0,
+            deadband: 0.0,
+        }
+    }
+}
+
+impl ValueTransform {
+    /// Hardware value -> value sent to the app.
+    pub fn apply(&self, input: f64) -> f64 {
+        let t = unlerp(input, self.in_min, self.in_max);
+        let t = if self.invert { 1.0 - t } else { t };
+        let t = quantize(self.curve_forward(t), self.steps);
+        lerp(t, self.out_min, self.out_max)
+    }
+
+    /// App value (feedback) -> hardware position. Inverse of [`apply`](Self::apply)
+    /// up to clamping and step quantization.
+    pub fn reverse(&self, output: f64) -> f64 {
+        let t = self.curve_backward(unlerp(output, self.out_min, self.out_max));
+        let t = if self.invert { 1.0 - t } else { t };
+        lerp(t, self.in_min, self.in_max)
+    }
+
+    /// Whether a move from `last` (previously forwarded input) to `input`
+    /// is large enough to forward.
+    pub fn passes_deadband(&self, last: Option<f64>, input: f64) -> bool {
+        match last {
+            Some(last) if self.deadband > 0.0 => {
+                (input - last).abs() >= self.deadband
+                    || input <= self.in_min.min(self.in_max)
+                    || input >= self.in_max.max(self.in_min)
+            },
+            _ => true,
+        }
+    }
+
+    /// Reject ranges and curve parameters that can't be applied.
+    /// `context` prefixes error messages (e.g. the control id).
+    pub fn validate(&self, context: &str) -> Result<()> {
+        for (name, v) in [
+            ("in_min", self.in_min),
+            ("in_max", self.in_max),
+            ("out_min", self.out_min),
+            ("out_max", self.out_max),
+        ] {
+            if !(0.0..=1.0).contains(&v) {
+                anyhow::bail!(
+                    "{}: value.{} must be in 0.0..=1.0, got {}",
+                    context,
+                    name,
+                    v
+                );
+            }
+        }
+        if self.in_min >= self.in_max {
+            anyhow::bail!(
+                "{}: value.in_min must be below value.in_max (use `invert` to reverse)",
+                context
+            );
+        }
+        if self.out_min == self.out_max {
+            anyhow::bail!("{}: value.out_min and value.out_max must differ", context);
+        }
+        if !(self.exponent > 0.0 && self.exponent.is_finite()) {
+            anyhow::bail!(
+                "{}: value.exponent must be > 0, got {}",
+                context,
+                self.exponent
+            );
+        }
+        if !(self.db_range > 0.0 && self.db_range.is_finite()) {
+            anyhow::bail!(
+                "{}: value.db_range must be > 0, got {}",
+                context,
+                self.db_range
+            );
+        }
+        if self.steps == 1 {
+            anyhow::bail!(
+                "{}: value.steps must be 0 (continuous) or at least 2",
+                context
+            );
+        }
+        if !(0.0..0.5).contains(&self.deadband) {
+            anyhow::bail!(
+                "{}: value.deadband must be in 0.0..0.5, got {}",
+                context,
+                self.deadband
+            );
+        }
+        Ok(())
+    }
+
+    fn curve_forward(&self, t: f64) -> f64 {
+        match self.curve {
+            ValueCurve::Linear => t,
+            ValueCurve::Exp => t.powf(self.exponent),
+            ValueCurve::Log => 1.0 - (1.0 - t).powf(self.exponent),
+            ValueCurve::Db if t <= 0.0 => 0.0,
+            ValueCurve::Db => 10f64.powf((t - 1.0) * self.db_range / 20.0),
+        }
+    }
+
+    fn curve_backward(&self, t: f64) -> f64 {
+        match self.curve {
+            ValueCurve::Linear => t,
+            ValueCurve::Exp => t.powf(1.0 / self.exponent),
+            ValueCurve::Log => 1.0 - (1.0 - t).powf(1.0 / self.exponent),
+            ValueCurve::Db if t <= 0.0 => 0.0,
+            ValueCurve::Db => (1.0 + 20.0 * t.log10() / self.db_range).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Position of `v` in `[min, max]`, clamped to 0..=1 (`max < min` reverses).
+fn unlerp(v: f64, min: f64, max: f64) -> f64 {
+    if min == max {
+        return 0.0;
+    }
+    ((v - min) / (max - min)).clamp(0.0, 1.0)
+}
+
+fn lerp(t: f64, min: f64, max: f64) -> f64 {
+    min + t * (max - min)
+}
+
+fn quantize(t: f64, steps: u32) -> f64 {
+    if steps < 2 {
+        return t;
+    }
+    let last = f64::from(steps - 1);
+    (t * last).round() / last
+}
+
+fn default_max() -> f64 {
+    1.0
+}
+fn default_exponent() -> f64 {
+    2.0
+}
+fn default_db_range() -> f64 {
+    60.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn default_is_identity() {
+        let t = ValueTransform::default();
+        for v in [0.0, 0.25, 1.0] {
+            assert!(close(t.apply(v), v));
+            assert!(close(t.reverse(v), v));
+        }
+    }
+
+    #[test]
+    fn ranges_and_invert() {
+        let t = ValueTransform {
+            in_min: 0.2,
+            in_max: 0.8,
+            out_min: 0.5,
+            out_max: 1.0,
+            invert: true,
+            ..Default::default()
+        };
+        assert!(close(t.apply(0.0), 1.0));
+        assert!(close(t.apply(0.5), 0.75));
+        assert!(close(t.apply(0.9), 0.5));
+        // Feedback below the output range parks the fader at its end stop.
+        assert!(close(t.reverse(0.75), 0.5));
+        assert!(close(t.reverse(0.1), 0.8));
+    }
+
+    #[test]
+    fn curves_round_trip() {
+        for curve in [ValueCurve::Exp, ValueCurve::Log, ValueCurve::Db] {
+            let t = ValueTransform {
+                curve,
+                exponent: 3.0,
+                ..Default::default()
+            };
+            for v in [0.0, 0.1, 0.5, 0.9, 1.0] {
+                assert!(close(t.reverse(t.apply(v)), v), "{curve:?} at {v}");
+            }
+        }
+        let db = ValueTransform {
+            curve: ValueCurve::Db,
+            db_range: 40.0,
+            ..Default::default()
+        };
+        // Half travel = -20 dB = 0.1 linear gain.
+        assert!(close(db.apply(0.5), 0.1));
+        assert!(close(db.apply(0.0), 0.0));
+    }
+
+    #[test]
+    fn steps_and_deadband() {
+        let t = ValueTransform {
+            steps: 5,
+            deadband: 0.05,
+            ..Default::default()
+        };
+        assert!(close(t.apply(0.3), 0.25));
+        assert!(close(t.apply(0.4), 0.5));
+        assert!(t.passes_deadband(None, 0.5));
+        assert!(!t.passes_deadband(Some(0.5), 0.53));
+        assert!(t.passes_deadband(Some(0.5), 0.56));
+        assert!(t.passes_deadband(Some(0.98), 1.0));
+    }
+
+    #[test]
+    fn validate_rejects_unusable_parameters() {
+        assert!(ValueTransform::default().validate("fader1").is_ok());
+        for bad in [
+            ValueTransform {
+                in_min: 0.8,
+                in_max: 0.2,
+                ..Default::default()
+            },
+            ValueTransform {
+                out_max: 1.5,
+                ..Default::default()
+            },
+            ValueTransform {
+                steps: 1,
+                ..Default::default()
+            },
+            ValueTransform {
+                exponent: 0.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(bad.validate("fader1").is_err(), "{bad:?}");
+        }
+    }
+}
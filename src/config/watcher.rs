@@ -1,8 +1,12 @@
 //! Configuration file watcher for hot-reload support
+//!
+//! Watches the config file and every file it pulls in with `include:`; a
+//! change to any of them reloads the whole config.
 
 use anyhow::{Context, Result};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::Path;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -12,22 +16,50 @@ use super::AppConfig;
 
 /// Config watcher that monitors file changes and sends reload notifications
 pub struct ConfigWatcher {
-    _watcher: RecommendedWatcher,
+    _watched: Arc<Mutex<Option<WatchedFiles>>>,
     rx: mpsc::Receiver<AppConfig>,
 }
 
+/// The notify watcher and the config files it watches.
+struct WatchedFiles {
+    watcher: RecommendedWatcher,
+    files: Vec<PathBuf>,
+}
+
+impl WatchedFiles {
+    /// Watch the files of the config just loaded. Every file is watched
+    /// anew: a save that replaces a file (atomic rename) drops its watch.
+    fn update(&mut self, files: Vec<PathBuf>) {
+        for old in &self.files {
+            if let Err(e) = self.watcher.unwatch(old) {
+                debug!("Failed to unwatch {}: {}", old.display(), e);
+            }
+        }
+        for file in &files {
+            if let Err(e) = self.watcher.watch(file, RecursiveMode::NonRecursive) {
+                warn!("Failed to watch config file {}: {}", file.display(), e);
+            }
+        }
+        self.files = files;
+    }
+}
+
 impl ConfigWatcher {
     /// Create a new config watcher for the specified file
     pub async fn new(config_path: String) -> Result<(Self, Arc<AppConfig>)> {
         let (tx, rx) = mpsc::channel(10);
 
         // Load initial config
-        let initial_config = AppConfig::load(&config_path)
+        let (initial_config, files) = AppConfig::load_bundle(&config_path)
             .await
             .context("Failed to load initial config")?;
         let initial_config = Arc::new(initial_config);
 
         let config_path_clone = config_path.clone();
+        // Filled once the watcher exists. Weak in the callback: the watcher
+        // owns the callback.
+        let watched: Arc<Mutex<Option<WatchedFiles>>> = Arc::new(Mutex::new(None));
+        let watched_weak = Arc::downgrade(&watched);
 
         // Capture the Tokio runtime handle BEFORE creating the watcher
         // (notify callbacks run on their own OS thread, not in Tokio context)
@@ -44,15 +76,22 @@ impl ConfigWatcher {
                         // Clone path for async block
                         let config_path = config_path_clone.clone();
                         let tx = tx.clone();
+                        let watched = watched_weak.clone();
 
                         // Use the captured runtime handle to spawn async task
                         runtime_handle.spawn(async move {
                             // Debounce: wait a bit for file writes to complete
                             tokio::time::sleep(Duration::from_millis(100)).await;
 
-                            match AppConfig::load(&config_path).await {
-                                Ok(new_config) => {
+                            match AppConfig::load_bundle(&config_path).await {
+                                Ok((new_config, files)) => {
                                     info!("Configuration reloaded successfully");
+                                    // Includes may have been added or removed
+                                    if let Some(watched) = watched.upgrade() {
+                                        if let Some(watched) = watched.lock().as_mut() {
+                                            watched.update(files);
+                                        }
+                                    }
                                     if let Err(e) = tx.send(new_config).await {
                                         error!("Failed to send config update: {}", e);
                                     }
@@ -70,16 +109,26 @@ impl ConfigWatcher {
             }
         })?;
 
-        // Watch the config file
+        // Watch the config file, then its includes
         watcher
             .watch(Path::new(&config_path), RecursiveMode::NonRecursive)
             .with_context(|| format!("Failed to watch config file: {}", config_path))?;
-
-        info!("Config file watcher started for: {}", config_path);
+        let includes = files.len() - 1;
+        let mut watched_files = WatchedFiles {
+            watcher,
+            files: vec![PathBuf::from(&config_path)],
+        };
+        watched_files.update(files);
+        *watched.lock() = Some(watched_files);
+
+        info!(
+            "Config file watcher started for: {} ({} include(s))",
+            config_path, includes
+        );
 
         Ok((
             Self {
-                _watcher: watcher,
+                _watched: watched,
                 rx,
             },
             initial_config,
@@ -147,4 +196,38 @@ pages:
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_config_watcher_reloads_on_include_change() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("test-config.yaml");
+        let include_path = temp_dir.path().join("shared").join("midi.yaml");
+        fs::create_dir_all(include_path.parent().unwrap())?;
+        fs::write(
+            &include_path,
+            "midi:\n  input_port: \"shared-in\"\n  output_port: \"shared-out\"\n",
+        )?;
+        fs::write(
+            &config_path,
+            "include: shared/midi.yaml\npages:\n  - name: \"Test Page\"\n",
+        )?;
+
+        let (mut watcher, config) =
+            ConfigWatcher::new(config_path.to_string_lossy().to_string()).await?;
+        assert_eq!(config.midi.input_port, "shared-in");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        fs::write(
+            &include_path,
+            "midi:\n  input_port: \"shared-in-modified\"\n  output_port: \"shared-out\"\n",
+        )?;
+
+        let new_config =
+            tokio::time::timeout(Duration::from_secs(2), watcher.next_config()).await?;
+        if let Some(new_config) = new_config {
+            assert_eq!(new_config.midi.input_port, "shared-in-modified");
+        }
+
+        Ok(())
+    }
 }
@@ -1,12 +1,14 @@
 //! Control mapping parser for X-Touch control definitions
 //!
 //! Parses the xtouch-matching.csv file to map control IDs to MIDI messages.
+//! Other surfaces (`xtouch.device`) have their own CSV under `docs/devices/`.
 
+use crate::config::DeviceProfile;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::{OnceLock, RwLock};
 use tracing::info;
 
 /// Control mapping entry from CSV
@@ -228,6 +230,39 @@ impl ControlMappingDB {
         })
     }
 
+    /// The same control's spec in the other mode: CTRL → MCU when `to_mcu`,
+    /// MCU → CTRL otherwise.
+    pub fn translate_spec(&self, spec: &MidiSpec, to_mcu: bool) -> Option<MidiSpec> {
+        let control_id = self.find_control_by_midi(spec, !to_mcu)?;
+        self.get_midi_spec(control_id, to_mcu)
+    }
+
+    /// An MCU-addressed surface message (LED note or CC on channel 1, fader
+    /// PB) re-encoded for CTRL mode. A 14-bit fader position becomes the
+    /// fader's 7-bit CC. `None` when the control has no CTRL counterpart.
+    pub fn mcu_to_ctrl(&self, raw: &[u8]) -> Option<Vec<u8>> {
+        let status = *raw.first()?;
+        if status & 0xF0 != 0xE0 && status & 0x0F != 0 {
+            return None;
+        }
+        let value = match *raw {
+            [status, _, _] if status & 0xF0 == 0x80 => 0,
+            [status, _, msb] if status & 0xF0 == 0xE0 => msb & 0x7F,
+            [_, _, value] => value,
+            _ => return None,
+        };
+        let spec = MidiSpec::from_raw(raw).ok()?;
+        Some(match self.translate_spec(&spec, false)? {
+            MidiSpec::Note { note } => vec![0x90, note, value],
+            MidiSpec::ControlChange { cc } => vec![0xB0, cc, value],
+            MidiSpec::PitchBend { channel } => {
+                let mut message = raw.to_vec();
+                message[0] = 0xE0 | channel;
+                message
+            },
+        })
+    }
+
     /// Get all fader control IDs (fader1-fader8, fader_master)
     #[cfg(test)]
     pub fn get_fader_controls(&self) -> Vec<&str> {
@@ -258,25 +293,48 @@ impl ControlMappingDB {
     }
 }
 
-/// Default embedded CSV content (for when file is not available)
-pub const DEFAULT_CSV: &str = include_str!("../docs/xtouch-matching.csv");
+/// Device whose mappings [`load_default_mappings`] returns
+static ACTIVE_DEVICE: RwLock<DeviceProfile> = RwLock::new(DeviceProfile::Xtouch);
+
+/// Global cache for the embedded mappings, one per device
+static DEVICE_DBS: [OnceLock<ControlMappingDB>; 5] = [
+    OnceLock::new(),
+    OnceLock::new(),
+    OnceLock::new(),
+    OnceLock::new(),
+    OnceLock::new(),
+];
+
+/// Select the surface the mappings are loaded for (at startup, before
+/// anything resolves a control)
+pub fn select_device(device: DeviceProfile) {
+    *ACTIVE_DEVICE.write().unwrap() = device;
+}
 
-/// Global cache for the embedded default mappings
-static DEFAULT_DB: OnceLock<ControlMappingDB> = OnceLock::new();
+/// The surface selected with [`select_device`]
+pub fn active_device() -> DeviceProfile {
+    *ACTIVE_DEVICE.read().unwrap()
+}
 
-/// Load the default control mappings (cached after first parse)
-///
-/// Returns a static reference to avoid cloning the entire DB on every call.
-/// The DB is parsed once and cached in a `OnceLock`.
-pub fn load_default_mappings() -> Result<&'static ControlMappingDB> {
-    if let Some(db) = DEFAULT_DB.get() {
+/// Load the embedded control mappings of `device` (cached after first parse)
+pub fn load_device_mappings(device: DeviceProfile) -> Result<&'static ControlMappingDB> {
+    let cell = &DEVICE_DBS[device as usize];
+    if let Some(db) = cell.get() {
         return Ok(db);
     }
 
-    let db = ControlMappingDB::load_from_string(DEFAULT_CSV)?;
+    let db = ControlMappingDB::load_from_string(device.control_csv().1)?;
     // Ignore error if another thread set it first
-    let _ = DEFAULT_DB.set(db);
-    Ok(DEFAULT_DB.get().expect("just set"))
+    let _ = cell.set(db);
+    Ok(cell.get().expect("just set"))
+}
+
+/// Load the control mappings of the active device (cached after first parse)
+///
+/// Returns a static reference to avoid cloning the entire DB on every call.
+/// The DB is parsed once and cached in a `OnceLock`.
+pub fn load_default_mappings() -> Result<&'static ControlMappingDB> {
+    load_device_mappings(active_device())
 }
 
 /// Warm the default cache so parsing happens at startup
@@ -377,4 +435,53 @@ mod tests {
         assert!(buttons.contains(&"mute1"));
         assert!(buttons.contains(&"solo1"));
     }
+
+    #[test]
+    fn test_mcu_to_ctrl() {
+        let db = load_device_mappings(DeviceProfile::Xtouch).unwrap();
+        // mute1 LED, fader 2 at full scale, master fader
+        assert_eq!(db.mcu_to_ctrl(&[0x90, 16, 127]), Some(vec![0x90, 24, 127]));
+        assert_eq!(
+            db.mcu_to_ctrl(&[0xE1, 0x7F, 0x7F]),
+            Some(vec![0xB0, 71, 127])
+        );
+        assert_eq!(
+            db.mcu_to_ctrl(&[0xE8, 0x00, 0x40]),
+            Some(vec![0xB0, 78, 64])
+        );
+        assert_eq!(
+            db.translate_spec(&MidiSpec::Note { note: 58 }, true),
+            Some(MidiSpec::Note { note: 54 })
+        );
+        // V-Pot ring CC: MCU only
+        assert_eq!(db.mcu_to_ctrl(&[0xB0, 48, 3]), None);
+    }
+
+    #[test]
+    fn test_device_mappings() {
+        for device in [
+            DeviceProfile::Xtouch,
+            DeviceProfile::XtouchOne,
+            DeviceProfile::XtouchCompact,
+            DeviceProfile::Mcu,
+            DeviceProfile::PlatformM,
+        ] {
+            let db = load_device_mappings(device).unwrap();
+            let faders = db.get_fader_controls();
+            let expected = usize::from(device.strips()) + db.get("fader_master").map_or(0, |_| 1);
+            assert_eq!(faders.len(), expected, "{:?}", device);
+        }
+
+        let one = load_device_mappings(DeviceProfile::XtouchOne).unwrap();
+        assert!(one.get("jog_wheel").is_some());
+        assert!(one.get("vpot1_rotate").is_none());
+        assert_eq!(
+            one.find_control_by_midi(&MidiSpec::PitchBend { channel: 0 }, true),
+            Some("fader1")
+        );
+
+        let compact = load_device_mappings(DeviceProfile::XtouchCompact).unwrap();
+        assert!(compact.get("vpot8_push").is_some());
+        assert!(compact.get("f1").is_none());
+    }
 }
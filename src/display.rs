@@ -8,7 +8,6 @@ use std::sync::Arc;
 
 use tracing::warn;
 
-use crate::config::PageConfig;
 use crate::midi::MidiMessage;
 use crate::router::Router;
 use crate::xtouch::XTouchDriver;
@@ -20,25 +19,24 @@ use crate::xtouch::XTouchDriver;
 /// from the router at call time, so it always reflects the latest state.
 pub async fn update_xtouch_display(router: &Router, xtouch: &Arc<XTouchDriver>) {
     // Read config once to extract all needed fields
-    let (active_page, active_page_name, paging_channel, paging) = {
+    let (paging_channel, paging) = {
         let config = router.config.read().await;
-        let index = *router.active_page_index.read().await;
-        let page = config.pages.get(index).cloned();
-        let name = page
-            .as_ref()
-            .map(|p| p.name.clone())
-            .unwrap_or_else(|| "(none)".to_string());
         let paging_channel = config.paging.as_ref().map(|p| p.channel).unwrap_or(1);
-        let paging = config.paging.clone();
-        (page, name, paging_channel, paging)
+        (paging_channel, config.paging.clone())
     };
+    // Labels and colors follow the page's current bank
+    let active_page = router.get_active_page().await;
+    let active_page_name = active_page
+        .as_ref()
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| "(none)".to_string());
 
     if let Some(page) = &active_page {
-        let labels = page.lcd.as_ref().and_then(|lcd| lcd.labels.as_ref());
-        let colors_u8 = convert_lcd_colors(page);
+        // Labels and colors may read signals: render them against the store
+        let frame = router.render_lcd(page).await;
 
         if let Err(e) = xtouch
-            .apply_lcd_for_page(labels, colors_u8.as_ref(), &active_page_name)
+            .apply_lcd_for_page(&frame.labels, &frame.colors, &active_page_name)
             .await
         {
             warn!("Failed to apply LCD for page: {}", e);
@@ -63,15 +61,6 @@ pub async fn update_xtouch_display(router: &Router, xtouch: &Arc<XTouchDriver>)
     }
 }
 
-/// Convert LCD colors from a page config to u8 values.
-pub fn convert_lcd_colors(page: &PageConfig) -> Option<Vec<u8>> {
-    page.lcd.as_ref().and_then(|lcd| {
-        lcd.colors
-            .as_ref()
-            .map(|colors| colors.iter().map(|c| c.to_u8()).collect())
-    })
-}
-
 /// Flush pending MIDI messages from the router to the X-Touch hardware.
 ///
 /// Takes all queued messages (e.g., from page refresh) and sends them sequentially.
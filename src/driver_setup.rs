@@ -1,17 +1,25 @@
 //! Driver registration and initialization helpers.
 //!
-//! Contains functions for registering MIDI bridge drivers, OBS driver,
+//! Contains functions for registering MIDI bridge drivers, OBS/vMix drivers,
 //! loading the control database, and performing the startup refresh sequence.
 
+use std::path::Path;
 use std::sync::Arc;
 
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ProcessConfig};
 use crate::control_mapping::ControlMappingDB;
+use crate::drivers::macros::MacroDriver;
 use crate::drivers::midibridge::MidiBridgeDriver;
+use crate::drivers::mmc::MmcDriver;
+use crate::drivers::mpris::MprisDriver;
 use crate::drivers::obs::ObsDriver;
+use crate::drivers::process::ProcessDriver;
+use crate::drivers::pulseaudio::PulseAudioDriver;
+use crate::drivers::vars::VarsDriver;
+use crate::drivers::vmix::VmixDriver;
 use crate::drivers::winaudio::WinAudioDriver;
 use crate::drivers::winmedia::WinMediaDriver;
 use crate::drivers::Driver;
@@ -76,9 +84,11 @@ pub async fn register_midi_bridge_drivers(
     }
 }
 
-/// Load the control mapping database (external file or embedded fallback).
+/// Load the control mapping database of the active device (external file
+/// or embedded fallback).
 pub async fn load_control_database() -> Arc<ControlMappingDB> {
-    match ControlMappingDB::load_from_csv("docs/xtouch-matching.csv").await {
+    let (csv_path, _) = control_mapping::active_device().control_csv();
+    match ControlMappingDB::load_from_csv(csv_path).await {
         Ok(db) => {
             info!("Loaded control database ({} controls)", db.mappings.len());
             Arc::new(db)
@@ -216,6 +226,53 @@ pub async fn register_winaudio_driver(
     }
 }
 
+/// Register the Linux audio driver if `pulseaudio` is configured or any
+/// page references the `pulseaudio` app.
+///
+/// Same contract as [`register_winaudio_driver`]: cross-platform
+/// registration (no-op backend off Linux), volume feedback injected on
+/// `feedback_tx`, dynamic LCD strips pushed on `led_tx`.
+pub async fn register_pulseaudio_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    feedback_tx: &mpsc::Sender<(String, Vec<u8>)>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+) {
+    let referenced = config.references_app(crate::drivers::pulseaudio::DRIVER_NAME);
+
+    if !referenced && config.pulseaudio.is_none() {
+        debug!("PulseAudio driver not configured and unreferenced — skipping registration");
+        return;
+    }
+
+    if router
+        .get_driver(crate::drivers::pulseaudio::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("PulseAudio driver already registered — skipping");
+        return;
+    }
+
+    let driver = Arc::new(PulseAudioDriver::new(
+        config.pulseaudio.clone().unwrap_or_default(),
+    ));
+    driver.set_router(router.clone()).await;
+    driver.set_led_sender(led_tx.clone()).await;
+    driver.set_feedback_sender(feedback_tx.clone()).await;
+
+    match router
+        .register_driver(crate::drivers::pulseaudio::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered PulseAudio driver"),
+        Err(e) => warn!(
+            "Failed to register PulseAudio driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
 /// Register the WinMedia driver if any control mapping references it.
 ///
 /// Idempotent: skips if already in the router or no page references
@@ -264,6 +321,350 @@ pub async fn register_winmedia_driver(
     }
 }
 
+/// Register the MPRIS media driver if `mpris` is configured or any page
+/// references the `mpris` app.
+///
+/// Linux counterpart of [`register_winmedia_driver`] (no-op stub off
+/// Linux). Now-playing signals go through the shared indicator callback;
+/// play LED and volume fader feedback are injected on `feedback_tx`, the
+/// optional now-playing LCD strip is pushed on `led_tx`.
+pub async fn register_mpris_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    feedback_tx: &mpsc::Sender<(String, Vec<u8>)>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+) {
+    let referenced = config.references_app(crate::drivers::mpris::DRIVER_NAME);
+
+    if !referenced && config.mpris.is_none() {
+        debug!("MPRIS driver not configured and unreferenced — skipping registration");
+        return;
+    }
+
+    if router
+        .get_driver(crate::drivers::mpris::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("MPRIS driver already registered — skipping");
+        return;
+    }
+
+    let driver = Arc::new(MprisDriver::new(config.mpris.clone().unwrap_or_default()));
+    driver.set_router(router.clone()).await;
+    driver.set_feedback_sender(feedback_tx.clone()).await;
+    driver.set_led_sender(led_tx.clone()).await;
+    driver.set_control_db(Arc::clone(control_db)).await;
+
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    match router
+        .register_driver(crate::drivers::mpris::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered MPRIS driver"),
+        Err(e) => warn!(
+            "Failed to register MPRIS driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
+/// Register the MMC / MTC driver if `mmc` is configured.
+///
+/// Unlike the other optional drivers a page reference alone is not enough:
+/// the driver needs `mmc.output_port`. Transport-state and timecode signals
+/// go through the shared indicator callback; the 7-segment display is
+/// written on `led_tx`.
+pub async fn register_mmc_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+    tray_handler: &Arc<crate::tray::TrayMessageHandler>,
+) {
+    let Some(mmc_config) = config.mmc.clone() else {
+        if config.references_app(crate::drivers::mmc::DRIVER_NAME) {
+            warn!("Pages reference app 'mmc' but no `mmc:` section is configured");
+        }
+        return;
+    };
+
+    if router
+        .get_driver(crate::drivers::mmc::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("MMC driver already registered — skipping");
+        return;
+    }
+
+    let driver = Arc::new(MmcDriver::new(mmc_config));
+    driver.set_router(router.clone()).await;
+    driver.set_led_sender(led_tx.clone()).await;
+
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    let status_callback =
+        tray_handler.subscribe_driver(crate::drivers::mmc::DRIVER_NAME.to_string());
+    driver.subscribe_connection_status(status_callback);
+
+    match router
+        .register_driver(crate::drivers::mmc::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered MMC driver"),
+        Err(e) => warn!(
+            "Failed to register MMC driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
+/// Register the process launcher driver if the `allowlist` file exists.
+///
+/// Only commands listed in it can be launched, so a page reference without
+/// the file registers nothing. The file is read once, when the driver
+/// registers. Running / exit status / stdout signals go through the shared
+/// indicator callback.
+pub async fn register_process_driver(
+    config: &AppConfig,
+    allowlist: &Path,
+    router: &Arc<Router>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+) {
+    if router
+        .get_driver(crate::drivers::process::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("Process driver already registered — skipping");
+        return;
+    }
+
+    let process_config = match ProcessConfig::load(allowlist).await {
+        Ok(Some(process_config)) => process_config,
+        Ok(None) => {
+            if config.references_app(crate::drivers::process::DRIVER_NAME) {
+                warn!(
+                    "Pages reference app 'process' but {} doesn't exist",
+                    allowlist.display()
+                );
+            }
+            return;
+        },
+        Err(e) => {
+            warn!("Process driver disabled: {:#}", e);
+            return;
+        },
+    };
+
+    let driver = Arc::new(ProcessDriver::new(process_config));
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    match router
+        .register_driver(crate::drivers::process::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered process driver"),
+        Err(e) => warn!(
+            "Failed to register process driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
+/// Register the user variables driver if `vars` is configured or any page
+/// references the `vars` app.
+///
+/// Idempotent: skips if already in the router, so values survive profile
+/// reloads. Values persist in the router's state database; if the tree
+/// can't be opened the driver still runs, in memory only.
+pub async fn register_vars_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+) {
+    if config.vars.is_none() && !config.references_app(crate::drivers::vars::DRIVER_NAME) {
+        debug!("Vars driver not configured and unreferenced — skipping registration");
+        return;
+    }
+
+    if router
+        .get_driver(crate::drivers::vars::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("Vars driver already registered — skipping");
+        return;
+    }
+
+    let store = match router.open_state_tree(crate::drivers::vars::STATE_TREE) {
+        Ok(tree) => Some(tree),
+        Err(e) => {
+            warn!(
+                "Vars: persistence unavailable, values kept in memory only: {}",
+                e
+            );
+            None
+        },
+    };
+    let driver = Arc::new(VarsDriver::new(
+        config.vars.clone().unwrap_or_default(),
+        store,
+    ));
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    match router
+        .register_driver(crate::drivers::vars::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered vars driver"),
+        Err(e) => warn!(
+            "Failed to register vars driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
+/// Register the macro driver if `macros` is configured or any page
+/// references the `macro` app.
+///
+/// Idempotent: skips if already in the router. The driver reads macro
+/// definitions from the live config at each start, so a reload needs no
+/// re-registration. It holds a weak router handle to dispatch steps.
+pub async fn register_macro_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+) {
+    if config.macros.is_none() && !config.references_app(crate::drivers::macros::DRIVER_NAME) {
+        debug!("Macro driver not configured and unreferenced — skipping registration");
+        return;
+    }
+
+    if router
+        .get_driver(crate::drivers::macros::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("Macro driver already registered — skipping");
+        return;
+    }
+
+    let driver = Arc::new(MacroDriver::new(Arc::downgrade(router)));
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    match router
+        .register_driver(crate::drivers::macros::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered macro driver"),
+        Err(e) => warn!(
+            "Failed to register macro driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
+/// Register the vMix TCP driver if `vmix` is configured or any page
+/// references the `vmix` app.
+///
+/// Idempotent: skips if already in the router. Tally/activator signals go
+/// through the shared indicator callback (LEDs); audio activators are
+/// injected on `feedback_tx` so faders follow vMix input volumes.
+pub async fn register_vmix_driver(
+    config: &AppConfig,
+    router: &Arc<Router>,
+    feedback_tx: &mpsc::Sender<(String, Vec<u8>)>,
+    control_db: &Arc<ControlMappingDB>,
+    led_tx: &mpsc::Sender<Vec<u8>>,
+    api_state: &Arc<api::ApiState>,
+    tray_handler: &Arc<crate::tray::TrayMessageHandler>,
+) {
+    let referenced = config.references_app(crate::drivers::vmix::DRIVER_NAME);
+
+    if !referenced && config.vmix.is_none() {
+        debug!("vMix driver not configured and unreferenced — skipping registration");
+        return;
+    }
+
+    if router
+        .get_driver(crate::drivers::vmix::DRIVER_NAME)
+        .await
+        .is_some()
+    {
+        debug!("vMix driver already registered — skipping");
+        return;
+    }
+
+    let driver = Arc::new(VmixDriver::from_config(
+        &config.vmix.clone().unwrap_or_default(),
+    ));
+    driver.set_router(router.clone()).await;
+    driver.set_feedback_sender(feedback_tx.clone()).await;
+    driver.set_control_db(Arc::clone(control_db)).await;
+    if let Some(live_tx) = router.live_tx_snapshot().await {
+        driver.set_live_tx(live_tx);
+    }
+
+    driver.subscribe_indicators(obs_indicators::build_indicator_callback(
+        router.clone(),
+        control_db.clone(),
+        led_tx.clone(),
+        Arc::clone(api_state),
+    ));
+
+    let status_callback =
+        tray_handler.subscribe_driver(crate::drivers::vmix::DRIVER_NAME.to_string());
+    driver.subscribe_connection_status(status_callback);
+
+    match router
+        .register_driver(crate::drivers::vmix::DRIVER_NAME.to_string(), driver)
+        .await
+    {
+        Ok(_) => info!("Registered vMix driver"),
+        Err(e) => warn!(
+            "Failed to register vMix driver (will continue without it): {}",
+            e
+        ),
+    }
+}
+
 /// Wait for startup delay, then apply initial state refresh to X-Touch.
 pub async fn apply_startup_refresh(
     config: &AppConfig,
@@ -164,6 +164,14 @@ mod tests {
             gamepad: None,
             pages_global: None,
             winaudio: None,
+            pulseaudio: None,
+            vmix: None,
+            mpris: None,
+            mmc: None,
+            process: None,
+            vars: None,
+            macros: None,
+            page_templates: None,
             pages: vec![],
             tray: None,
         };
@@ -0,0 +1,23 @@
+//! Action catalog for the macro driver.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+
+/// Build the macro action catalog. Macro names come from the live config,
+/// so the `name` param is free text.
+pub fn macro_catalog() -> Vec<ActionDescriptor> {
+    let action = |name: &str, label: &str, description: &str| {
+        ActionDescriptor::simple(name, label)
+            .with_description(description)
+            .with_param(ParamDescriptor::new("name", ParamKind::String))
+    };
+
+    vec![
+        action(
+            "run",
+            "Run",
+            "Start the macro (cancels the run in progress, if any).",
+        ),
+        action("stop", "Stop", "Cancel the running macro."),
+        action("toggle", "Toggle", "Stop when running, start otherwise."),
+    ]
+}
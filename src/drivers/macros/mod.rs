@@ -0,0 +1,298 @@
+//! Macro driver
+//!
+//! Runs the named step sequences of the `macros:` config section in the
+//! background, so pauses (`delay_ms`), signal waits (`wait_for`) and
+//! repeats never hold up the event loop. Steps go through the router like a
+//! button press: any driver action works, including another macro (which
+//! then runs alongside, not inline).
+//!
+//! Actions (`params: [name]`):
+//! - `run`: start the macro, cancelling a run already in progress
+//! - `stop`: cancel it
+//! - `toggle`: `stop` when running, `run` otherwise
+//!
+//! Each macro publishes `macro.<name>.running`. Definitions are read from
+//! the live config at each start, so profile reloads apply to the next run.
+
+mod catalog;
+
+use crate::config::MacroConfig;
+use crate::drivers::{Driver, ExecutionContext, IndicatorCallback, IndicatorEmitters};
+use crate::router::Router;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+
+/// Driver name used for `app: "macro"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "macro";
+
+/// Macro indicator signal names: `macro.<name>.<field>`.
+pub mod signals {
+    pub const PREFIX: &str = "macro";
+    /// `true` while the macro runs.
+    pub const RUNNING: &str = "running";
+
+    /// Full signal name for a macro field.
+    pub fn name(macro_name: &str, field: &str) -> String {
+        format!("{}.{}.{}", PREFIX, macro_name, field)
+    }
+}
+
+/// Parsed driver action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacroAction {
+    Run,
+    Stop,
+    Toggle,
+}
+
+impl MacroAction {
+    fn parse(action: &str) -> Result<Self> {
+        Ok(match action {
+            // `execute`: mappings that leave `action` out
+            "run" | "execute" => Self::Run,
+            "stop" => Self::Stop,
+            "toggle" => Self::Toggle,
+            _ => bail!("Unknown macro action '{}'", action),
+        })
+    }
+}
+
+/// A macro run, owned by its task.
+struct Running {
+    /// Tells this run apart from a later one under the same name.
+    generation: u64,
+    task: AbortHandle,
+}
+
+/// Shared handles for the macro tasks, cloned into each of them.
+#[derive(Clone)]
+struct Shared {
+    /// Weak: the router owns the drivers.
+    router: Weak<Router>,
+    /// Running macros by name. Signals for a macro are emitted while
+    /// holding this lock so a fast finish can't overtake its own start.
+    running: Arc<parking_lot::Mutex<HashMap<String, Running>>>,
+    next_generation: Arc<AtomicU64>,
+    indicators: IndicatorEmitters,
+}
+
+pub struct MacroDriver {
+    initialized: AtomicBool,
+    shared: Shared,
+}
+
+impl MacroDriver {
+    pub fn new(router: Weak<Router>) -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            shared: Shared {
+                router,
+                running: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                next_generation: Arc::new(AtomicU64::new(0)),
+                indicators: IndicatorEmitters::default(),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl Driver for MacroDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, ctx: ExecutionContext) -> Result<()> {
+        let names: Vec<String> = ctx
+            .config
+            .read()
+            .await
+            .macros
+            .as_ref()
+            .map(|macros| macros.keys().cloned().collect())
+            .unwrap_or_default();
+        info!("Macro driver initializing ({} macro(s))", names.len());
+        for name in &names {
+            self.shared
+                .indicators
+                .seed(&signals::name(name, signals::RUNNING), Value::Bool(false));
+        }
+        self.initialized.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!("Macro driver not initialized, dropping action '{}'", action);
+            return Ok(());
+        }
+
+        let action = MacroAction::parse(action)?;
+        let Some(name) = params.first().and_then(Value::as_str) else {
+            bail!("macro actions expect a macro name as first param");
+        };
+        let definition = ctx
+            .config
+            .read()
+            .await
+            .macros
+            .as_ref()
+            .and_then(|macros| macros.get(name).cloned())
+            .ok_or_else(|| anyhow!("macro: '{}' is not defined in `macros`", name))?;
+        if ctx.is_button_release() {
+            return Ok(());
+        }
+
+        let control_id = ctx
+            .control_id
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", DRIVER_NAME, name));
+        match action {
+            MacroAction::Run => self.shared.start(name, definition, control_id),
+            MacroAction::Stop => {
+                if !self.shared.stop(name) {
+                    debug!("macro.{}: not running, nothing to stop", name);
+                }
+            },
+            MacroAction::Toggle => {
+                if !self.shared.stop(name) {
+                    self.shared.start(name, definition, control_id);
+                }
+            },
+        }
+
+        if let Some(ref tracker) = ctx.activity_tracker {
+            tracker.record(DRIVER_NAME, crate::tray::ActivityDirection::Outbound);
+        }
+        Ok(())
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("Macro sync (no-op)");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        let names: Vec<String> = self.shared.running.lock().keys().cloned().collect();
+        for name in names {
+            self.shared.stop(&name);
+        }
+        self.shared.indicators.clear_subscribers();
+        debug!("Macro driver shut down");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("Macro driver: new indicator subscription");
+        self.shared.indicators.subscribe(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        catalog::macro_catalog()
+    }
+}
+
+impl Shared {
+    /// Start `name`, cancelling the run in progress if any.
+    fn start(&self, name: &str, definition: MacroConfig, control_id: String) {
+        let mut running = self.running.lock();
+        if let Some(previous) = running.remove(name) {
+            previous.task.abort();
+            debug!("macro.{}: retriggered, cancelling the current run", name);
+        }
+
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        let shared = self.clone();
+        let owned_name = name.to_string();
+        let task = tokio::spawn(async move {
+            shared.play(&owned_name, &definition, &control_id).await;
+            shared.finish(&owned_name, generation);
+        });
+        running.insert(
+            name.to_string(),
+            Running {
+                generation,
+                task: task.abort_handle(),
+            },
+        );
+        info!("macro.{}: started", name);
+        self.indicators
+            .emit_if_changed(&signals::name(name, signals::RUNNING), Value::Bool(true));
+    }
+
+    /// Cancel `name`. Returns whether it was running.
+    fn stop(&self, name: &str) -> bool {
+        let mut running = self.running.lock();
+        let Some(run) = running.remove(name) else {
+            return false;
+        };
+        run.task.abort();
+        info!("macro.{}: stopped", name);
+        self.indicators
+            .emit_if_changed(&signals::name(name, signals::RUNNING), Value::Bool(false));
+        true
+    }
+
+    /// End of a run: clear it unless a newer run replaced it meanwhile.
+    fn finish(&self, name: &str, generation: u64) {
+        let mut running = self.running.lock();
+        if running
+            .get(name)
+            .is_some_and(|run| run.generation == generation)
+        {
+            running.remove(name);
+            debug!("macro.{}: finished", name);
+            self.indicators
+                .emit_if_changed(&signals::name(name, signals::RUNNING), Value::Bool(false));
+        }
+    }
+
+    /// Play the steps `repeat` times (forever for 0).
+    async fn play(&self, name: &str, definition: &MacroConfig, control_id: &str) {
+        let mut pass: u32 = 0;
+        loop {
+            for (idx, step) in definition.steps.iter().enumerate() {
+                if let Some(ms) = step.delay_ms {
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                }
+                let Some(router) = self.router.upgrade() else {
+                    debug!("macro.{}: router gone, ending run", name);
+                    return;
+                };
+                if let Some(wait) = &step.wait_for {
+                    let timeout = wait.timeout_ms.map(Duration::from_millis);
+                    if !router.wait_for_condition(&wait.condition, timeout).await {
+                        warn!(
+                            "macro.{}: step {} timed out waiting for '{}', aborting",
+                            name, idx, wait.condition.signal
+                        );
+                        return;
+                    }
+                }
+                if let Some(action) = &step.action {
+                    if action.midi.is_some() {
+                        warn!(
+                            "macro.{}: step {} uses `midi` (unsupported in macros), skipping",
+                            name, idx
+                        );
+                        continue;
+                    }
+                    router.dispatch_synthetic_step(control_id, action).await;
+                }
+            }
+            pass = pass.saturating_add(1);
+            if definition.repeat != 0 && pass >= definition.repeat {
+                return;
+            }
+            // An endless loop must still let other tasks (and `stop`) in.
+            tokio::task::yield_now().await;
+        }
+    }
+}
@@ -20,6 +20,23 @@ use crate::midi::{find_port_by_substring, parse_message, MidiMessage};
 /// Callback type for MIDI feedback from applications
 pub type FeedbackCallback = Arc<dyn Fn(&[u8]) + Send + Sync>;
 
+/// Which ports a bridge opens. Set explicitly rather than inferred from an
+/// empty port name, which keeps matching the first enumerated port.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BridgeDirection {
+    /// Open both the output and the input port.
+    #[default]
+    Both,
+    /// Open the output port only; the input side stays closed.
+    OutputOnly,
+}
+
+impl BridgeDirection {
+    fn opens_in(self) -> bool {
+        self == BridgeDirection::Both
+    }
+}
+
 /// Cap on the per-port reconnect retry counter. Past this point the backoff
 /// delay is already saturated at 10s and further growth only risks overflow
 /// in the delay math or in metrics export.
@@ -48,6 +65,7 @@ pub struct MidiBridgeDriver {
     filter: Option<MidiFilterConfig>,
     transform: Option<TransformConfig>,
     optional: bool,
+    direction: BridgeDirection,
 
     // MIDI ports (wrapped in Arc<Mutex<>> for interior mutability)
     // Note: Using Arc<Mutex<>> instead of Arc<tokio::sync::Mutex<>> for non-async interior mutability
@@ -105,6 +123,7 @@ impl MidiBridgeDriver {
             filter,
             transform,
             optional,
+            direction: BridgeDirection::Both,
             midi_out: Arc::new(Mutex::new(None)),
             midi_in: Arc::new(Mutex::new(None)),
             feedback_callback: Arc::new(Mutex::new(None)),
@@ -122,6 +141,12 @@ impl MidiBridgeDriver {
         }
     }
 
+    /// Restrict which ports `init` opens (both by default).
+    pub fn with_direction(mut self, direction: BridgeDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
     /// Clone every shared `Arc` handle for use in a spawned background task.
     /// Centralises the field-by-field clone so adding a shared field only
     /// needs updating one place (this was previously duplicated inline twice
@@ -134,6 +159,7 @@ impl MidiBridgeDriver {
             filter: self.filter.clone(),
             transform: self.transform.clone(),
             optional: self.optional,
+            direction: self.direction,
             midi_out: self.midi_out.clone(),
             midi_in: self.midi_in.clone(),
             feedback_callback: self.feedback_callback.clone(),
@@ -163,7 +189,7 @@ impl MidiBridgeDriver {
 
     /// Spawn the IN reconnect loop unless one is already running.
     fn spawn_in_reconnect(&self) {
-        if self.reconnecting_in.swap(true, Ordering::AcqRel) {
+        if !self.direction.opens_in() || self.reconnecting_in.swap(true, Ordering::AcqRel) {
             return; // a loop is already running
         }
         let me = self.clone_handle();
@@ -240,8 +266,9 @@ impl MidiBridgeDriver {
 
     /// Check current connection status based on port states
     fn compute_status(&self) -> crate::tray::ConnectionStatus {
+        // A closed input side (output-only bridge) never blocks `Connected`.
         let out_connected = self.midi_out.lock().is_some();
-        let in_connected = self.midi_in.lock().is_some();
+        let in_connected = !self.direction.opens_in() || self.midi_in.lock().is_some();
 
         if out_connected && in_connected {
             crate::tray::ConnectionStatus::Connected
@@ -294,6 +321,9 @@ impl MidiBridgeDriver {
 
     /// Try to open the input port once
     fn try_open_in(&self) -> Result<()> {
+        if !self.direction.opens_in() {
+            return Ok(()); // output-only bridge
+        }
         let midi_in = midir::MidiInput::new("XTouch-GW-Bridge-In")?;
 
         let port = find_port_by_substring(&midi_in, &self.from_port)
@@ -0,0 +1,25 @@
+//! Static action catalog for the MMC driver.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the static MMC action catalog.
+pub fn mmc_catalog() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::simple("play", "Play"),
+        ActionDescriptor::simple("stop", "Stop"),
+        ActionDescriptor::simple("pause", "Pause"),
+        ActionDescriptor::simple("record", "Record")
+            .with_description("Record strobe: punch in (starts playback if stopped)."),
+        ActionDescriptor::simple("record_exit", "Record exit")
+            .with_description("Punch out, keep playing."),
+        ActionDescriptor::simple("fast_forward", "Fast forward"),
+        ActionDescriptor::simple("rewind", "Rewind"),
+        ActionDescriptor::simple("locate", "Locate")
+            .with_description("Jump to a position: \"HH:MM:SS:FF\" or a number of seconds.")
+            .with_param(
+                ParamDescriptor::new("position", ParamKind::String)
+                    .with_default(json!("00:00:00:00")),
+            ),
+    ]
+}
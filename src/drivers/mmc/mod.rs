@@ -0,0 +1,441 @@
+//! MIDI Machine Control / MIDI Time Code driver
+//!
+//! Outbound: MMC transport commands (`play`, `stop`, `record`, `locate`,
+//! ...) as real-time SysEx on a MIDI port, so the X-Touch transport section
+//! drives a DAW or video player. Port handling and reconnection are those
+//! of [`MidiBridgeDriver`], wrapped rather than re-implemented.
+//!
+//! Timecode (`mmc.mtc`):
+//! - `generate`: an MTC clock follows the MMC transport and sends quarter
+//!   frames (plus a full frame on `locate`)
+//! - `receive`: quarter frames / full frames read from `input_port`
+//!
+//! Either way the position is published as the `mtc.position` signal and,
+//! with `mmc.display`, on the 7-segment timecode display.
+
+mod catalog;
+mod protocol;
+mod timecode;
+
+use crate::config::{MmcConfig, MtcMode};
+use crate::drivers::midibridge::{BridgeDirection, MidiBridgeDriver};
+use crate::drivers::{Driver, ExecutionContext, IndicatorCallback, IndicatorEmitters};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::{debug, info, trace, warn};
+
+use protocol::MmcCommand;
+use timecode::{FrameRate, QuarterFrameDecoder, QuarterFrameGenerator, Timecode};
+
+/// Driver name used for `app: "mmc"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "mmc";
+
+/// MMC / MTC indicator signal names (shared between emitter and consumers).
+pub mod signals {
+    /// Current timecode, `"HH:MM:SS:FF"` (generated or received).
+    pub const POSITION: &str = "mtc.position";
+    /// Last transport command sent: `"play"` | `"stop"` | `"pause"` |
+    /// `"record"` | `"fast_forward"` | `"rewind"`.
+    pub const STATE: &str = "mmc.state";
+}
+
+/// Generator tick. Quarter frames are scheduled from elapsed time, so the
+/// tick only bounds jitter (a quarter frame is 8.3-10.4 ms).
+const GENERATOR_TICK_MS: u64 = 5;
+
+/// Bounded capacity for raw MIDI handed over from the port callback.
+const INPUT_QUEUE: usize = 256;
+
+/// MTC clock driven by the MMC transport (`mtc: generate`).
+#[derive(Debug)]
+struct Clock {
+    /// Position while stopped; start point of the running generator.
+    position: Timecode,
+    /// `Some` while running.
+    started_at: Option<Instant>,
+    generator: QuarterFrameGenerator,
+    /// Send a full frame on the next tick (after a locate).
+    full_frame_pending: bool,
+}
+
+impl Clock {
+    fn new(rate: FrameRate) -> Self {
+        Self {
+            position: Timecode::default(),
+            started_at: None,
+            generator: QuarterFrameGenerator::new(Timecode::default(), rate),
+            full_frame_pending: true,
+        }
+    }
+
+    fn current(&self) -> Timecode {
+        match self.started_at {
+            Some(at) => self.generator.position(at.elapsed()),
+            None => self.position,
+        }
+    }
+
+    fn start(&mut self, rate: FrameRate) {
+        if self.started_at.is_none() {
+            self.generator = QuarterFrameGenerator::new(self.position, rate);
+            self.started_at = Some(Instant::now());
+        }
+    }
+
+    fn stop(&mut self) {
+        self.position = self.current();
+        self.started_at = None;
+    }
+
+    fn locate(&mut self, tc: Timecode, rate: FrameRate) {
+        self.position = tc;
+        self.full_frame_pending = true;
+        if self.started_at.is_some() {
+            self.generator = QuarterFrameGenerator::new(tc, rate);
+            self.started_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Shared handles for the background tasks, cloned into each of them.
+#[derive(Clone)]
+struct Shared {
+    config: Arc<MmcConfig>,
+    rate: FrameRate,
+    bridge: Arc<MidiBridgeDriver>,
+    /// Stops the tasks of the current `init`: signalled on `shutdown()`,
+    /// replaced (so dropped) by the next `init`.
+    task_shutdown: Arc<parking_lot::Mutex<Option<watch::Sender<bool>>>>,
+    router: Arc<RwLock<Option<Arc<crate::router::Router>>>>,
+    led_tx: Arc<RwLock<Option<mpsc::Sender<Vec<u8>>>>>,
+    clock: Arc<parking_lot::Mutex<Clock>>,
+    indicators: IndicatorEmitters,
+    /// Last published position, re-rendered after page changes.
+    last_position: Arc<parking_lot::Mutex<Option<Timecode>>>,
+}
+
+pub struct MmcDriver {
+    initialized: AtomicBool,
+    shared: Shared,
+}
+
+impl MmcDriver {
+    pub fn new(config: MmcConfig) -> Self {
+        let rate = FrameRate::from_config(config.fps, config.drop_frame);
+        // Only `receive` needs the input side.
+        let direction = match config.mtc {
+            MtcMode::Receive => BridgeDirection::Both,
+            MtcMode::Off | MtcMode::Generate => BridgeDirection::OutputOnly,
+        };
+        let bridge = Arc::new(
+            MidiBridgeDriver::new(
+                config.output_port.clone(),
+                config.input_port.clone().unwrap_or_default(),
+                None,
+                None,
+                true,
+            )
+            .with_direction(direction),
+        );
+        Self {
+            initialized: AtomicBool::new(false),
+            shared: Shared {
+                config: Arc::new(config),
+                rate,
+                bridge,
+                task_shutdown: Arc::new(parking_lot::Mutex::new(None)),
+                router: Arc::new(RwLock::new(None)),
+                led_tx: Arc::new(RwLock::new(None)),
+                clock: Arc::new(parking_lot::Mutex::new(Clock::new(rate))),
+                indicators: IndicatorEmitters::default(),
+                last_position: Arc::new(parking_lot::Mutex::new(None)),
+            },
+        }
+    }
+
+    /// Wire the driver to the router (page changes re-render the display).
+    pub async fn set_router(&self, router: Arc<crate::router::Router>) {
+        *self.shared.router.write().await = Some(router);
+    }
+
+    /// Wire the driver to the LED MIDI channel (7-segment display).
+    pub async fn set_led_sender(&self, tx: mpsc::Sender<Vec<u8>>) {
+        *self.shared.led_tx.write().await = Some(tx);
+    }
+}
+
+#[async_trait]
+impl Driver for MmcDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, ctx: ExecutionContext) -> Result<()> {
+        info!(
+            "MMC driver initializing (port '{}', mtc {:?})",
+            self.shared.config.output_port, self.shared.config.mtc
+        );
+        // A fresh channel per init so the same instance survives an
+        // unregister/re-register cycle across profile switches; dropping
+        // the previous sender stops the previous init's tasks.
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        *self.shared.task_shutdown.lock() = Some(shutdown_tx);
+
+        if self.shared.config.mtc == MtcMode::Receive {
+            self.shared.spawn_receiver(shutdown_rx.clone());
+        }
+        self.shared.bridge.init(ctx).await?;
+        if self.shared.config.mtc == MtcMode::Generate {
+            self.shared.spawn_generator(shutdown_rx.clone());
+        }
+        if self.shared.config.display {
+            self.shared.spawn_page_watcher(shutdown_rx).await;
+        }
+
+        self.initialized.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!("MMC driver not initialized, dropping action '{}'", action);
+            return Ok(());
+        }
+
+        let command = protocol::parse_action(action, &params, self.shared.rate)?;
+        if ctx.is_button_release() {
+            return Ok(());
+        }
+
+        let sysex = command.to_sysex(self.shared.config.device_id, self.shared.rate);
+        debug!("MMC {:?} -> {:02X?}", command, sysex);
+        self.shared.bridge.send_message(&sysex)?;
+        if let Some(ref tracker) = ctx.activity_tracker {
+            tracker.record(DRIVER_NAME, crate::tray::ActivityDirection::Outbound);
+        }
+
+        if self.shared.config.mtc == MtcMode::Generate {
+            let mut clock = self.shared.clock.lock();
+            match command {
+                MmcCommand::Play | MmcCommand::DeferredPlay | MmcCommand::RecordStrobe => {
+                    clock.start(self.shared.rate)
+                },
+                MmcCommand::Stop | MmcCommand::Pause | MmcCommand::Reset | MmcCommand::Eject => {
+                    clock.stop()
+                },
+                MmcCommand::Locate(tc) => clock.locate(tc, self.shared.rate),
+                MmcCommand::FastForward | MmcCommand::RecordExit | MmcCommand::Rewind => {},
+            }
+        }
+
+        if let Some(state) = command.state() {
+            self.shared
+                .indicators
+                .emit_if_changed(signals::STATE, Value::String(state.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("MMC sync (no-op)");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        if let Some(tx) = self.shared.task_shutdown.lock().take() {
+            let _ = tx.send(true);
+        }
+        self.shared.bridge.shutdown().await?;
+        self.shared.indicators.clear_subscribers();
+        debug!("MMC driver shut down");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("MMC driver: new indicator subscription");
+        self.shared.indicators.subscribe(callback);
+    }
+
+    fn connection_status(&self) -> crate::tray::ConnectionStatus {
+        self.shared.bridge.connection_status()
+    }
+
+    fn subscribe_connection_status(&self, callback: crate::tray::StatusCallback) {
+        self.shared.bridge.subscribe_connection_status(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        catalog::mmc_catalog()
+    }
+}
+
+impl Shared {
+    /// Run the MTC clock: send due quarter frames and publish the position
+    /// once per frame.
+    fn spawn_generator(&self, mut shutdown_rx: watch::Receiver<bool>) {
+        let shared = self.clone();
+        tokio::spawn(async move {
+            debug!("MTC generator started ({:?})", shared.rate);
+            let mut ticker = tokio::time::interval(Duration::from_millis(GENERATOR_TICK_MS));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            debug!("MTC generator exiting (shutdown)");
+                            break;
+                        }
+                        continue;
+                    }
+                    _ = ticker.tick() => {}
+                }
+
+                let (pieces, full_frame, position) = {
+                    let mut clock = shared.clock.lock();
+                    let pieces = match clock.started_at {
+                        Some(at) => clock.generator.due(at.elapsed()),
+                        None => Vec::new(),
+                    };
+                    let position = clock.current();
+                    let full_frame = std::mem::take(&mut clock.full_frame_pending)
+                        .then(|| timecode::full_frame_sysex(position, shared.rate));
+                    (pieces, full_frame, position)
+                };
+
+                // Send failures already trigger the bridge's reconnect loop;
+                // the clock keeps running and resumes output once it's back.
+                if let Some(msg) = full_frame {
+                    let _ = shared.bridge.send_message(&msg);
+                }
+                for data in pieces {
+                    if shared.bridge.send_message(&[0xF1, data]).is_err() {
+                        break;
+                    }
+                }
+                shared.publish_position(position).await;
+            }
+        });
+    }
+
+    /// Decode MTC from the input port. The port callback runs on the MIDI
+    /// thread, so it only queues the bytes for this task.
+    fn spawn_receiver(&self, mut shutdown_rx: watch::Receiver<bool>) {
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(INPUT_QUEUE);
+        self.bridge
+            .set_feedback_callback(Arc::new(move |data: &[u8]| {
+                if matches!(data.first(), Some(0xF0 | 0xF1)) && tx.try_send(data.to_vec()).is_err()
+                {
+                    trace!("MTC input queue full, dropping {:02X?}", data);
+                }
+            }));
+
+        let shared = self.clone();
+        tokio::spawn(async move {
+            let mut decoder = QuarterFrameDecoder::default();
+            loop {
+                let data = tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            break;
+                        }
+                        continue;
+                    }
+                    data = rx.recv() => match data {
+                        Some(data) => data,
+                        None => break,
+                    },
+                };
+                let position = match data.as_slice() {
+                    [0xF1, qf, ..] => decoder.push(*qf).map(|(tc, _)| tc),
+                    [0xF0, body @ .., 0xF7] => {
+                        timecode::parse_full_frame(body).map(|(tc, rate)| {
+                            decoder.set(tc, rate);
+                            tc
+                        })
+                    },
+                    _ => None,
+                };
+                if let Some(tc) = position {
+                    shared.publish_position(tc).await;
+                }
+            }
+            debug!("MTC receiver stopped");
+        });
+    }
+
+    /// Re-render the display every time a page is activated: the page
+    /// refresh writes the page name to the 7-segment display.
+    async fn spawn_page_watcher(&self, mut shutdown_rx: watch::Receiver<bool>) {
+        let router_arc = self.router.read().await.clone();
+        let Some(router) = router_arc else {
+            debug!("MMC: no router wired, skipping page watcher");
+            return;
+        };
+        let Some(live_tx) = router.live_tx_snapshot().await else {
+            debug!("MMC: live_tx not yet wired, skipping page watcher");
+            return;
+        };
+        let mut rx = live_tx.subscribe();
+        let shared = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            break;
+                        }
+                        continue;
+                    }
+                    recv = rx.recv() => match recv {
+                        Ok(ev) => ev,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                if !matches!(event, crate::event_bus::LiveEvent::PageChanged { .. }) {
+                    continue;
+                }
+                let position = *shared.last_position.lock();
+                if let Some(tc) = position {
+                    shared.render_display(tc).await;
+                }
+            }
+        });
+    }
+
+    /// Emit `mtc.position` and update the display when the frame changed.
+    async fn publish_position(&self, tc: Timecode) {
+        {
+            let mut last = self.last_position.lock();
+            if *last == Some(tc) {
+                return;
+            }
+            *last = Some(tc);
+        }
+        self.indicators
+            .emit_if_changed(signals::POSITION, Value::String(tc.to_string()));
+        if self.config.display {
+            self.render_display(tc).await;
+        }
+    }
+
+    async fn render_display(&self, tc: Timecode) {
+        let Some(tx) = self.led_tx.read().await.clone() else {
+            return;
+        };
+        let (text, dots) = tc.seven_segment();
+        for msg in crate::xtouch::build_seven_segment_sysex(&text, dots) {
+            if tx.send(msg).await.is_err() {
+                debug!("MMC display: led_tx closed, dropping render");
+                return;
+            }
+        }
+    }
+}
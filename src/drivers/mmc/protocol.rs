@@ -0,0 +1,142 @@
+//! MIDI Machine Control commands and YAML action parsing.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::timecode::{FrameRate, Timecode};
+
+/// MMC command (sub-ID #2 of the real-time `06` command set).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MmcCommand {
+    Stop,
+    Play,
+    DeferredPlay,
+    FastForward,
+    Rewind,
+    RecordStrobe,
+    RecordExit,
+    Pause,
+    Eject,
+    Reset,
+    Locate(Timecode),
+}
+
+impl MmcCommand {
+    /// `F0 7F <device> 06 <command> [...] F7`.
+    pub(super) fn to_sysex(self, device_id: u8, rate: FrameRate) -> Vec<u8> {
+        let mut msg = vec![0xF0, 0x7F, device_id & 0x7F, 0x06];
+        match self {
+            Self::Locate(tc) => {
+                // Locate, 6 bytes follow, TARGET subcommand, standard time
+                // (hours byte carries the rate, subframes = 0).
+                msg.extend_from_slice(&[
+                    0x44,
+                    0x06,
+                    0x01,
+                    (rate.code() << 5) | tc.hours,
+                    tc.minutes,
+                    tc.seconds,
+                    tc.frames,
+                    0x00,
+                ]);
+            },
+            other => msg.push(other.code()),
+        }
+        msg.push(0xF7);
+        msg
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            Self::Stop => 0x01,
+            Self::Play => 0x02,
+            Self::DeferredPlay => 0x03,
+            Self::FastForward => 0x04,
+            Self::Rewind => 0x05,
+            Self::RecordStrobe => 0x06,
+            Self::RecordExit => 0x07,
+            Self::Pause => 0x09,
+            Self::Eject => 0x0A,
+            Self::Reset => 0x0D,
+            Self::Locate(_) => 0x44,
+        }
+    }
+
+    /// Value of the `mmc.state` signal after sending this command
+    /// (`None` = leaves the state unchanged).
+    pub(super) fn state(self) -> Option<&'static str> {
+        match self {
+            Self::Stop | Self::Reset | Self::Eject => Some("stop"),
+            Self::Play | Self::DeferredPlay | Self::RecordExit => Some("play"),
+            Self::FastForward => Some("fast_forward"),
+            Self::Rewind => Some("rewind"),
+            Self::RecordStrobe => Some("record"),
+            Self::Pause => Some("pause"),
+            Self::Locate(_) => None,
+        }
+    }
+}
+
+/// Parse `(action, params)` into an MMC command. `locate` takes a
+/// `"HH:MM:SS:FF"` string or a number of seconds (default: zero).
+pub(super) fn parse_action(action: &str, params: &[Value], rate: FrameRate) -> Result<MmcCommand> {
+    Ok(match action {
+        "play" => MmcCommand::Play,
+        "deferred_play" => MmcCommand::DeferredPlay,
+        "stop" => MmcCommand::Stop,
+        "pause" => MmcCommand::Pause,
+        "record" | "record_strobe" => MmcCommand::RecordStrobe,
+        "record_exit" => MmcCommand::RecordExit,
+        "fast_forward" | "ffwd" => MmcCommand::FastForward,
+        "rewind" | "rew" => MmcCommand::Rewind,
+        "eject" => MmcCommand::Eject,
+        "reset" => MmcCommand::Reset,
+        "locate" => MmcCommand::Locate(match params.first() {
+            None => Timecode::default(),
+            Some(Value::String(s)) => Timecode::parse(s, rate)?,
+            Some(v) => match v.as_f64() {
+                Some(seconds) => Timecode::from_seconds(seconds, rate),
+                None => bail!("mmc.locate expects \"HH:MM:SS:FF\" or seconds, got {}", v),
+            },
+        }),
+        _ => bail!("Unknown mmc action '{}'", action),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn transport_sysex() {
+        let play = parse_action("play", &[], FrameRate::Fps25).unwrap();
+        assert_eq!(
+            play.to_sysex(0x7F, FrameRate::Fps25),
+            vec![0xF0, 0x7F, 0x7F, 0x06, 0x02, 0xF7]
+        );
+        let rec = parse_action("record", &[], FrameRate::Fps25).unwrap();
+        assert_eq!(
+            rec.to_sysex(0x10, FrameRate::Fps25),
+            vec![0xF0, 0x7F, 0x10, 0x06, 0x06, 0xF7]
+        );
+        assert_eq!(rec.state(), Some("record"));
+        assert!(parse_action("jump", &[], FrameRate::Fps25).is_err());
+    }
+
+    #[test]
+    fn locate_from_timecode_or_seconds() {
+        let rate = FrameRate::Fps25;
+        let cmd = parse_action("locate", &[json!("01:00:10:12")], rate).unwrap();
+        assert_eq!(
+            cmd.to_sysex(0x7F, rate),
+            vec![0xF0, 0x7F, 0x7F, 0x06, 0x44, 0x06, 0x01, 0x21, 0x00, 0x0A, 0x0C, 0x00, 0xF7]
+        );
+        let MmcCommand::Locate(tc) = parse_action("locate", &[json!(61.48)], rate).unwrap() else {
+            panic!("expected locate");
+        };
+        assert_eq!(tc.to_string(), "00:01:01:12");
+        assert!(parse_action("locate", &[json!(true)], rate).is_err());
+        assert!(parse_action("locate", &[json!("1:2")], rate).is_err());
+    }
+}
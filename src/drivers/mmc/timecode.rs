@@ -0,0 +1,414 @@
+//! SMPTE timecode arithmetic and MIDI Time Code framing.
+//!
+//! Pure, clock-free helpers: timecode <-> frame-count conversion
+//! (including 29.97 drop-frame), quarter-frame generation and decoding,
+//! full-frame SysEx, and the 7-segment layout. The driver owns the clock.
+
+use anyhow::{bail, Result};
+use std::fmt;
+use std::time::Duration;
+
+/// MTC frame rate. The discriminant is the 2-bit MTC rate code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FrameRate {
+    Fps24 = 0,
+    Fps25 = 1,
+    Fps2997Df = 2,
+    Fps30 = 3,
+}
+
+impl FrameRate {
+    /// From the YAML `fps` / `drop_frame` pair (validated at config load).
+    pub(super) fn from_config(fps: u8, drop_frame: bool) -> Self {
+        match (fps, drop_frame) {
+            (24, _) => Self::Fps24,
+            (25, _) => Self::Fps25,
+            (30, true) => Self::Fps2997Df,
+            _ => Self::Fps30,
+        }
+    }
+
+    pub(super) fn from_code(code: u8) -> Self {
+        match code & 0x03 {
+            0 => Self::Fps24,
+            1 => Self::Fps25,
+            2 => Self::Fps2997Df,
+            _ => Self::Fps30,
+        }
+    }
+
+    pub(super) fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Frame numbers per second (30 for drop-frame).
+    pub(super) fn nominal(self) -> u64 {
+        match self {
+            Self::Fps24 => 24,
+            Self::Fps25 => 25,
+            Self::Fps2997Df | Self::Fps30 => 30,
+        }
+    }
+
+    /// Wall-clock frames per second.
+    pub(super) fn real_fps(self) -> f64 {
+        match self {
+            Self::Fps2997Df => 30_000.0 / 1_001.0,
+            other => other.nominal() as f64,
+        }
+    }
+
+    /// Frame count of 24 hours, where the timecode wraps.
+    fn frames_per_day(self) -> u64 {
+        match self {
+            // 30 fps minus 2 dropped numbers in 54 of every 60 minutes.
+            Self::Fps2997Df => 24 * (30 * 3600 - 2 * 54),
+            other => 24 * 3600 * other.nominal(),
+        }
+    }
+}
+
+/// `HH:MM:SS:FF` position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+}
+
+impl Timecode {
+    /// Parse `HH:MM:SS:FF` (`;` accepted before the frames, as written for
+    /// drop-frame). Fields are range-checked against `rate`.
+    pub(super) fn parse(s: &str, rate: FrameRate) -> Result<Self> {
+        let parts: Vec<&str> = s.trim().split([':', ';']).collect();
+        let [h, m, sec, f] = parts.as_slice() else {
+            bail!("Invalid timecode '{}' (expected HH:MM:SS:FF)", s);
+        };
+        let field = |v: &str, max: u64| -> Result<u8> {
+            match v.parse::<u64>() {
+                Ok(n) if n < max => Ok(n as u8),
+                _ => bail!("Invalid timecode '{}' (field '{}' out of range)", s, v),
+            }
+        };
+        Ok(Self {
+            hours: field(h, 24)?,
+            minutes: field(m, 60)?,
+            seconds: field(sec, 60)?,
+            frames: field(f, rate.nominal())?,
+        })
+    }
+
+    /// Frames since `00:00:00:00`.
+    pub(super) fn to_frames(self, rate: FrameRate) -> u64 {
+        let nominal = rate.nominal();
+        let (h, m, s, f) = (
+            self.hours as u64,
+            self.minutes as u64,
+            self.seconds as u64,
+            self.frames as u64,
+        );
+        let frames = ((h * 3600 + m * 60 + s) * nominal) + f;
+        if rate == FrameRate::Fps2997Df {
+            let total_minutes = h * 60 + m;
+            frames - 2 * (total_minutes - total_minutes / 10)
+        } else {
+            frames
+        }
+    }
+
+    /// Inverse of [`Timecode::to_frames`], wrapping at 24 hours.
+    pub(super) fn from_frames(frames: u64, rate: FrameRate) -> Self {
+        let mut frames = frames % rate.frames_per_day();
+        if rate == FrameRate::Fps2997Df {
+            // Re-insert the dropped frame numbers: 2 per minute, except
+            // every tenth minute (17982 frames per 10 minutes).
+            let tens = frames / 17_982;
+            let rem = frames % 17_982;
+            frames += 18 * tens;
+            if rem >= 2 {
+                frames += 2 * ((rem - 2) / 1_798);
+            }
+        }
+        let nominal = rate.nominal();
+        Self {
+            hours: (frames / (3600 * nominal)) as u8,
+            minutes: (frames / (60 * nominal) % 60) as u8,
+            seconds: (frames / nominal % 60) as u8,
+            frames: (frames % nominal) as u8,
+        }
+    }
+
+    /// Position after `seconds` of wall-clock time (negative clamps at 0).
+    pub(super) fn from_seconds(seconds: f64, rate: FrameRate) -> Self {
+        let frames = (seconds.max(0.0) * rate.real_fps()).round() as u64;
+        Self::from_frames(frames, rate)
+    }
+
+    /// 12-digit text and dot mask for the X-Touch timecode display:
+    /// hours right-aligned in the 3-digit "bars" field, frames in the
+    /// 3-digit "ticks" field, dots between the groups.
+    pub(super) fn seven_segment(self) -> (String, u16) {
+        let text = format!(
+            "   {:02}{:02}{:02} {:02}",
+            self.hours, self.minutes, self.seconds, self.frames
+        );
+        (text, (1 << 4) | (1 << 6) | (1 << 8))
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}:{:02}",
+            self.hours, self.minutes, self.seconds, self.frames
+        )
+    }
+}
+
+/// Data byte of quarter-frame `piece` (0..=7) describing `tc`.
+pub(super) fn quarter_frame(piece: u8, tc: Timecode, rate: FrameRate) -> u8 {
+    let nibble = match piece {
+        0 => tc.frames & 0x0F,
+        1 => tc.frames >> 4,
+        2 => tc.seconds & 0x0F,
+        3 => tc.seconds >> 4,
+        4 => tc.minutes & 0x0F,
+        5 => tc.minutes >> 4,
+        6 => tc.hours & 0x0F,
+        _ => (tc.hours >> 4) | (rate.code() << 1),
+    };
+    ((piece & 0x07) << 4) | (nibble & 0x0F)
+}
+
+/// Full-frame message (`F0 7F <dev> 01 01 hr mn sc fr F7`), sent on
+/// locate so receivers jump without waiting for two frames of pieces.
+pub(super) fn full_frame_sysex(tc: Timecode, rate: FrameRate) -> Vec<u8> {
+    vec![
+        0xF0,
+        0x7F,
+        0x7F,
+        0x01,
+        0x01,
+        (rate.code() << 5) | tc.hours,
+        tc.minutes,
+        tc.seconds,
+        tc.frames,
+        0xF7,
+    ]
+}
+
+/// Parse a full-frame message (SysEx body, without `F0` / `F7`).
+pub(super) fn parse_full_frame(body: &[u8]) -> Option<(Timecode, FrameRate)> {
+    let [0x7F, _device, 0x01, 0x01, hr, mn, sc, fr] = body else {
+        return None;
+    };
+    Some((
+        Timecode {
+            hours: hr & 0x1F,
+            minutes: *mn,
+            seconds: *sc,
+            frames: *fr,
+        },
+        FrameRate::from_code(hr >> 5),
+    ))
+}
+
+/// Quarter frames scheduled against elapsed running time.
+///
+/// The clock only asks "what is due after `elapsed`?", so a coarse or
+/// jittery tick still yields the exact average rate (4 pieces per frame).
+/// The timecode carried by a cycle is latched at its piece 0, per spec.
+#[derive(Debug, Clone)]
+pub(super) struct QuarterFrameGenerator {
+    rate: FrameRate,
+    start_frames: u64,
+    sent: u64,
+    latched: Timecode,
+}
+
+/// Beyond this backlog (two full cycles) the generator skips ahead rather
+/// than bursting stale pieces, e.g. after the host was suspended.
+const MAX_BACKLOG: u64 = 16;
+
+impl QuarterFrameGenerator {
+    pub(super) fn new(start: Timecode, rate: FrameRate) -> Self {
+        Self {
+            rate,
+            start_frames: start.to_frames(rate),
+            sent: 0,
+            latched: start,
+        }
+    }
+
+    /// Position `elapsed` after the start.
+    pub(super) fn position(&self, elapsed: Duration) -> Timecode {
+        let frames = (elapsed.as_secs_f64() * self.rate.real_fps()) as u64;
+        Timecode::from_frames(self.start_frames + frames, self.rate)
+    }
+
+    /// Quarter-frame data bytes due by `elapsed`.
+    pub(super) fn due(&mut self, elapsed: Duration) -> Vec<u8> {
+        let due = (elapsed.as_secs_f64() * self.rate.real_fps() * 4.0) as u64;
+        if due.saturating_sub(self.sent) > MAX_BACKLOG {
+            self.sent = due - due % 8;
+        }
+        let mut out = Vec::new();
+        while self.sent < due {
+            let piece = (self.sent % 8) as u8;
+            if piece == 0 {
+                self.latched = Timecode::from_frames(self.start_frames + self.sent / 4, self.rate);
+            }
+            out.push(quarter_frame(piece, self.latched, self.rate));
+            self.sent += 1;
+        }
+        out
+    }
+}
+
+/// Reassembles incoming quarter frames.
+#[derive(Debug, Default)]
+pub(super) struct QuarterFrameDecoder {
+    nibbles: [u8; 8],
+    /// Bit per piece received since the last piece 0.
+    seen: u8,
+    last: Option<(Timecode, FrameRate)>,
+}
+
+impl QuarterFrameDecoder {
+    /// Feed one quarter-frame data byte; returns the current position when
+    /// it advances. A complete cycle (pieces 0..=7) is two frames old when
+    /// its last piece arrives, and piece 3 of the next cycle marks one more
+    /// frame, so the position updates once per frame.
+    pub(super) fn push(&mut self, data: u8) -> Option<(Timecode, FrameRate)> {
+        let piece = (data >> 4) & 0x07;
+        if piece == 0 {
+            self.seen = 0;
+        }
+        self.nibbles[piece as usize] = data & 0x0F;
+        self.seen |= 1 << piece;
+
+        match piece {
+            3 if self.seen == 0x0F => {
+                let (tc, rate) = self.last?;
+                let next = Timecode::from_frames(tc.to_frames(rate) + 1, rate);
+                self.last = Some((next, rate));
+                self.last
+            },
+            7 if self.seen == 0xFF => {
+                let n = &self.nibbles;
+                let rate = FrameRate::from_code(n[7] >> 1);
+                let tc = Timecode {
+                    frames: n[0] | (n[1] << 4),
+                    seconds: n[2] | (n[3] << 4),
+                    minutes: n[4] | (n[5] << 4),
+                    hours: n[6] | ((n[7] & 0x01) << 4),
+                };
+                let now = Timecode::from_frames(tc.to_frames(rate) + 2, rate);
+                self.last = Some((now, rate));
+                self.last
+            },
+            _ => None,
+        }
+    }
+
+    /// Jump to a full-frame position.
+    pub(super) fn set(&mut self, tc: Timecode, rate: FrameRate) {
+        self.seen = 0;
+        self.last = Some((tc, rate));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tc(h: u8, m: u8, s: u8, f: u8) -> Timecode {
+        Timecode {
+            hours: h,
+            minutes: m,
+            seconds: s,
+            frames: f,
+        }
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let t = Timecode::parse("01:02:03;04", FrameRate::Fps2997Df).unwrap();
+        assert_eq!(t, tc(1, 2, 3, 4));
+        assert_eq!(t.to_string(), "01:02:03:04");
+        assert!(Timecode::parse("00:00:00:25", FrameRate::Fps25).is_err());
+        assert!(Timecode::parse("00:00:00", FrameRate::Fps25).is_err());
+    }
+
+    #[test]
+    fn non_drop_frame_conversion() {
+        let t = tc(1, 0, 0, 1);
+        assert_eq!(t.to_frames(FrameRate::Fps25), 90_001);
+        assert_eq!(Timecode::from_frames(90_001, FrameRate::Fps25), t);
+        assert_eq!(
+            Timecode::from_frames(24 * 3600 * 25, FrameRate::Fps25),
+            tc(0, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn drop_frame_skips_two_numbers_per_minute() {
+        let df = FrameRate::Fps2997Df;
+        assert_eq!(Timecode::from_frames(1_799, df), tc(0, 0, 59, 29));
+        assert_eq!(Timecode::from_frames(1_800, df), tc(0, 1, 0, 2));
+        // Every tenth minute keeps frames 00 and 01.
+        assert_eq!(Timecode::from_frames(17_982, df), tc(0, 10, 0, 0));
+        for t in [tc(0, 1, 0, 2), tc(0, 10, 0, 0), tc(23, 59, 59, 29)] {
+            assert_eq!(Timecode::from_frames(t.to_frames(df), df), t);
+        }
+    }
+
+    #[test]
+    fn generator_latches_and_decoder_reassembles() {
+        let rate = FrameRate::Fps25;
+        let mut generator = QuarterFrameGenerator::new(tc(10, 20, 30, 5), rate);
+        // 8 pieces = 2 frames = 80 ms at 25 fps.
+        let pieces = generator.due(Duration::from_millis(80));
+        assert_eq!(pieces.len(), 8);
+        assert_eq!(pieces[0], 0x05);
+        assert_eq!(pieces[7], 0x70 | (rate.code() << 1));
+
+        let mut decoder = QuarterFrameDecoder::default();
+        let decoded: Vec<_> = pieces.iter().filter_map(|&b| decoder.push(b)).collect();
+        assert_eq!(decoded, vec![(tc(10, 20, 30, 7), rate)]);
+
+        // Next cycle: piece 3 advances one frame before the cycle completes.
+        let pieces = generator.due(Duration::from_millis(160));
+        let decoded: Vec<_> = pieces.iter().filter_map(|&b| decoder.push(b)).collect();
+        assert_eq!(
+            decoded,
+            vec![(tc(10, 20, 30, 8), rate), (tc(10, 20, 30, 9), rate)]
+        );
+    }
+
+    #[test]
+    fn generator_skips_large_backlog() {
+        let mut generator = QuarterFrameGenerator::new(Timecode::default(), FrameRate::Fps25);
+        let pieces = generator.due(Duration::from_secs(10));
+        assert!(pieces.is_empty());
+        assert_eq!(generator.due(Duration::from_millis(10_010)), vec![0x00]);
+    }
+
+    #[test]
+    fn full_frame_round_trip() {
+        let msg = full_frame_sysex(tc(1, 2, 3, 4), FrameRate::Fps30);
+        let parsed = parse_full_frame(&msg[1..msg.len() - 1]);
+        assert_eq!(parsed, Some((tc(1, 2, 3, 4), FrameRate::Fps30)));
+        assert_eq!(parse_full_frame(&[0x7F, 0x7F, 0x06, 0x02]), None);
+    }
+
+    #[test]
+    fn seven_segment_layout() {
+        let (text, dots) = tc(1, 2, 3, 4).seven_segment();
+        assert_eq!(text, "   010203 04");
+        assert_eq!(text.len(), 12);
+        assert_eq!(dots, 0b1_0101_0000);
+    }
+}
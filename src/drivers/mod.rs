@@ -6,6 +6,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -19,6 +20,60 @@ use tokio::sync::RwLock;
 /// * `value` - Signal value (boolean, string, number, etc.)
 pub type IndicatorCallback = Arc<dyn Fn(String, Value) + Send + Sync>;
 
+/// Indicator subscribers of a driver, plus the last value emitted per
+/// signal: values are only emitted when they change, and replayed to late
+/// subscribers. Clones share the same subscribers and cache.
+#[derive(Clone, Default)]
+pub struct IndicatorEmitters {
+    callbacks: Arc<parking_lot::RwLock<Vec<IndicatorCallback>>>,
+    last_emitted: Arc<parking_lot::RwLock<HashMap<String, Value>>>,
+}
+
+impl IndicatorEmitters {
+    /// Add a subscriber and replay the last value of every signal to it.
+    pub fn subscribe(&self, callback: IndicatorCallback) {
+        for (signal, value) in self.last_emitted.read().iter() {
+            callback(signal.clone(), value.clone());
+        }
+        self.callbacks.write().push(callback);
+    }
+
+    /// Drop every subscriber (on shutdown: re-registration re-subscribes).
+    /// The cache is kept for the next subscribers.
+    pub fn clear_subscribers(&self) {
+        self.callbacks.write().clear();
+    }
+
+    /// Emit `value` on `signal` unless it is the last value emitted there.
+    pub fn emit_if_changed(&self, signal: &str, value: Value) {
+        self.emit_unless(signal, value, |last, value| last == Some(value));
+    }
+
+    /// Emit `value` on `signal` if nothing was emitted there yet, so
+    /// indicators start in a known state.
+    pub fn seed(&self, signal: &str, value: Value) {
+        self.emit_unless(signal, value, |last, _| last.is_some());
+    }
+
+    fn emit_unless(
+        &self,
+        signal: &str,
+        value: Value,
+        skip: impl FnOnce(Option<&Value>, &Value) -> bool,
+    ) {
+        {
+            let mut last = self.last_emitted.write();
+            if skip(last.get(signal), &value) {
+                return;
+            }
+            last.insert(signal.to_string(), value.clone());
+        }
+        for emit in self.callbacks.read().iter() {
+            emit(signal.to_string(), value.clone());
+        }
+    }
+}
+
 /// Execution context passed to drivers for accessing router state and config
 #[derive(Clone)]
 #[allow(dead_code)] // `config` and `active_page` are part of the public driver API (consumed by tests and 3rd-party drivers).
@@ -120,8 +175,15 @@ pub trait Driver: Send + Sync {
 }
 
 pub mod console;
+pub mod macros;
 pub mod midibridge;
+pub mod mmc;
+pub mod mpris;
 pub mod obs;
+pub mod process;
+pub mod pulseaudio;
+pub mod vars;
+pub mod vmix;
 pub mod winaudio;
 pub mod winmedia;
 
@@ -129,9 +191,23 @@ pub mod winmedia;
 #[allow(unused_imports)]
 pub use console::ConsoleDriver;
 #[allow(unused_imports)]
+pub use macros::MacroDriver;
+#[allow(unused_imports)]
 pub use midibridge::MidiBridgeDriver;
+#[allow(unused_imports)]
+pub use mmc::MmcDriver;
+#[allow(unused_imports)]
+pub use mpris::MprisDriver;
 pub use obs::ObsDriver;
 #[allow(unused_imports)]
+pub use process::ProcessDriver;
+#[allow(unused_imports)]
+pub use pulseaudio::PulseAudioDriver;
+#[allow(unused_imports)]
+pub use vars::VarsDriver;
+#[allow(unused_imports)]
+pub use vmix::VmixDriver;
+#[allow(unused_imports)]
 pub use winaudio::WinAudioDriver;
 #[allow(unused_imports)]
 pub use winmedia::WinMediaDriver;
@@ -0,0 +1,35 @@
+//! Static action catalog for the MPRIS driver.
+//!
+//! Transport actions keep the WinMedia names so a page can switch
+//! `app: "winmedia"` to `app: "mpris"` without touching `action:`.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the static MPRIS action catalog.
+pub fn mpris_catalog() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::simple("play_pause", "Play / pause")
+            .with_description("Toggle playback in the selected player."),
+        ActionDescriptor::simple("play", "Play"),
+        ActionDescriptor::simple("pause", "Pause"),
+        ActionDescriptor::simple("stop", "Stop"),
+        ActionDescriptor::simple("next", "Next track").with_description("Skip to next track."),
+        ActionDescriptor::simple("previous", "Previous track")
+            .with_description("Skip to previous track."),
+        ActionDescriptor::simple("seek", "Seek")
+            .with_description("Seek relative to the current position (negative = backwards).")
+            .with_param(ParamDescriptor::new("seconds", ParamKind::Number).with_default(json!(10))),
+        ActionDescriptor::simple("volume", "Player volume (fader)")
+            .with_description("Bind to a fader; the position becomes the player volume."),
+        ActionDescriptor::simple("select_player", "Select player")
+            .with_description(
+                "Control a specific player (bus name, e.g. \"spotify\"), or \"auto\".",
+            )
+            .with_param(
+                ParamDescriptor::new("player", ParamKind::String).with_default(json!("auto")),
+            ),
+        ActionDescriptor::simple("next_player", "Next player")
+            .with_description("Cycle transport control to the next running player."),
+    ]
+}
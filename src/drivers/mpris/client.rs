@@ -0,0 +1,237 @@
+//! MPRIS D-Bus calls (session bus).
+//!
+//! Thin async wrappers over `org.mpris.MediaPlayer2.Player` method calls
+//! and `org.freedesktop.DBus.Properties` reads. No proxies or code
+//! generation: the driver only needs a handful of calls, and untyped
+//! `call_method` keeps tolerant parsing of player-provided metadata (many
+//! players send partial or oddly typed `xesam:*` entries).
+//!
+//! Module is `#[cfg(target_os = "linux")]`-gated at its parent declaration
+//! in `mod.rs`.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::Connection;
+
+use super::state::{PlaybackStatus, PlayerState, BUS_PREFIX};
+
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_IFACE: &str = "org.mpris.MediaPlayer2.Player";
+const ROOT_IFACE: &str = "org.mpris.MediaPlayer2";
+const PROPERTIES_IFACE: &str = "org.freedesktop.DBus.Properties";
+
+pub(super) async fn connect() -> Result<Connection> {
+    Connection::session()
+        .await
+        .context("Failed to connect to the D-Bus session bus")
+}
+
+/// Bus names of every MPRIS player currently on the bus, sorted so
+/// enumeration order is stable between polls.
+pub(super) async fn list_players(conn: &Connection) -> Result<Vec<String>> {
+    let reply = conn
+        .call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus"),
+            "ListNames",
+            &(),
+        )
+        .await?;
+    let names: Vec<String> = reply.body().deserialize()?;
+    let mut players: Vec<String> = names
+        .into_iter()
+        .filter(|n| n.starts_with(BUS_PREFIX))
+        .collect();
+    players.sort();
+    Ok(players)
+}
+
+/// Invoke an argument-less `Player` method (`PlayPause`, `Next`, ...).
+pub(super) async fn call(conn: &Connection, bus_name: &str, method: &str) -> Result<()> {
+    conn.call_method(Some(bus_name), OBJECT_PATH, Some(PLAYER_IFACE), method, &())
+        .await
+        .with_context(|| format!("{} on {} failed", method, bus_name))?;
+    Ok(())
+}
+
+/// Relative seek in microseconds (negative seeks backwards).
+pub(super) async fn seek(conn: &Connection, bus_name: &str, offset_us: i64) -> Result<()> {
+    conn.call_method(
+        Some(bus_name),
+        OBJECT_PATH,
+        Some(PLAYER_IFACE),
+        "Seek",
+        &(offset_us,),
+    )
+    .await
+    .with_context(|| format!("Seek on {} failed", bus_name))?;
+    Ok(())
+}
+
+/// Set the player volume (`0.0..=1.0`).
+pub(super) async fn set_volume(conn: &Connection, bus_name: &str, volume: f64) -> Result<()> {
+    conn.call_method(
+        Some(bus_name),
+        OBJECT_PATH,
+        Some(PROPERTIES_IFACE),
+        "Set",
+        &(PLAYER_IFACE, "Volume", Value::from(volume)),
+    )
+    .await
+    .with_context(|| format!("Setting Volume on {} failed", bus_name))?;
+    Ok(())
+}
+
+/// Read the full player state (status, metadata, volume, identity).
+pub(super) async fn read_state(conn: &Connection, bus_name: &str) -> Result<PlayerState> {
+    let props = get_all(conn, bus_name, PLAYER_IFACE).await?;
+    // `Identity` lives on the root interface; a player missing it still
+    // gets a usable name from its bus name.
+    let identity = get_all(conn, bus_name, ROOT_IFACE)
+        .await
+        .ok()
+        .and_then(|root| root.get("Identity").and_then(|v| as_string(v)));
+
+    let status = props
+        .get("PlaybackStatus")
+        .and_then(|v| as_string(v))
+        .map(|s| PlaybackStatus::parse(&s))
+        .unwrap_or_default();
+    let volume = props.get("Volume").and_then(|v| match unwrap_variant(v) {
+        Value::F64(f) => Some(*f),
+        _ => None,
+    });
+
+    let (title, artist) = props
+        .get("Metadata")
+        .map(|v| parse_metadata(v))
+        .unwrap_or_default();
+
+    Ok(PlayerState {
+        bus_name: bus_name.to_string(),
+        identity,
+        status,
+        title,
+        artist,
+        volume,
+    })
+}
+
+/// Just the `PlaybackStatus` of `bus_name` (auto-selection looks at
+/// every player each poll, so this avoids a full `GetAll`).
+pub(super) async fn playback_status(conn: &Connection, bus_name: &str) -> Result<PlaybackStatus> {
+    let reply = conn
+        .call_method(
+            Some(bus_name),
+            OBJECT_PATH,
+            Some(PROPERTIES_IFACE),
+            "Get",
+            &(PLAYER_IFACE, "PlaybackStatus"),
+        )
+        .await?;
+    let value: OwnedValue = reply.body().deserialize()?;
+    Ok(as_string(&value)
+        .map(|s| PlaybackStatus::parse(&s))
+        .unwrap_or_default())
+}
+
+async fn get_all(
+    conn: &Connection,
+    bus_name: &str,
+    iface: &str,
+) -> Result<HashMap<String, OwnedValue>> {
+    let reply = conn
+        .call_method(
+            Some(bus_name),
+            OBJECT_PATH,
+            Some(PROPERTIES_IFACE),
+            "GetAll",
+            &(iface,),
+        )
+        .await?;
+    Ok(reply.body().deserialize()?)
+}
+
+/// `(title, artist)` from an `a{sv}` metadata dict. Missing or oddly
+/// typed entries yield empty strings.
+fn parse_metadata(metadata: &Value<'_>) -> (String, String) {
+    let Value::Dict(dict) = unwrap_variant(metadata) else {
+        return Default::default();
+    };
+    let mut title = String::new();
+    let mut artist = String::new();
+    for (key, value) in dict.iter() {
+        match unwrap_variant(key) {
+            Value::Str(k) if k.as_str() == "xesam:title" => {
+                title = as_string(value).unwrap_or_default();
+            },
+            Value::Str(k) if k.as_str() == "xesam:artist" => artist = join_strings(value),
+            _ => {},
+        }
+    }
+    (title, artist)
+}
+
+/// Strip `v` variant wrappers (metadata values are always boxed).
+fn unwrap_variant<'a>(v: &'a Value<'a>) -> &'a Value<'a> {
+    match v {
+        Value::Value(inner) => unwrap_variant(inner),
+        other => other,
+    }
+}
+
+fn as_string(v: &Value<'_>) -> Option<String> {
+    match unwrap_variant(v) {
+        Value::Str(s) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+/// `xesam:artist` is specified as `as`; some players send a plain string.
+fn join_strings(v: &Value<'_>) -> String {
+    match unwrap_variant(v) {
+        Value::Str(s) => s.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(as_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(entries: Vec<(&str, Value<'static>)>) -> OwnedValue {
+        let map: HashMap<String, Value<'static>> = entries
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        OwnedValue::try_from(Value::from(map)).unwrap()
+    }
+
+    #[test]
+    fn metadata_title_and_artist_list() {
+        let md = metadata(vec![
+            ("xesam:title", Value::from("Song")),
+            ("xesam:artist", Value::from(vec!["A", "B"])),
+            ("mpris:length", Value::from(180_000_000i64)),
+        ]);
+        assert_eq!(
+            parse_metadata(&md),
+            ("Song".to_string(), "A, B".to_string())
+        );
+    }
+
+    #[test]
+    fn metadata_tolerates_string_artist_and_missing_title() {
+        let md = metadata(vec![("xesam:artist", Value::from("Solo"))]);
+        assert_eq!(parse_metadata(&md), (String::new(), "Solo".to_string()));
+        assert_eq!(parse_metadata(&Value::from(3u32)), Default::default());
+    }
+}
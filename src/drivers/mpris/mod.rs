@@ -0,0 +1,525 @@
+//! Linux media player driver over D-Bus MPRIS.
+//!
+//! Outbound: `org.mpris.MediaPlayer2.Player` method calls on the selected
+//! player — the WinMedia transport actions (`play_pause`, `next`,
+//! `previous`, `stop`) plus `seek`, `volume` and player selection.
+//!
+//! Inbound: polls the session bus every 500 ms (same cadence as the
+//! WinMedia SMTC poller) and publishes the selected player's state:
+//! - `mpris.*` indicator signals (status, title, artist, player, volume)
+//! - the play LED on the control bound to `play_pause` / `play`
+//! - the fader bound to `volume`
+//! - optionally title/artist on one LCD strip (`mpris.now_playing_strip`)
+//!
+//! On non-Linux targets this driver is a no-op stub that logs every
+//! action, like WinMedia off Windows.
+
+mod catalog;
+#[cfg(target_os = "linux")]
+mod client;
+mod state;
+
+use crate::config::MprisConfig;
+use crate::control_mapping::ControlMappingDB;
+use crate::drivers::{Driver, ExecutionContext, IndicatorCallback, IndicatorEmitters};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::{debug, info, warn};
+
+use state::{MprisAction, PlayerState};
+
+/// Driver name used for `app: "mpris"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "mpris";
+
+/// MPRIS indicator signal names (shared between emitter and consumers).
+pub mod signals {
+    /// `"playing"` | `"paused"` | `"stopped"` (`"stopped"` when no player runs).
+    pub const STATUS: &str = "mpris.status";
+    /// Current track title (`""` when unknown).
+    pub const TITLE: &str = "mpris.title";
+    /// Current track artists, comma-separated.
+    pub const ARTIST: &str = "mpris.artist";
+    /// Selected player's display name (`Identity`, else bus-name suffix).
+    pub const PLAYER: &str = "mpris.player";
+    /// Selected player's volume, `0.0..=1.0` (only when exposed).
+    pub const VOLUME: &str = "mpris.volume";
+}
+
+/// Bus poll cadence, matching the WinMedia SMTC poller.
+#[cfg(target_os = "linux")]
+const POLL_INTERVAL_MS: u64 = 500;
+
+type FeedbackSender = mpsc::Sender<(String, Vec<u8>)>;
+type RouterSlot = Arc<RwLock<Option<Arc<crate::router::Router>>>>;
+
+/// Which player transport actions target.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct Selection {
+    /// Name pinned by `select_player` / `next_player` (`None` = auto).
+    pinned: Option<String>,
+    /// Bus name resolved by the last poll (or the last explicit selection).
+    current: Option<String>,
+}
+
+/// Shared handles for the poller, page watcher and action path. Cloned
+/// into every background task.
+#[derive(Clone)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct Shared {
+    config: Arc<RwLock<MprisConfig>>,
+    /// Stops the tasks of the current `init`: signalled on `shutdown()`,
+    /// replaced (so dropped) by the next `init`.
+    task_shutdown: Arc<parking_lot::Mutex<Option<watch::Sender<bool>>>>,
+    router: RouterSlot,
+    feedback_tx: Arc<RwLock<Option<FeedbackSender>>>,
+    led_tx: Arc<RwLock<Option<mpsc::Sender<Vec<u8>>>>>,
+    control_db: Arc<RwLock<Option<Arc<ControlMappingDB>>>>,
+    indicators: IndicatorEmitters,
+    selection: Arc<RwLock<Selection>>,
+    /// Last polled state of the selected player (`None` = no player).
+    last_state: Arc<RwLock<Option<PlayerState>>>,
+    #[cfg(target_os = "linux")]
+    conn: Arc<RwLock<Option<zbus::Connection>>>,
+}
+
+pub struct MprisDriver {
+    initialized: AtomicBool,
+    shared: Shared,
+}
+
+impl MprisDriver {
+    pub fn new(config: MprisConfig) -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            shared: Shared {
+                config: Arc::new(RwLock::new(config)),
+                task_shutdown: Arc::new(parking_lot::Mutex::new(None)),
+                router: Arc::new(RwLock::new(None)),
+                feedback_tx: Arc::new(RwLock::new(None)),
+                led_tx: Arc::new(RwLock::new(None)),
+                control_db: Arc::new(RwLock::new(None)),
+                indicators: IndicatorEmitters::default(),
+                selection: Arc::new(RwLock::new(Selection::default())),
+                last_state: Arc::new(RwLock::new(None)),
+                #[cfg(target_os = "linux")]
+                conn: Arc::new(RwLock::new(None)),
+            },
+        }
+    }
+
+    /// Wire the driver to the router (active page → LED/fader/LCD targets).
+    pub async fn set_router(&self, router: Arc<crate::router::Router>) {
+        *self.shared.router.write().await = Some(router);
+    }
+
+    /// Wire the driver to the unified feedback channel.
+    pub async fn set_feedback_sender(&self, tx: FeedbackSender) {
+        *self.shared.feedback_tx.write().await = Some(tx);
+    }
+
+    /// Wire the driver to the LED MIDI channel (now-playing LCD strip).
+    pub async fn set_led_sender(&self, tx: mpsc::Sender<Vec<u8>>) {
+        *self.shared.led_tx.write().await = Some(tx);
+    }
+
+    /// Share the application-wide control mapping DB.
+    pub async fn set_control_db(&self, db: Arc<ControlMappingDB>) {
+        *self.shared.control_db.write().await = Some(db);
+    }
+}
+
+#[async_trait]
+impl Driver for MprisDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, _ctx: ExecutionContext) -> Result<()> {
+        info!("MPRIS driver initializing");
+        // A fresh channel per init so the same instance survives an
+        // unregister/re-register cycle across profile switches; dropping
+        // the previous sender stops the previous init's tasks.
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        *self.shared.task_shutdown.lock() = Some(shutdown_tx);
+        self.initialized.store(true, Ordering::Release);
+
+        #[cfg(target_os = "linux")]
+        {
+            self.shared.spawn_poller(shutdown_rx.clone());
+            self.shared.spawn_page_watcher(shutdown_rx).await;
+        }
+        #[cfg(not(target_os = "linux"))]
+        drop(shutdown_rx);
+
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!("MPRIS driver not initialized, dropping action '{}'", action);
+            return Ok(());
+        }
+
+        let parsed = state::parse_action(action, &params)?;
+
+        // Faders send 0 at the bottom of their travel; only buttons
+        // treat value 0 as a release.
+        if !matches!(parsed, MprisAction::Volume(_)) && ctx.is_button_release() {
+            return Ok(());
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.shared.execute(parsed, &ctx).await
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = ctx;
+            debug!("MPRIS: '{}' ({:?}) is a no-op on non-Linux", action, parsed);
+            Ok(())
+        }
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("MPRIS sync requested (state is polled)");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        if let Some(tx) = self.shared.task_shutdown.lock().take() {
+            let _ = tx.send(true);
+        }
+        #[cfg(target_os = "linux")]
+        {
+            self.shared.conn.write().await.take();
+        }
+        self.shared.indicators.clear_subscribers();
+        debug!("MPRIS driver shut down");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("MPRIS driver: new indicator subscription");
+        self.shared.indicators.subscribe(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        catalog::mpris_catalog()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Shared {
+    async fn execute(&self, action: MprisAction, ctx: &ExecutionContext) -> Result<()> {
+        let Some(conn) = self.conn.read().await.clone() else {
+            debug!("MPRIS: session bus not connected, dropping {:?}", action);
+            return Ok(());
+        };
+
+        match action {
+            MprisAction::SelectPlayer(name) => {
+                let players = client::list_players(&conn).await?;
+                let mut sel = self.selection.write().await;
+                sel.current = match name.as_deref() {
+                    Some(wanted) => players
+                        .iter()
+                        .find(|p| state::matches_player(p, wanted))
+                        .cloned()
+                        .or(sel.current.take()),
+                    None => sel.current.take(),
+                };
+                info!(
+                    "MPRIS: player selection -> {}",
+                    name.as_deref().unwrap_or("auto")
+                );
+                sel.pinned = name;
+                return Ok(());
+            },
+            MprisAction::NextPlayer => {
+                let players = client::list_players(&conn).await?;
+                let mut sel = self.selection.write().await;
+                let next = state::next_player(&players, sel.current.as_deref());
+                sel.pinned = next.as_deref().map(|p| state::short_name(p).to_string());
+                info!("MPRIS: next player -> {:?}", sel.pinned);
+                sel.current = next;
+                return Ok(());
+            },
+            _ => {},
+        }
+
+        let Some(player) = self.selection.read().await.current.clone() else {
+            debug!("MPRIS: no player available for {:?}", action);
+            return Ok(());
+        };
+
+        match action {
+            MprisAction::Method(method) => client::call(&conn, &player, method).await,
+            MprisAction::Seek(offset_us) => client::seek(&conn, &player, offset_us).await,
+            MprisAction::Volume(volume) => {
+                let volume = volume.or_else(|| {
+                    ctx.value
+                        .as_ref()
+                        .and_then(|v| v.as_f64())
+                        .map(|v| crate::drivers::winaudio::normalize_fader_value(v) as f64)
+                });
+                match volume {
+                    Some(v) => client::set_volume(&conn, &player, v).await,
+                    None => Ok(()),
+                }
+            },
+            MprisAction::SelectPlayer(_) | MprisAction::NextPlayer => Ok(()),
+        }
+    }
+
+    fn spawn_poller(&self, mut shutdown_rx: watch::Receiver<bool>) {
+        let shared = self.clone();
+        tokio::spawn(async move {
+            debug!("MPRIS poller started");
+            let mut ticker =
+                tokio::time::interval(std::time::Duration::from_millis(POLL_INTERVAL_MS));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut reported_failure = false;
+
+            loop {
+                tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            debug!("MPRIS poller exiting (shutdown)");
+                            break;
+                        }
+                        continue;
+                    }
+                    _ = ticker.tick() => {}
+                }
+
+                let conn = shared.conn.read().await.clone();
+                let conn = match conn {
+                    Some(conn) => conn,
+                    None => match client::connect().await {
+                        Ok(conn) => {
+                            info!("MPRIS: connected to the session bus");
+                            reported_failure = false;
+                            *shared.conn.write().await = Some(conn.clone());
+                            conn
+                        },
+                        Err(e) => {
+                            if !reported_failure {
+                                warn!("MPRIS: {} — retrying in the background", e);
+                                reported_failure = true;
+                            }
+                            continue;
+                        },
+                    },
+                };
+
+                if let Err(e) = shared.poll_once(&conn).await {
+                    debug!("MPRIS poll failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn poll_once(&self, conn: &zbus::Connection) -> Result<()> {
+        let players = client::list_players(conn).await?;
+        let mut playing = Vec::new();
+        for p in &players {
+            // A player vanishing mid-poll is normal; skip it.
+            if let Ok(state::PlaybackStatus::Playing) = client::playback_status(conn, p).await {
+                playing.push(p.clone());
+            }
+        }
+
+        let preferred = self.config.read().await.preferred_players.clone();
+        let chosen = {
+            let mut sel = self.selection.write().await;
+            let chosen = state::choose_player(
+                &players,
+                &playing,
+                sel.pinned.as_deref(),
+                sel.current.as_deref(),
+                &preferred,
+            );
+            sel.current = chosen.clone();
+            chosen
+        };
+
+        let new_state = match chosen {
+            Some(player) => Some(client::read_state(conn, &player).await?),
+            None => None,
+        };
+
+        let previous = {
+            let mut last = self.last_state.write().await;
+            if *last == new_state {
+                return Ok(());
+            }
+            std::mem::replace(&mut *last, new_state.clone())
+        };
+
+        self.publish(previous.as_ref(), new_state.as_ref()).await;
+        Ok(())
+    }
+
+    /// Fan a state change out to signals, the play LED, the volume fader
+    /// and the LCD strip. `previous = None` forces every output.
+    async fn publish(&self, previous: Option<&PlayerState>, current: Option<&PlayerState>) {
+        let empty = PlayerState::default();
+        let cur = current.unwrap_or(&empty);
+
+        self.indicators
+            .emit_if_changed(signals::STATUS, serde_json::json!(cur.status.as_str()));
+        self.indicators
+            .emit_if_changed(signals::TITLE, serde_json::json!(cur.title));
+        self.indicators
+            .emit_if_changed(signals::ARTIST, serde_json::json!(cur.artist));
+        let player = current.map(PlayerState::display_name).unwrap_or_default();
+        self.indicators
+            .emit_if_changed(signals::PLAYER, serde_json::json!(player));
+        if let Some(volume) = cur.volume {
+            self.indicators
+                .emit_if_changed(signals::VOLUME, serde_json::json!(volume));
+        }
+
+        let prev = previous.unwrap_or(&empty);
+        let force = previous.is_none();
+        if force || prev.status != cur.status {
+            self.send_play_led(cur.status == state::PlaybackStatus::Playing)
+                .await;
+        }
+        if let Some(volume) = cur.volume.filter(|v| force || prev.volume != Some(*v)) {
+            self.send_volume_feedback(volume).await;
+        }
+        if force || prev.title != cur.title || prev.artist != cur.artist {
+            self.render_now_playing(&cur.title, &cur.artist).await;
+        }
+    }
+
+    /// Re-push LED, fader and LCD every time a page is activated: the
+    /// poller only reports *changes*, and the router's page refresh
+    /// resets the surface to the page's static state.
+    async fn spawn_page_watcher(&self, mut shutdown_rx: watch::Receiver<bool>) {
+        let router_arc = self.router.read().await.clone();
+        let Some(router) = router_arc else {
+            debug!("MPRIS: no router wired, skipping page watcher");
+            return;
+        };
+        let Some(live_tx) = router.live_tx_snapshot().await else {
+            debug!("MPRIS: live_tx not yet wired, skipping page watcher");
+            return;
+        };
+        let mut rx = live_tx.subscribe();
+        let shared = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            break;
+                        }
+                        continue;
+                    }
+                    recv = rx.recv() => match recv {
+                        Ok(ev) => ev,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                if !matches!(event, crate::event_bus::LiveEvent::PageChanged { .. }) {
+                    continue;
+                }
+                let cached = shared.last_state.read().await.clone();
+                shared.publish(None, cached.as_ref()).await;
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Shared {
+    /// Send `bytes_for(spec)` as `"mpris"` feedback for the first control
+    /// on the active page matching `pred`.
+    async fn send_control_feedback(
+        &self,
+        pred: impl Fn(&crate::config::ControlMapping) -> bool,
+        bytes_for: impl Fn(&crate::control_mapping::MidiSpec) -> Vec<u8>,
+    ) {
+        let Some(tx) = self.feedback_tx.read().await.clone() else {
+            return;
+        };
+        let Some(router) = self.router.read().await.clone() else {
+            return;
+        };
+        let Some(page) = router.get_active_page().await else {
+            return;
+        };
+        let Some(db) = self.control_db.read().await.clone() else {
+            debug!("MPRIS: control DB not yet wired, dropping feedback");
+            return;
+        };
+        let mcu_mode = router.config.read().await.is_mcu_mode();
+        let Some(spec) = page
+            .controls
+            .as_ref()
+            .and_then(|controls| controls.iter().find(|(_, m)| pred(m)))
+            .and_then(|(id, _)| db.get_midi_spec(id, mcu_mode))
+        else {
+            return;
+        };
+        if let Err(e) = tx.send((DRIVER_NAME.to_string(), bytes_for(&spec))).await {
+            debug!("MPRIS: feedback channel closed: {}", e);
+        }
+    }
+
+    async fn send_play_led(&self, playing: bool) {
+        self.send_control_feedback(
+            |m| m.app == DRIVER_NAME && m.action.as_deref().is_some_and(state::is_play_action),
+            |spec| spec.led_bytes(playing),
+        )
+        .await;
+    }
+
+    async fn send_volume_feedback(&self, volume: f64) {
+        self.send_control_feedback(
+            |m| {
+                m.app == DRIVER_NAME
+                    && m.action.as_deref() == Some("volume")
+                    && m.params.as_deref().unwrap_or_default().is_empty()
+            },
+            |spec| crate::drivers::winaudio::bytes_for_volume(spec, volume as f32),
+        )
+        .await;
+    }
+
+    /// Title on the upper line, artist on the lower line of the
+    /// configured strip, only while the active page binds `app: "mpris"`.
+    async fn render_now_playing(&self, title: &str, artist: &str) {
+        let Some(strip) = self.config.read().await.now_playing_strip else {
+            return;
+        };
+        let Some(router) = self.router.read().await.clone() else {
+            return;
+        };
+        let Some(page) = router.get_active_page().await else {
+            return;
+        };
+        if !crate::drivers::winaudio::page_uses_app(&page, DRIVER_NAME) {
+            return;
+        }
+        let Some(tx) = self.led_tx.read().await.clone() else {
+            return;
+        };
+        let (upper, lower) =
+            crate::xtouch::build_lcd_strip_sysex(strip.saturating_sub(1).min(7), title, artist);
+        if tx.send(upper).await.is_err() || tx.send(lower).await.is_err() {
+            debug!("MPRIS LCD: led_tx closed, dropping render");
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Platform-independent MPRIS model: player state snapshots, player
+//! selection and action parsing. Kept free of D-Bus types so it is
+//! unit-testable on every host.
+
+// Only the action parser is used by the non-Linux stub.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Well-known bus name prefix of every MPRIS player.
+pub(super) const BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+/// `PlaybackStatus` property of `org.mpris.MediaPlayer2.Player`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum PlaybackStatus {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+impl PlaybackStatus {
+    pub(super) fn parse(s: &str) -> Self {
+        match s {
+            "Playing" => Self::Playing,
+            "Paused" => Self::Paused,
+            _ => Self::Stopped,
+        }
+    }
+
+    /// Signal value: `"playing"` / `"paused"` / `"stopped"`.
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Playing => "playing",
+            Self::Paused => "paused",
+            Self::Stopped => "stopped",
+        }
+    }
+}
+
+/// One poll of the selected player.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(super) struct PlayerState {
+    /// Full bus name, e.g. `org.mpris.MediaPlayer2.spotify`.
+    pub bus_name: String,
+    /// Human-readable `Identity` (e.g. "Spotify"), when the player sets it.
+    pub identity: Option<String>,
+    pub status: PlaybackStatus,
+    pub title: String,
+    /// `xesam:artist` entries joined with ", ".
+    pub artist: String,
+    /// `0.0..=1.0`; `None` when the player doesn't expose volume.
+    pub volume: Option<f64>,
+}
+
+impl PlayerState {
+    /// Display name: `Identity`, else the bus-name suffix.
+    pub(super) fn display_name(&self) -> String {
+        self.identity
+            .clone()
+            .unwrap_or_else(|| short_name(&self.bus_name).to_string())
+    }
+}
+
+/// Bus-name suffix identifying the player (`spotify`,
+/// `firefox.instance_1_42`, ...).
+pub(super) fn short_name(bus_name: &str) -> &str {
+    bus_name.strip_prefix(BUS_PREFIX).unwrap_or(bus_name)
+}
+
+/// True if `wanted` (YAML name, case-insensitive) designates `bus_name`.
+/// Matches the whole suffix or its first dotted segment, so `"firefox"`
+/// selects `org.mpris.MediaPlayer2.firefox.instance_1_42`.
+pub(super) fn matches_player(bus_name: &str, wanted: &str) -> bool {
+    let suffix = short_name(bus_name);
+    let head = suffix.split('.').next().unwrap_or(suffix);
+    suffix.eq_ignore_ascii_case(wanted) || head.eq_ignore_ascii_case(wanted)
+}
+
+/// Pick the player controlled by transport actions.
+///
+/// 1. An explicitly selected player (`select_player` / `next_player`) wins
+///    while it stays on the bus.
+/// 2. Otherwise a playing player, honoring `preferred` order first.
+/// 3. Otherwise keep the current player if it is still there, so a paused
+///    player stays in control instead of jumping around.
+/// 4. Otherwise the first preferred player present, else the first one.
+pub(super) fn choose_player(
+    players: &[String],
+    playing: &[String],
+    selected: Option<&str>,
+    current: Option<&str>,
+    preferred: &[String],
+) -> Option<String> {
+    if let Some(wanted) = selected {
+        if let Some(p) = players.iter().find(|p| matches_player(p, wanted)) {
+            return Some(p.clone());
+        }
+    }
+    let by_preference = |candidates: &[String]| -> Option<String> {
+        preferred
+            .iter()
+            .find_map(|want| candidates.iter().find(|p| matches_player(p, want)))
+            .or_else(|| candidates.first())
+            .cloned()
+    };
+    if let Some(p) = by_preference(playing) {
+        return Some(p);
+    }
+    if let Some(cur) = current.filter(|c| players.iter().any(|p| p == c)) {
+        return Some(cur.to_string());
+    }
+    by_preference(players)
+}
+
+/// Player after `current` in bus order (wrapping), for `next_player`.
+pub(super) fn next_player(players: &[String], current: Option<&str>) -> Option<String> {
+    let pos = current.and_then(|c| players.iter().position(|p| p == c));
+    let next = match pos {
+        Some(i) => (i + 1) % players.len(),
+        None => 0,
+    };
+    players.get(next).cloned()
+}
+
+/// Parsed YAML action.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum MprisAction {
+    /// Argument-less `Player` method: `PlayPause`, `Play`, `Pause`,
+    /// `Stop`, `Next`, `Previous`.
+    Method(&'static str),
+    /// Relative seek in microseconds.
+    Seek(i64),
+    /// Absolute volume; `None` means "take it from the fader value".
+    Volume(Option<f64>),
+    /// Pin a player by name; `None` (`"auto"`) returns to auto-selection.
+    SelectPlayer(Option<String>),
+    NextPlayer,
+}
+
+/// Default seek step when `seek` has no param.
+const DEFAULT_SEEK_SECONDS: f64 = 10.0;
+
+/// Map `(action, params)` to an [`MprisAction`]. Transport names and
+/// aliases match the WinMedia driver so pages can switch `app:` between
+/// the two unchanged.
+pub(super) fn parse_action(action: &str, params: &[Value]) -> Result<MprisAction> {
+    Ok(match action {
+        "play_pause" | "playpause" | "toggle" => MprisAction::Method("PlayPause"),
+        "play" => MprisAction::Method("Play"),
+        "pause" => MprisAction::Method("Pause"),
+        "stop" => MprisAction::Method("Stop"),
+        "next" | "next_track" | "nexttrack" | "forward" => MprisAction::Method("Next"),
+        "previous" | "prev" | "prev_track" | "previous_track" | "prevtrack" | "back" => {
+            MprisAction::Method("Previous")
+        },
+        "seek" => {
+            let seconds = match params.first() {
+                None => DEFAULT_SEEK_SECONDS,
+                Some(v) => v
+                    .as_f64()
+                    .ok_or_else(|| anyhow::anyhow!("mpris.seek expects seconds, got {}", v))?,
+            };
+            MprisAction::Seek((seconds * 1_000_000.0) as i64)
+        },
+        "volume" => match params.first() {
+            None => MprisAction::Volume(None),
+            Some(v) => {
+                let volume = v
+                    .as_f64()
+                    .ok_or_else(|| anyhow::anyhow!("mpris.volume expects 0.0..=1.0, got {}", v))?;
+                MprisAction::Volume(Some(volume.clamp(0.0, 1.0)))
+            },
+        },
+        "select_player" => {
+            let Some(name) = params.first().and_then(|v| v.as_str()) else {
+                bail!("mpris.select_player expects a player name (or \"auto\")");
+            };
+            let name = name.trim();
+            if name.is_empty() || name.eq_ignore_ascii_case("auto") {
+                MprisAction::SelectPlayer(None)
+            } else {
+                MprisAction::SelectPlayer(Some(name.to_string()))
+            }
+        },
+        "next_player" => MprisAction::NextPlayer,
+        _ => bail!("Unknown mpris action '{}'", action),
+    })
+}
+
+/// True for the actions whose button LED follows the playing state.
+pub(super) fn is_play_action(action: &str) -> bool {
+    matches!(
+        parse_action(action, &[]),
+        Ok(MprisAction::Method("PlayPause" | "Play"))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bus(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| format!("{BUS_PREFIX}{n}")).collect()
+    }
+
+    #[test]
+    fn player_name_matching() {
+        let ff = "org.mpris.MediaPlayer2.firefox.instance_1_42";
+        assert!(matches_player(ff, "firefox"));
+        assert!(matches_player(ff, "Firefox.instance_1_42"));
+        assert!(!matches_player(ff, "fire"));
+        assert_eq!(short_name("org.mpris.MediaPlayer2.spotify"), "spotify");
+    }
+
+    #[test]
+    fn explicit_selection_wins_while_present() {
+        let players = bus(&["spotify", "vlc"]);
+        let playing = bus(&["spotify"]);
+        let chosen = choose_player(&players, &playing, Some("vlc"), None, &[]);
+        assert_eq!(chosen.as_deref(), Some("org.mpris.MediaPlayer2.vlc"));
+
+        // Selected player gone → auto-selection (the playing one).
+        let chosen = choose_player(&players, &playing, Some("mpv"), None, &[]);
+        assert_eq!(chosen.as_deref(), Some("org.mpris.MediaPlayer2.spotify"));
+    }
+
+    #[test]
+    fn auto_prefers_playing_then_current_then_preferred() {
+        let players = bus(&["chromium", "spotify", "vlc"]);
+        let preferred = vec!["vlc".to_string()];
+
+        let playing = bus(&["chromium", "vlc"]);
+        let chosen = choose_player(&players, &playing, None, None, &preferred);
+        assert_eq!(chosen.as_deref(), Some("org.mpris.MediaPlayer2.vlc"));
+
+        let current = "org.mpris.MediaPlayer2.spotify";
+        let chosen = choose_player(&players, &[], None, Some(current), &preferred);
+        assert_eq!(chosen.as_deref(), Some(current));
+
+        let chosen = choose_player(&players, &[], None, None, &preferred);
+        assert_eq!(chosen.as_deref(), Some("org.mpris.MediaPlayer2.vlc"));
+
+        assert_eq!(choose_player(&[], &[], None, None, &preferred), None);
+    }
+
+    #[test]
+    fn next_player_wraps() {
+        let players = bus(&["a", "b"]);
+        assert_eq!(next_player(&players, None), Some(players[0].clone()));
+        assert_eq!(
+            next_player(&players, Some(&players[0])),
+            Some(players[1].clone())
+        );
+        assert_eq!(
+            next_player(&players, Some(&players[1])),
+            Some(players[0].clone())
+        );
+        assert_eq!(next_player(&[], None), None);
+    }
+
+    #[test]
+    fn parses_transport_aliases_like_winmedia() {
+        assert_eq!(
+            parse_action("toggle", &[]).unwrap(),
+            MprisAction::Method("PlayPause")
+        );
+        assert_eq!(
+            parse_action("back", &[]).unwrap(),
+            MprisAction::Method("Previous")
+        );
+        assert_eq!(
+            parse_action("pause", &[]).unwrap(),
+            MprisAction::Method("Pause")
+        );
+        assert!(parse_action("rewind", &[]).is_err());
+    }
+
+    #[test]
+    fn parses_seek_volume_and_selection() {
+        assert_eq!(
+            parse_action("seek", &[json!(-5)]).unwrap(),
+            MprisAction::Seek(-5_000_000)
+        );
+        assert_eq!(
+            parse_action("seek", &[]).unwrap(),
+            MprisAction::Seek(10_000_000)
+        );
+        assert!(parse_action("seek", &[json!("x")]).is_err());
+        assert_eq!(
+            parse_action("volume", &[json!(1.5)]).unwrap(),
+            MprisAction::Volume(Some(1.0))
+        );
+        assert_eq!(
+            parse_action("select_player", &[json!("Auto")]).unwrap(),
+            MprisAction::SelectPlayer(None)
+        );
+        assert_eq!(
+            parse_action("select_player", &[json!("spotify")]).unwrap(),
+            MprisAction::SelectPlayer(Some("spotify".into()))
+        );
+        assert!(parse_action("select_player", &[]).is_err());
+    }
+
+    #[test]
+    fn play_led_follows_play_actions_only() {
+        assert!(is_play_action("play_pause"));
+        assert!(is_play_action("play"));
+        assert!(!is_play_action("pause"));
+        assert!(!is_play_action("next"));
+    }
+}
@@ -0,0 +1,49 @@
+//! Action catalog for the process driver, built from the configured
+//! command names.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the process action catalog. The `command` param defaults to the
+/// first allowlisted name; descriptions list all of them.
+pub fn process_catalog(commands: &[&str]) -> Vec<ActionDescriptor> {
+    let allowed = if commands.is_empty() {
+        "none, add them under `process.commands`".to_string()
+    } else {
+        commands.join(", ")
+    };
+    let action = |name: &str, label: &str, description: &str| {
+        let param = ParamDescriptor::new("command", ParamKind::String);
+        let param = match commands.first() {
+            Some(first) => param.with_default(json!(first)),
+            None => param,
+        };
+        ActionDescriptor::simple(name, label)
+            .with_description(&format!("{} Commands: {}.", description, allowed))
+            .with_param(param)
+    };
+
+    vec![
+        action(
+            "run",
+            "Run",
+            "Run and wait for exit (killed after timeout_ms).",
+        ),
+        action(
+            "start",
+            "Start",
+            "Launch a long-running helper if not already running.",
+        ),
+        action("stop", "Stop", "Kill the running process."),
+        action(
+            "restart",
+            "Restart",
+            "Kill the running process and launch it again.",
+        ),
+        action(
+            "toggle",
+            "Start / stop",
+            "Start if stopped, stop if running.",
+        ),
+    ]
+}
@@ -0,0 +1,476 @@
+//! Process launcher driver
+//!
+//! Runs commands from the `commands` allowlist of `process.yaml`, next to
+//! `config.yaml`. Mappings only name a command (`params: [name, ...]`):
+//! program, arguments and working directory come from that file, which
+//! profiles, the editor and `include:` fragments can't write, so none of
+//! them can launch anything else. Extra params fill the `{1}`..`{9}`
+//! argument templates.
+//!
+//! Actions:
+//! - `run`: launch and wait for exit in the background, killed after
+//!   `timeout_ms`
+//! - `start`: launch a long-running helper (no-op while it runs)
+//! - `stop`: kill it
+//! - `restart`: kill it if running, then launch it again
+//! - `toggle`: `start` when stopped, `stop` when running
+//!
+//! Each command publishes `process.<name>.running`, `.status`,
+//! `.exit_code` and, with `capture_stdout`, `.stdout`.
+
+mod catalog;
+mod template;
+
+use crate::config::{ProcessCommandConfig, ProcessConfig};
+use crate::drivers::{Driver, ExecutionContext, IndicatorCallback, IndicatorEmitters};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Driver name used for `app: "process"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "process";
+
+/// Process indicator signal names: `process.<command>.<field>`.
+pub mod signals {
+    pub const PREFIX: &str = "process";
+    /// `true` while the command runs.
+    pub const RUNNING: &str = "running";
+    /// `"running"` | `"exited"` (code 0) | `"failed"` | `"timeout"` | `"stopped"`.
+    pub const STATUS: &str = "status";
+    /// Exit code of the last run (`null` when killed or never started).
+    pub const EXIT_CODE: &str = "exit_code";
+    /// Last non-empty stdout line (`capture_stdout`).
+    pub const STDOUT: &str = "stdout";
+
+    /// Full signal name for a command field.
+    pub fn name(command: &str, field: &str) -> String {
+        format!("{}.{}.{}", PREFIX, command, field)
+    }
+}
+
+/// Longest stdout line published, in characters.
+const MAX_STDOUT_CHARS: usize = 256;
+
+/// Hide the console window of console programs launched from the gateway.
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Parsed driver action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessAction {
+    Run,
+    Start,
+    Stop,
+    Restart,
+    Toggle,
+}
+
+impl ProcessAction {
+    fn parse(action: &str) -> Result<Self> {
+        Ok(match action {
+            "run" => Self::Run,
+            "start" => Self::Start,
+            "stop" | "kill" => Self::Stop,
+            "restart" => Self::Restart,
+            "toggle" => Self::Toggle,
+            _ => bail!("Unknown process action '{}'", action),
+        })
+    }
+}
+
+/// How a launch ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// Exited by itself; `None` when terminated by a signal.
+    Exited(Option<i32>),
+    /// Killed by `stop` / `restart` / shutdown.
+    Stopped,
+    /// Killed after `timeout_ms` (`run`).
+    Timeout,
+}
+
+/// A launched command, owned by its supervisor task.
+struct Running {
+    /// Tells this launch apart from a later one under the same name.
+    generation: u64,
+    stop_tx: oneshot::Sender<()>,
+    supervisor: JoinHandle<()>,
+}
+
+/// Shared handles for the supervisor tasks, cloned into each of them.
+#[derive(Clone)]
+struct Shared {
+    config: Arc<ProcessConfig>,
+    /// Launched commands by name. Signals for a command are emitted while
+    /// holding this lock so a fast exit can't overtake its own start.
+    running: Arc<parking_lot::Mutex<HashMap<String, Running>>>,
+    next_generation: Arc<AtomicU64>,
+    indicators: IndicatorEmitters,
+}
+
+pub struct ProcessDriver {
+    initialized: AtomicBool,
+    shared: Shared,
+}
+
+impl ProcessDriver {
+    pub fn new(config: ProcessConfig) -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            shared: Shared {
+                config: Arc::new(config),
+                running: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                next_generation: Arc::new(AtomicU64::new(0)),
+                indicators: IndicatorEmitters::default(),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl Driver for ProcessDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, _ctx: ExecutionContext) -> Result<()> {
+        info!(
+            "Process driver initializing ({} allowlisted command(s))",
+            self.shared.config.commands.len()
+        );
+        for name in self.shared.config.commands.keys() {
+            self.shared
+                .indicators
+                .seed(&signals::name(name, signals::RUNNING), Value::Bool(false));
+        }
+        self.initialized.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!(
+                "Process driver not initialized, dropping action '{}'",
+                action
+            );
+            return Ok(());
+        }
+
+        let action = ProcessAction::parse(action)?;
+        let (name, command) = self.shared.command(params.first())?;
+        if ctx.is_button_release() {
+            return Ok(());
+        }
+
+        let launched = match action {
+            ProcessAction::Stop => {
+                self.shared.stop(name).await;
+                false
+            },
+            ProcessAction::Restart => {
+                self.shared.stop(name).await;
+                self.shared.launch(name, command, &params, false)?
+            },
+            ProcessAction::Toggle => {
+                if self.shared.stop(name).await {
+                    false
+                } else {
+                    self.shared.launch(name, command, &params, false)?
+                }
+            },
+            ProcessAction::Run => self.shared.launch(name, command, &params, true)?,
+            ProcessAction::Start => self.shared.launch(name, command, &params, false)?,
+        };
+        if !launched && matches!(action, ProcessAction::Run | ProcessAction::Start) {
+            debug!("process.{}: already running, ignoring {:?}", name, action);
+        }
+
+        if let Some(ref tracker) = ctx.activity_tracker {
+            tracker.record(DRIVER_NAME, crate::tray::ActivityDirection::Outbound);
+        }
+        Ok(())
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("Process sync (no-op)");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        let names: Vec<String> = self.shared.running.lock().keys().cloned().collect();
+        for name in names {
+            self.shared.stop(&name).await;
+        }
+        self.shared.indicators.clear_subscribers();
+        debug!("Process driver shut down");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("Process driver: new indicator subscription");
+        self.shared.indicators.subscribe(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        let mut names: Vec<&str> = self
+            .shared
+            .config
+            .commands
+            .keys()
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        catalog::process_catalog(&names)
+    }
+}
+
+impl Shared {
+    /// Resolve the command-name param against the allowlist.
+    fn command<'a>(&'a self, param: Option<&Value>) -> Result<(&'a str, &'a ProcessCommandConfig)> {
+        let Some(requested) = param.and_then(Value::as_str) else {
+            bail!("process actions expect a command name as first param");
+        };
+        self.config
+            .commands
+            .get_key_value(requested)
+            .map(|(name, command)| (name.as_str(), command))
+            .ok_or_else(|| anyhow!("process: '{}' is not in process.commands", requested))
+    }
+
+    /// Launch `command` unless it is already running. Returns whether a
+    /// process was started. `wait` applies the command's `timeout_ms`.
+    fn launch(
+        &self,
+        name: &str,
+        command: &ProcessCommandConfig,
+        params: &[Value],
+        wait: bool,
+    ) -> Result<bool> {
+        let args = template::expand_args(&command.args, params.get(1..).unwrap_or_default())
+            .map_err(|e| anyhow!("process.{}: {}", name, e))?;
+
+        let mut running = self.running.lock();
+        if running.contains_key(name) {
+            return Ok(false);
+        }
+
+        let mut cmd = Command::new(&command.program);
+        cmd.args(&args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .stdout(if command.capture_stdout {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .kill_on_drop(true);
+        if let Some(cwd) = &command.cwd {
+            cmd.current_dir(cwd);
+        }
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                self.emit_outcome(name, None, "failed");
+                bail!(
+                    "process.{}: failed to start '{}': {}",
+                    name,
+                    command.program,
+                    e
+                );
+            },
+        };
+        info!("process.{}: started '{}' {:?}", name, command.program, args);
+
+        self.indicators
+            .emit_if_changed(&signals::name(name, signals::RUNNING), Value::Bool(true));
+        self.indicators.emit_if_changed(
+            &signals::name(name, signals::STATUS),
+            Value::String("running".into()),
+        );
+        if let Some(stdout) = child.stdout.take() {
+            self.spawn_stdout_reader(name, stdout);
+        }
+
+        let timeout =
+            (wait && command.timeout_ms > 0).then(|| Duration::from_millis(command.timeout_ms));
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let shared = self.clone();
+        let owned_name = name.to_string();
+        let supervisor = tokio::spawn(async move {
+            let outcome = supervise(&mut child, timeout, stop_rx).await;
+            shared.finish(&owned_name, generation, outcome);
+        });
+        running.insert(
+            name.to_string(),
+            Running {
+                generation,
+                stop_tx,
+                supervisor,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Kill a running command and wait for its supervisor to publish the
+    /// outcome. Returns whether something was running.
+    async fn stop(&self, name: &str) -> bool {
+        let Some(entry) = self.running.lock().remove(name) else {
+            return false;
+        };
+        let _ = entry.stop_tx.send(());
+        if let Err(e) = entry.supervisor.await {
+            warn!("process.{}: supervisor task failed: {}", name, e);
+        }
+        true
+    }
+
+    /// Record how a launch ended and publish it.
+    fn finish(&self, name: &str, generation: u64, outcome: Outcome) {
+        let mut running = self.running.lock();
+        if running
+            .get(name)
+            .is_some_and(|r| r.generation == generation)
+        {
+            running.remove(name);
+        }
+        match outcome {
+            Outcome::Exited(Some(0)) => {
+                info!("process.{}: exited", name);
+                self.emit_outcome(name, Some(0), "exited");
+            },
+            Outcome::Exited(code) => {
+                warn!("process.{}: exited with {:?}", name, code);
+                self.emit_outcome(name, code, "failed");
+            },
+            Outcome::Stopped => {
+                info!("process.{}: stopped", name);
+                self.emit_outcome(name, None, "stopped");
+            },
+            Outcome::Timeout => {
+                warn!("process.{}: killed after timeout", name);
+                self.emit_outcome(name, None, "timeout");
+            },
+        }
+    }
+
+    fn emit_outcome(&self, name: &str, code: Option<i32>, status: &str) {
+        self.indicators.emit_if_changed(
+            &signals::name(name, signals::EXIT_CODE),
+            code.map_or(Value::Null, Value::from),
+        );
+        self.indicators.emit_if_changed(
+            &signals::name(name, signals::STATUS),
+            Value::String(status.to_string()),
+        );
+        self.indicators
+            .emit_if_changed(&signals::name(name, signals::RUNNING), Value::Bool(false));
+    }
+
+    /// Publish the last non-empty stdout line until the pipe closes.
+    fn spawn_stdout_reader(&self, name: &str, stdout: tokio::process::ChildStdout) {
+        let shared = self.clone();
+        let signal = signals::name(name, signals::STDOUT);
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        let line = line.trim();
+                        if !line.is_empty() {
+                            let line: String = line.chars().take(MAX_STDOUT_CHARS).collect();
+                            shared
+                                .indicators
+                                .emit_if_changed(&signal, Value::String(line));
+                        }
+                    },
+                    Ok(None) => break,
+                    Err(e) => {
+                        debug!("{}: stdout read failed: {}", signal, e);
+                        break;
+                    },
+                }
+            }
+        });
+    }
+}
+
+/// Wait for the child to exit, to be stopped, or to run past `timeout`.
+async fn supervise(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    stop_rx: oneshot::Receiver<()>,
+) -> Outcome {
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let outcome = tokio::select! {
+        status = child.wait() => {
+            return match status {
+                Ok(status) => Outcome::Exited(status.code()),
+                Err(e) => {
+                    warn!("process: wait failed: {}", e);
+                    Outcome::Exited(None)
+                },
+            };
+        },
+        _ = stop_rx => Outcome::Stopped,
+        _ = deadline => Outcome::Timeout,
+    };
+    if let Err(e) = child.kill().await {
+        debug!("process: kill failed (already exited?): {}", e);
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_and_signal_names() {
+        assert_eq!(ProcessAction::parse("kill").unwrap(), ProcessAction::Stop);
+        assert_eq!(
+            ProcessAction::parse("toggle").unwrap(),
+            ProcessAction::Toggle
+        );
+        assert!(ProcessAction::parse("exec").is_err());
+        assert_eq!(
+            signals::name("capture", signals::EXIT_CODE),
+            "process.capture.exit_code"
+        );
+    }
+
+    #[test]
+    fn only_allowlisted_commands_resolve() {
+        let config: ProcessConfig =
+            serde_yaml::from_str("commands:\n  capture:\n    program: capture-tool\n").unwrap();
+        let driver = ProcessDriver::new(config);
+        assert!(driver
+            .shared
+            .command(Some(&Value::String("capture".into())))
+            .is_ok());
+        assert!(driver
+            .shared
+            .command(Some(&Value::String("/bin/sh".into())))
+            .is_err());
+        assert!(driver.shared.command(None).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Argument templates: `{1}`..`{9}` replaced by the action's extra params.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Expand the `{N}` placeholders of every argument with `params[N - 1]`.
+/// Other braces are kept as-is. Each template stays a single argument:
+/// values are never split on whitespace nor passed through a shell.
+pub(super) fn expand_args(args: &[String], params: &[Value]) -> Result<Vec<String>> {
+    args.iter().map(|arg| expand(arg, params)).collect()
+}
+
+fn expand(arg: &str, params: &[Value]) -> Result<String> {
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(len) if len > 0 && after[..len].bytes().all(|b| b.is_ascii_digit()) => {
+                let index: usize = after[..len].parse()?;
+                let Some(value) = index.checked_sub(1).and_then(|i| params.get(i)) else {
+                    bail!(
+                        "argument '{}' needs param {{{}}}, got {} extra param(s)",
+                        arg,
+                        index,
+                        params.len()
+                    );
+                };
+                out.push_str(&param_to_string(value));
+                rest = &after[len + 1..];
+            },
+            _ => {
+                out.push('{');
+                rest = after;
+            },
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn param_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn placeholders_are_replaced_in_place() {
+        let expanded = expand_args(
+            &args(&["--scene={1}", "{2}", "-o", "{1} {1}"]),
+            &[json!("Main Cam"), json!(3)],
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args(&["--scene=Main Cam", "3", "-o", "Main Cam Main Cam"])
+        );
+    }
+
+    #[test]
+    fn literal_braces_are_kept() {
+        let expanded = expand_args(&args(&["{\"a\":1}", "{x}", "{", "{}"]), &[]).unwrap();
+        assert_eq!(expanded, args(&["{\"a\":1}", "{x}", "{", "{}"]));
+    }
+
+    #[test]
+    fn missing_param_is_an_error() {
+        assert!(expand_args(&args(&["{2}"]), &[json!("only one")]).is_err());
+        assert!(expand_args(&args(&["{0}"]), &[json!("x")]).is_err());
+    }
+}
@@ -0,0 +1,504 @@
+//! PulseAudio backend task: owns the server connection and mirrors the
+//! WinAudio COM thread API so the driver front-end stays identical.
+//!
+//! Public API is the [`BackendHandle`]: spawn the task, send commands
+//! over an `mpsc` channel, and shut it down on driver teardown. The task
+//! reconnects on its own when the server restarts (e.g. a PipeWire
+//! upgrade), re-subscribing and re-pushing full state each time.
+//!
+//! Module is `#[cfg(target_os = "linux")]`-gated at its parent declaration
+//! in `mod.rs`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::connection::{self, Connection};
+use super::protocol::{self, command, subscription, Facility, SinkInput, TagReader};
+
+/// Delay between reconnect attempts while the server is unreachable.
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Bounded capacity for the command queue. Same trade-off as the WinAudio
+/// COM thread: senders use `try_send` and a stale fader sample is dropped
+/// rather than growing the queue while the server is slow.
+const CMD_QUEUE: usize = 64;
+/// Bounded capacity for the event queue.
+const EVENT_QUEUE: usize = 256;
+
+#[derive(Debug)]
+enum AudioCmd {
+    SetMasterScalar(f32),
+    ToggleMasterMute,
+    /// Re-read the default sink and push `AudioEvent::MasterVolumeChanged`.
+    RefreshMaster,
+    /// Re-read every sink input and push one snapshot per process, plus
+    /// `AudioEvent::ActiveSessionsChanged`.
+    RefreshSessions,
+    /// Set volume (0.0..=1.0) on every stream whose process name matches
+    /// `process_name_lc`. Silently ignored if none exists.
+    SetSessionScalar {
+        process_name_lc: String,
+        scalar: f32,
+    },
+    ToggleSessionMute {
+        process_name_lc: String,
+    },
+    EnumerateSessions {
+        reply: tokio::sync::oneshot::Sender<Vec<String>>,
+    },
+    Shutdown,
+}
+
+/// Same shape as the WinAudio `AudioEvent` so the driver consumer can be
+/// mirrored one-to-one.
+#[derive(Debug, Clone)]
+pub enum AudioEvent {
+    MasterVolumeChanged {
+        scalar: f32,
+        mute: bool,
+    },
+    /// One per process (streams of the same binary are folded together).
+    SessionVolumeSnapshot {
+        process_name_lc: String,
+        scalar: f32,
+        mute: bool,
+    },
+    /// Lowercase process names of every current stream, in stream
+    /// creation order, no duplicates.
+    ActiveSessionsChanged {
+        names_lc: Vec<String>,
+    },
+}
+
+pub struct BackendHandle {
+    cmd_tx: mpsc::Sender<AudioCmd>,
+    event_rx: Arc<Mutex<Option<mpsc::Receiver<AudioEvent>>>>,
+    join: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BackendHandle {
+    /// Spawn the backend task. `server` overrides socket discovery (see
+    /// [`connection::socket_path`]).
+    pub fn spawn(server: Option<String>) -> Self {
+        let (cmd_tx, cmd_rx) = mpsc::channel::<AudioCmd>(CMD_QUEUE);
+        let (event_tx, event_rx) = mpsc::channel::<AudioEvent>(EVENT_QUEUE);
+        let join = tokio::spawn(run_backend(server, cmd_rx, event_tx));
+        Self {
+            cmd_tx,
+            event_rx: Arc::new(Mutex::new(Some(event_rx))),
+            join: Mutex::new(Some(join)),
+        }
+    }
+
+    pub fn set_master_scalar(&self, scalar: f32) {
+        let _ = self.cmd_tx.try_send(AudioCmd::SetMasterScalar(scalar));
+    }
+
+    pub fn toggle_master_mute(&self) {
+        let _ = self.cmd_tx.try_send(AudioCmd::ToggleMasterMute);
+    }
+
+    pub fn refresh_master(&self) {
+        let _ = self.cmd_tx.try_send(AudioCmd::RefreshMaster);
+    }
+
+    pub fn refresh_sessions(&self) {
+        let _ = self.cmd_tx.try_send(AudioCmd::RefreshSessions);
+    }
+
+    pub fn set_session_scalar(&self, process_name_lc: String, scalar: f32) {
+        let _ = self.cmd_tx.try_send(AudioCmd::SetSessionScalar {
+            process_name_lc,
+            scalar,
+        });
+    }
+
+    pub fn toggle_session_mute(&self, process_name_lc: String) {
+        let _ = self
+            .cmd_tx
+            .try_send(AudioCmd::ToggleSessionMute { process_name_lc });
+    }
+
+    pub async fn enumerate_sessions(&self) -> Vec<String> {
+        let (reply, rx) = tokio::sync::oneshot::channel();
+        if self
+            .cmd_tx
+            .send(AudioCmd::EnumerateSessions { reply })
+            .await
+            .is_err()
+        {
+            return Vec::new();
+        }
+        rx.await.unwrap_or_default()
+    }
+
+    /// Take the event receiver (one-shot — only one consumer is supported).
+    pub async fn take_event_rx(&self) -> Option<mpsc::Receiver<AudioEvent>> {
+        self.event_rx.lock().await.take()
+    }
+
+    pub async fn shutdown(self) {
+        let _ = self.cmd_tx.send(AudioCmd::Shutdown).await;
+        if let Some(join) = self.join.lock().await.take() {
+            if let Err(e) = join.await {
+                warn!("PulseAudio backend task join failed: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Why a connected session ended.
+enum SessionEnd {
+    Shutdown,
+    Disconnected(anyhow::Error),
+}
+
+async fn run_backend(
+    server: Option<String>,
+    mut cmd_rx: mpsc::Receiver<AudioCmd>,
+    event_tx: mpsc::Sender<AudioEvent>,
+) {
+    // Log the first failure at `warn`, repeats at `debug`, so a host
+    // without a sound server doesn't flood the log every few seconds.
+    let mut reported_failure = false;
+    loop {
+        match open(server.as_deref()).await {
+            Ok(conn) => {
+                reported_failure = false;
+                info!("PulseAudio connected");
+                let mut mixer = Mixer::new(conn, event_tx.clone());
+                match mixer.run(&mut cmd_rx).await {
+                    SessionEnd::Shutdown => break,
+                    SessionEnd::Disconnected(e) => warn!("PulseAudio disconnected: {}", e),
+                }
+            },
+            Err(e) if !reported_failure => {
+                reported_failure = true;
+                warn!(
+                    "PulseAudio unavailable: {} — retrying every {:?}",
+                    e, RECONNECT_DELAY
+                );
+            },
+            Err(e) => debug!("PulseAudio reconnect failed: {}", e),
+        }
+
+        if !idle_until_retry(&mut cmd_rx).await {
+            break;
+        }
+    }
+    debug!("PulseAudio backend stopped");
+}
+
+async fn open(server: Option<&str>) -> Result<Connection> {
+    let path: PathBuf = connection::socket_path(server).ok_or_else(|| {
+        anyhow::anyhow!("no server socket (set `pulseaudio.server` or $XDG_RUNTIME_DIR)")
+    })?;
+    Connection::open(&path).await
+}
+
+/// Drain commands while disconnected so senders never block. Returns
+/// `false` on shutdown.
+async fn idle_until_retry(cmd_rx: &mut mpsc::Receiver<AudioCmd>) -> bool {
+    let deadline = tokio::time::sleep(RECONNECT_DELAY);
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => return true,
+            cmd = cmd_rx.recv() => match cmd {
+                None | Some(AudioCmd::Shutdown) => return false,
+                Some(AudioCmd::EnumerateSessions { reply }) => {
+                    let _ = reply.send(Vec::new());
+                },
+                Some(_) => {},
+            },
+        }
+    }
+}
+
+/// Aggregated per-process state emitted to the driver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProcessState {
+    scalar: f32,
+    mute: bool,
+}
+
+/// Fold the streams of each process into one fader state: the loudest
+/// stream drives the fader, and the process counts as muted only when
+/// every stream is.
+fn fold_by_process(inputs: &[SinkInput]) -> (Vec<String>, HashMap<String, ProcessState>) {
+    let mut order = Vec::new();
+    let mut states: HashMap<String, ProcessState> = HashMap::new();
+    for input in inputs {
+        let Some(name) = input.process_name_lc.as_ref() else {
+            continue;
+        };
+        let scalar = protocol::volume_to_scalar(&input.volume);
+        match states.get_mut(name) {
+            Some(state) => {
+                state.scalar = state.scalar.max(scalar);
+                state.mute &= input.mute;
+            },
+            None => {
+                order.push(name.clone());
+                states.insert(
+                    name.clone(),
+                    ProcessState {
+                        scalar,
+                        mute: input.mute,
+                    },
+                );
+            },
+        }
+    }
+    (order, states)
+}
+
+/// Connected state: the connection plus the last snapshot pushed to the
+/// driver, used to emit only what changed on subscription events.
+struct Mixer {
+    conn: Connection,
+    event_tx: mpsc::Sender<AudioEvent>,
+    master_channels: u8,
+    master_mute: bool,
+    inputs: Vec<SinkInput>,
+    active: Vec<String>,
+    last_states: HashMap<String, ProcessState>,
+}
+
+impl Mixer {
+    fn new(conn: Connection, event_tx: mpsc::Sender<AudioEvent>) -> Self {
+        Self {
+            conn,
+            event_tx,
+            master_channels: 2,
+            master_mute: false,
+            inputs: Vec::new(),
+            active: Vec::new(),
+            last_states: HashMap::new(),
+        }
+    }
+
+    async fn run(&mut self, cmd_rx: &mut mpsc::Receiver<AudioCmd>) -> SessionEnd {
+        if let Err(e) = self.start().await {
+            return SessionEnd::Disconnected(e);
+        }
+        loop {
+            tokio::select! {
+                cmd = cmd_rx.recv() => {
+                    let Some(cmd) = cmd else {
+                        return SessionEnd::Shutdown;
+                    };
+                    if matches!(cmd, AudioCmd::Shutdown) {
+                        return SessionEnd::Shutdown;
+                    }
+                    if let Err(e) = self.handle_cmd(cmd).await {
+                        return SessionEnd::Disconnected(e);
+                    }
+                }
+                event = self.conn.next_event() => {
+                    let Some(first) = event else {
+                        return SessionEnd::Disconnected(anyhow::anyhow!("connection closed"));
+                    };
+                    // Coalesce a burst (a fader sweep in pavucontrol) into
+                    // one re-read per facility.
+                    let mut facilities = self.conn.drain_events();
+                    facilities.push(first);
+                    if let Err(e) = self.handle_events(&facilities).await {
+                        return SessionEnd::Disconnected(e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Subscribe and push the initial full state.
+    async fn start(&mut self) -> Result<()> {
+        self.conn
+            .request(command::SUBSCRIBE, |w| {
+                w.put_u32(
+                    subscription::MASK_SINK
+                        | subscription::MASK_SINK_INPUT
+                        | subscription::MASK_SERVER,
+                );
+            })
+            .await?;
+        self.refresh_master().await?;
+        self.refresh_sessions(true).await
+    }
+
+    async fn handle_cmd(&mut self, cmd: AudioCmd) -> Result<()> {
+        match cmd {
+            // Setters don't emit: the server echoes a change event which
+            // re-reads and pushes the resulting state.
+            AudioCmd::SetMasterScalar(scalar) => {
+                let channels = self.master_channels;
+                self.conn
+                    .request(command::SET_SINK_VOLUME, |w| {
+                        w.put_u32(protocol::INVALID_INDEX);
+                        w.put_string(Some(protocol::DEFAULT_SINK));
+                        w.put_cvolume(channels, protocol::scalar_to_volume(scalar));
+                    })
+                    .await?;
+            },
+            AudioCmd::ToggleMasterMute => {
+                self.refresh_master().await?;
+                let mute = !self.master_mute;
+                self.conn
+                    .request(command::SET_SINK_MUTE, |w| {
+                        w.put_u32(protocol::INVALID_INDEX);
+                        w.put_string(Some(protocol::DEFAULT_SINK));
+                        w.put_bool(mute);
+                    })
+                    .await?;
+            },
+            AudioCmd::RefreshMaster => self.refresh_master().await?,
+            AudioCmd::RefreshSessions => self.refresh_sessions(true).await?,
+            AudioCmd::SetSessionScalar {
+                process_name_lc,
+                scalar,
+            } => {
+                let volume = protocol::scalar_to_volume(scalar);
+                for (index, channels) in self.streams_of(&process_name_lc) {
+                    self.conn
+                        .request(command::SET_SINK_INPUT_VOLUME, |w| {
+                            w.put_u32(index);
+                            w.put_cvolume(channels, volume);
+                        })
+                        .await?;
+                }
+            },
+            AudioCmd::ToggleSessionMute { process_name_lc } => {
+                let Some(state) = self.last_states.get(&process_name_lc).copied() else {
+                    debug!("session_mute: no stream for '{}'", process_name_lc);
+                    return Ok(());
+                };
+                for (index, _) in self.streams_of(&process_name_lc) {
+                    self.conn
+                        .request(command::SET_SINK_INPUT_MUTE, |w| {
+                            w.put_u32(index);
+                            w.put_bool(!state.mute);
+                        })
+                        .await?;
+                }
+            },
+            AudioCmd::EnumerateSessions { reply } => {
+                let _ = reply.send(self.active.clone());
+            },
+            AudioCmd::Shutdown => {},
+        }
+        Ok(())
+    }
+
+    async fn handle_events(&mut self, facilities: &[Facility]) -> Result<()> {
+        let server = facilities.contains(&Facility::Server);
+        // A default-sink switch shows up as a server event.
+        if server || facilities.contains(&Facility::Sink) {
+            self.refresh_master().await?;
+        }
+        if server || facilities.contains(&Facility::SinkInput) {
+            self.refresh_sessions(false).await?;
+        }
+        Ok(())
+    }
+
+    /// `(index, channel count)` of every stream owned by `process_name_lc`.
+    fn streams_of(&self, process_name_lc: &str) -> Vec<(u32, u8)> {
+        self.inputs
+            .iter()
+            .filter(|i| i.process_name_lc.as_deref() == Some(process_name_lc))
+            .map(|i| (i.index, i.volume.len().max(1) as u8))
+            .collect()
+    }
+
+    async fn refresh_master(&mut self) -> Result<()> {
+        let reply = self
+            .conn
+            .request(command::GET_SINK_INFO, |w| {
+                w.put_u32(protocol::INVALID_INDEX);
+                w.put_string(Some(protocol::DEFAULT_SINK));
+            })
+            .await?;
+        let sink = protocol::read_sink_volume(&mut TagReader::new(&reply))?;
+        self.master_channels = sink.volume.len().max(1) as u8;
+        self.master_mute = sink.mute;
+        self.emit(AudioEvent::MasterVolumeChanged {
+            scalar: protocol::volume_to_scalar(&sink.volume),
+            mute: sink.mute,
+        });
+        Ok(())
+    }
+
+    /// Re-read all sink inputs. With `force`, every process snapshot is
+    /// pushed (page activation); otherwise only the ones that changed.
+    async fn refresh_sessions(&mut self, force: bool) -> Result<()> {
+        let reply = self
+            .conn
+            .request(command::GET_SINK_INPUT_INFO_LIST, |_| {})
+            .await?;
+        self.inputs = protocol::read_sink_input_list(&mut TagReader::new(&reply))?;
+
+        let (order, states) = fold_by_process(&self.inputs);
+        for name in &order {
+            let state = states[name];
+            if force || self.last_states.get(name) != Some(&state) {
+                self.emit(AudioEvent::SessionVolumeSnapshot {
+                    process_name_lc: name.clone(),
+                    scalar: state.scalar,
+                    mute: state.mute,
+                });
+            }
+        }
+        if force || order != self.active {
+            self.emit(AudioEvent::ActiveSessionsChanged {
+                names_lc: order.clone(),
+            });
+        }
+        self.active = order;
+        self.last_states = states;
+        Ok(())
+    }
+
+    fn emit(&self, event: AudioEvent) {
+        if self.event_tx.try_send(event).is_err() {
+            debug!("PulseAudio event queue full, dropping event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(index: u32, name: Option<&str>, volume: u32, mute: bool) -> SinkInput {
+        SinkInput {
+            index,
+            volume: vec![volume, volume],
+            mute,
+            process_name_lc: name.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn fold_keeps_creation_order_and_merges_streams() {
+        let half = protocol::VOLUME_NORM / 2;
+        let inputs = vec![
+            input(10, Some("firefox"), half, true),
+            input(11, None, protocol::VOLUME_NORM, false),
+            input(12, Some("spotify"), half, false),
+            input(13, Some("firefox"), protocol::VOLUME_NORM, false),
+        ];
+        let (order, states) = fold_by_process(&inputs);
+        assert_eq!(order, vec!["firefox".to_string(), "spotify".to_string()]);
+        let firefox = states["firefox"];
+        assert!((firefox.scalar - 1.0).abs() < 1e-6);
+        assert!(!firefox.mute, "muted only when every stream is");
+        assert!((states["spotify"].scalar - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,31 @@
+//! Static action catalog for the PulseAudio driver.
+//!
+//! Same actions and `target` syntax as the WinAudio catalog; the
+//! `pulseaudio.target` picker lists the slots of `pulseaudio.pinned_apps`.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the static PulseAudio action catalog.
+pub fn pulseaudio_catalog() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::simple("master_volume", "Master volume")
+            .with_description("Drive the default sink volume from a fader (14-bit PitchBend)."),
+        ActionDescriptor::simple("master_mute", "Master mute")
+            .with_description("Toggle the default sink mute on button press."),
+        ActionDescriptor::simple("session_volume", "Stream volume")
+            .with_description("Drive the volume of every stream of an application from a fader.")
+            .with_param(
+                ParamDescriptor::new("target", ParamKind::String)
+                    .with_picker("pulseaudio.target")
+                    .with_default(json!("auto")),
+            ),
+        ActionDescriptor::simple("session_mute", "Stream mute")
+            .with_description("Toggle the mute of every stream of an application on button press.")
+            .with_param(
+                ParamDescriptor::new("target", ParamKind::String)
+                    .with_picker("pulseaudio.target")
+                    .with_default(json!("auto")),
+            ),
+    ]
+}
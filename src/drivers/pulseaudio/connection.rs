@@ -0,0 +1,278 @@
+//! Async PulseAudio native-protocol connection over the server's Unix socket.
+//!
+//! A reader task decodes control packets into an `mpsc` queue so the
+//! backend loop can `select!` on it (cancel-safe) alongside its command
+//! queue. Requests are sequential: `request` writes one command and waits
+//! for the reply carrying the same tag, parking any subscription events
+//! that arrive in between for [`Connection::next_event`].
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::UnixStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, trace};
+
+use super::protocol::{self, command, Facility, TagReader, TagWriter};
+
+/// Connect + handshake timeout. The server is local; anything slower is
+/// treated as unavailable so the reconnect loop keeps its cadence.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Reply timeout for a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Bounded capacity for decoded packets. A fader sweep produces one
+/// change event per step; the backend drains them in bulk.
+const PACKET_QUEUE: usize = 256;
+
+/// Resolve the server socket: explicit config, then `$PULSE_SERVER`
+/// (`unix:` prefix optional), then `$XDG_RUNTIME_DIR/pulse/native`.
+pub(super) fn socket_path(configured: Option<&str>) -> Option<PathBuf> {
+    socket_path_from(
+        configured,
+        std::env::var("PULSE_SERVER").ok().as_deref(),
+        std::env::var("XDG_RUNTIME_DIR").ok().as_deref(),
+    )
+}
+
+fn socket_path_from(
+    configured: Option<&str>,
+    pulse_server: Option<&str>,
+    runtime_dir: Option<&str>,
+) -> Option<PathBuf> {
+    if let Some(server) = configured.or(pulse_server).filter(|s| !s.is_empty()) {
+        let path = server.strip_prefix("unix:").unwrap_or(server);
+        return Some(PathBuf::from(path));
+    }
+    runtime_dir.map(|dir| Path::new(dir).join("pulse").join("native"))
+}
+
+/// Read the auth cookie (`$PULSE_COOKIE`, `~/.config/pulse/cookie`,
+/// legacy `~/.pulse-cookie`). PipeWire ignores it, and a PulseAudio
+/// server with `auth-anonymous` accepts zeros, so a missing cookie is
+/// not fatal.
+fn read_cookie() -> Vec<u8> {
+    let candidates = std::env::var_os("PULSE_COOKIE")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(dirs::config_dir().map(|d| d.join("pulse").join("cookie")))
+        .chain(dirs::home_dir().map(|d| d.join(".pulse-cookie")));
+    for path in candidates {
+        if let Ok(cookie) = std::fs::read(&path) {
+            if cookie.len() == protocol::COOKIE_LEN {
+                return cookie;
+            }
+        }
+    }
+    vec![0; protocol::COOKIE_LEN]
+}
+
+pub(super) struct Connection {
+    writer: OwnedWriteHalf,
+    packets: mpsc::Receiver<Vec<u8>>,
+    events: VecDeque<Facility>,
+    next_tag: u32,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl Connection {
+    /// Connect, authenticate and register as a client.
+    pub(super) async fn open(path: &Path) -> Result<Self> {
+        tokio::time::timeout(CONNECT_TIMEOUT, Self::open_inner(path))
+            .await
+            .context("Timed out connecting to PulseAudio")?
+    }
+
+    async fn open_inner(path: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(path)
+            .await
+            .with_context(|| format!("Failed to connect to {}", path.display()))?;
+        let (mut read_half, writer) = stream.into_split();
+
+        let (packet_tx, packets) = mpsc::channel(PACKET_QUEUE);
+        let reader = tokio::spawn(async move {
+            if let Err(e) = read_packets(&mut read_half, packet_tx).await {
+                debug!("PulseAudio reader stopped: {}", e);
+            }
+        });
+
+        let mut conn = Self {
+            writer,
+            packets,
+            events: VecDeque::new(),
+            next_tag: 0,
+            reader,
+        };
+
+        let cookie = read_cookie();
+        let reply = conn
+            .request(command::AUTH, |w| {
+                w.put_u32(protocol::PROTOCOL_VERSION);
+                w.put_arbitrary(&cookie);
+            })
+            .await
+            .context("PulseAudio authentication failed")?;
+        // High bits carry shm/memfd flags.
+        let server_version = TagReader::new(&reply).get_u32()? & 0xFFFF;
+        if server_version < protocol::MIN_PROTOCOL_VERSION {
+            bail!(
+                "PulseAudio server protocol version {} is too old (need >= {})",
+                server_version,
+                protocol::MIN_PROTOCOL_VERSION
+            );
+        }
+
+        conn.request(command::SET_CLIENT_NAME, |w| {
+            w.put_proplist(&[
+                ("application.name", "xtouch-gw"),
+                ("application.id", "xtouch-gw"),
+            ]);
+        })
+        .await?;
+
+        debug!(
+            "PulseAudio handshake done (server protocol v{})",
+            server_version
+        );
+        Ok(conn)
+    }
+
+    /// Send one command and wait for its reply body (after command + tag).
+    pub(super) async fn request(
+        &mut self,
+        cmd: u32,
+        build: impl FnOnce(&mut TagWriter),
+    ) -> Result<Vec<u8>> {
+        let tag = self.next_tag;
+        self.next_tag = self.next_tag.wrapping_add(1) & 0x7FFF_FFFF;
+
+        let mut writer = TagWriter::command(cmd, tag);
+        build(&mut writer);
+        self.writer
+            .write_all(&writer.into_frame())
+            .await
+            .context("Failed to write to PulseAudio")?;
+
+        tokio::time::timeout(REQUEST_TIMEOUT, self.wait_reply(tag))
+            .await
+            .map_err(|_| anyhow!("PulseAudio request {} timed out", cmd))?
+    }
+
+    async fn wait_reply(&mut self, tag: u32) -> Result<Vec<u8>> {
+        loop {
+            let payload = self
+                .packets
+                .recv()
+                .await
+                .ok_or_else(|| anyhow!("PulseAudio connection closed"))?;
+            let (cmd, reply_tag, mut body) = protocol::parse_packet(&payload)?;
+            match cmd {
+                command::SUBSCRIBE_EVENT => {
+                    self.events
+                        .push_back(protocol::read_subscribe_event(&mut body)?);
+                },
+                command::REPLY if reply_tag == tag => {
+                    // Header is two `u32` tags: 1 + 4 bytes each.
+                    return Ok(payload[10..].to_vec());
+                },
+                command::ERROR if reply_tag == tag => {
+                    let code = body.get_u32().unwrap_or_default();
+                    bail!("PulseAudio error {}", code);
+                },
+                other => trace!("PulseAudio: ignoring packet {} (tag {})", other, reply_tag),
+            }
+        }
+    }
+
+    /// Next subscription event. `None` once the connection is closed.
+    /// Cancel-safe: only awaits the packet queue.
+    pub(super) async fn next_event(&mut self) -> Option<Facility> {
+        if let Some(event) = self.events.pop_front() {
+            return Some(event);
+        }
+        loop {
+            let payload = self.packets.recv().await?;
+            let Ok((cmd, _, mut body)) = protocol::parse_packet(&payload) else {
+                continue;
+            };
+            if cmd == command::SUBSCRIBE_EVENT {
+                if let Ok(event) = protocol::read_subscribe_event(&mut body) {
+                    return Some(event);
+                }
+            }
+        }
+    }
+
+    /// Events already received but not yet consumed (non-blocking).
+    pub(super) fn drain_events(&mut self) -> Vec<Facility> {
+        while let Ok(payload) = self.packets.try_recv() {
+            if let Ok((command::SUBSCRIBE_EVENT, _, mut body)) = protocol::parse_packet(&payload) {
+                if let Ok(event) = protocol::read_subscribe_event(&mut body) {
+                    self.events.push_back(event);
+                }
+            }
+        }
+        self.events.drain(..).collect()
+    }
+}
+
+/// Reader task body: decode framed control packets until EOF or error.
+async fn read_packets(
+    read_half: &mut tokio::net::unix::OwnedReadHalf,
+    packet_tx: mpsc::Sender<Vec<u8>>,
+) -> Result<()> {
+    let mut header = [0u8; protocol::FRAME_HEADER_LEN];
+    loop {
+        read_half.read_exact(&mut header).await?;
+        let (len, channel) = protocol::parse_frame_header(&header);
+        if len > protocol::MAX_PAYLOAD_LEN {
+            bail!("PulseAudio packet too large ({} bytes)", len);
+        }
+        let mut payload = vec![0u8; len];
+        read_half.read_exact(&mut payload).await?;
+        if channel != protocol::CONTROL_CHANNEL {
+            // Audio data: the driver never creates streams.
+            continue;
+        }
+        if packet_tx.send(payload).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_path_prefers_config_then_env() {
+        assert_eq!(
+            socket_path_from(
+                Some("/tmp/pulse.sock"),
+                Some("unix:/x"),
+                Some("/run/user/1000")
+            ),
+            Some(PathBuf::from("/tmp/pulse.sock"))
+        );
+        assert_eq!(
+            socket_path_from(None, Some("unix:/x/native"), Some("/run/user/1000")),
+            Some(PathBuf::from("/x/native"))
+        );
+        assert_eq!(
+            socket_path_from(None, None, Some("/run/user/1000")),
+            Some(PathBuf::from("/run/user/1000/pulse/native"))
+        );
+        assert_eq!(socket_path_from(None, None, None), None);
+    }
+}
@@ -0,0 +1,566 @@
+//! Linux audio driver: master sink + per-app stream volume/mute control.
+//!
+//! Talks the PulseAudio native protocol directly over the server socket,
+//! which `pipewire-pulse` implements too. Same model as the WinAudio
+//! driver — `master_*` / `session_*` actions, `pinned_apps`, the
+//! discovery FIFO and dynamic LCD colors all come from
+//! [`crate::drivers::winaudio::mapping`] unchanged — only the backend
+//! differs: sink inputs stand in for audio sessions, the default sink for
+//! the render endpoint, and a subscription replaces the COM callbacks.
+//!
+//! On non-Linux targets this driver is a no-op stub that logs every
+//! action, mirroring the WinAudio stub on non-Windows hosts.
+
+#[cfg(target_os = "linux")]
+mod backend;
+mod catalog;
+#[cfg(target_os = "linux")]
+mod connection;
+#[cfg(target_os = "linux")]
+mod protocol;
+
+use crate::config::PulseAudioConfig;
+use crate::drivers::winaudio::{
+    auto_strip_index, mapping, normalize_fader_value, page_uses_app, parse_session_target,
+    render_session_lcd_if_active, SessionTarget,
+};
+use crate::drivers::{Driver, ExecutionContext};
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info, warn};
+
+/// Driver name used for `app: "pulseaudio"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "pulseaudio";
+
+/// Item pushed onto the unified feedback channel: `(app_name, raw_midi_bytes)`.
+type FeedbackMsg = (String, Vec<u8>);
+
+/// Optional, post-construction-wired sender for the unified feedback channel.
+type FeedbackSender = Arc<RwLock<Option<mpsc::Sender<FeedbackMsg>>>>;
+
+type RouterSlot = Arc<RwLock<Option<Arc<crate::router::Router>>>>;
+type LedSender = Arc<RwLock<Option<mpsc::Sender<Vec<u8>>>>>;
+
+/// True if the page at `index` in the router's current config binds
+/// `app: "pulseaudio"`.
+async fn page_eligible_at_index(router_arc: &RouterSlot, index: usize) -> bool {
+    let Some(router) = router_arc.read().await.clone() else {
+        return false;
+    };
+    let cfg = router.config.read().await;
+    cfg.pages
+        .get(index)
+        .is_some_and(|p| page_uses_app(p, DRIVER_NAME))
+}
+
+pub struct PulseAudioDriver {
+    config: Arc<RwLock<PulseAudioConfig>>,
+    initialized: AtomicBool,
+    /// Wired post-construction via [`PulseAudioDriver::set_router`].
+    router: RouterSlot,
+    /// Wired post-construction via [`PulseAudioDriver::set_led_sender`].
+    led_tx: LedSender,
+    /// Wired post-construction. The backend event consumer injects
+    /// synthetic `("pulseaudio", raw_midi)` feedback through it.
+    feedback_tx: FeedbackSender,
+    /// Stable FIFO of non-pinned process names; never reorders existing entries.
+    discovery: Arc<RwLock<mapping::DiscoveryState>>,
+    /// Lowercased set of pinned process names, refreshed on init and `sync()`.
+    pinned_lc_cache: Arc<ArcSwap<HashSet<String>>>,
+    /// Signals the page-watcher task to stop on `shutdown()`.
+    page_watcher_shutdown: tokio::sync::watch::Sender<bool>,
+    #[cfg(target_os = "linux")]
+    backend: Arc<RwLock<Option<backend::BackendHandle>>>,
+}
+
+impl PulseAudioDriver {
+    pub fn new(config: PulseAudioConfig) -> Self {
+        let pinned_lc = mapping::pinned_lc_set(&config.pinned_apps);
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            initialized: AtomicBool::new(false),
+            router: Arc::new(RwLock::new(None)),
+            led_tx: Arc::new(RwLock::new(None)),
+            feedback_tx: Arc::new(RwLock::new(None)),
+            discovery: Arc::new(RwLock::new(mapping::DiscoveryState::default())),
+            pinned_lc_cache: Arc::new(ArcSwap::from_pointee(pinned_lc)),
+            page_watcher_shutdown: tokio::sync::watch::channel(false).0,
+            #[cfg(target_os = "linux")]
+            backend: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    async fn refresh_pinned_lc(&self) {
+        let cfg = self.config.read().await;
+        let new_set = mapping::pinned_lc_set(&cfg.pinned_apps);
+        drop(cfg);
+        self.pinned_lc_cache.store(Arc::new(new_set));
+    }
+
+    /// Wire the driver to the router so it can push LCD updates.
+    pub async fn set_router(&self, router: Arc<crate::router::Router>) {
+        *self.router.write().await = Some(router);
+    }
+
+    /// Wire the driver to the LED MIDI channel drained into the X-Touch.
+    pub async fn set_led_sender(&self, tx: mpsc::Sender<Vec<u8>>) {
+        *self.led_tx.write().await = Some(tx);
+    }
+
+    /// Wire the driver to the unified feedback channel.
+    pub async fn set_feedback_sender(&self, tx: mpsc::Sender<FeedbackMsg>) {
+        *self.feedback_tx.write().await = Some(tx);
+    }
+}
+
+#[async_trait]
+impl Driver for PulseAudioDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, _ctx: ExecutionContext) -> Result<()> {
+        info!("PulseAudio driver initializing");
+
+        self.refresh_pinned_lc().await;
+
+        // Pinned apps share the discovered apps' 1..=7 color cycle.
+        {
+            let cfg = self.config.read().await;
+            let pinned_lc: Vec<String> = cfg
+                .pinned_apps
+                .iter()
+                .map(|p| p.process_name.to_lowercase())
+                .collect();
+            drop(cfg);
+            let mut state = self.discovery.write().await;
+            state.observe_pinned(&pinned_lc);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let server = self.config.read().await.server.clone();
+            let handle = backend::BackendHandle::spawn(server);
+            if let Some(event_rx) = handle.take_event_rx().await {
+                let feedback = self.feedback_tx.read().await.clone();
+                if let Some(feedback) = feedback {
+                    tokio::spawn(run_event_consumer(
+                        event_rx,
+                        feedback,
+                        self.config.clone(),
+                        self.pinned_lc_cache.clone(),
+                        self.discovery.clone(),
+                        self.router.clone(),
+                        self.led_tx.clone(),
+                    ));
+                } else {
+                    warn!(
+                        "PulseAudio: no feedback sender wired; volume changes won't reach the X-Touch"
+                    );
+                }
+            }
+            *self.backend.write().await = Some(handle);
+
+            self.spawn_page_watcher().await;
+
+            // Initial state push once the active page is settled (see
+            // `wait_for_profile_loaded`). The backend pushes its own
+            // snapshot on connect too; this covers the case where it
+            // connected before the router page filter was ready.
+            let live_rx = match self.router.read().await.as_ref() {
+                Some(r) => r.live_tx_snapshot().await.map(|tx| tx.subscribe()),
+                None => None,
+            };
+            let backend = self.backend.clone();
+            let cfg = self.config.clone();
+            let pinned_lc_cache = self.pinned_lc_cache.clone();
+            let disc = self.discovery.clone();
+            let router = self.router.clone();
+            let led_tx = self.led_tx.clone();
+            tokio::spawn(async move {
+                crate::drivers::winaudio::wait_for_profile_loaded("PulseAudio", live_rx).await;
+                refresh_full_state(&backend, &pinned_lc_cache, &disc).await;
+                render_lcd_if_active(&router, &led_tx, &cfg, &pinned_lc_cache, &disc).await;
+            });
+        }
+        self.initialized.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!(
+                "PulseAudio driver not initialized, dropping action '{}'",
+                action
+            );
+            return Ok(());
+        }
+
+        let fader_scalar = ctx
+            .value
+            .as_ref()
+            .and_then(|v| v.as_f64())
+            .map(normalize_fader_value);
+
+        match action {
+            "master_volume" => self.handle_master_volume(fader_scalar).await,
+            "master_mute" => self.handle_master_mute(ctx.is_button_release()).await,
+            "session_volume" => {
+                let target = parse_session_target(&params)?;
+                self.handle_session_volume(target, fader_scalar, &ctx, action)
+                    .await
+            },
+            "session_mute" => {
+                let target = parse_session_target(&params)?;
+                self.handle_session_mute(target, ctx.is_button_release(), &ctx, action)
+                    .await
+            },
+            _ => {
+                warn!("Unknown pulseaudio action '{}'", action);
+                Ok(())
+            },
+        }
+    }
+
+    async fn sync(&self) -> Result<()> {
+        self.refresh_pinned_lc().await;
+        debug!("PulseAudio sync: pinned_lc cache refreshed");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        let _ = self.page_watcher_shutdown.send(true);
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(handle) = self.backend.write().await.take() {
+                handle.shutdown().await;
+            }
+        }
+        info!("PulseAudio driver shut down");
+        Ok(())
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        catalog::pulseaudio_catalog()
+    }
+}
+
+impl PulseAudioDriver {
+    async fn handle_master_volume(&self, normalized: Option<f32>) -> Result<()> {
+        let Some(scalar) = normalized else {
+            debug!("master_volume: no value, ignored");
+            return Ok(());
+        };
+        debug!("master_volume <- {:.3}", scalar);
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(backend) = self.backend.read().await.as_ref() {
+                backend.set_master_scalar(scalar);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_master_mute(&self, is_release: bool) -> Result<()> {
+        if is_release {
+            return Ok(());
+        }
+        debug!("master_mute toggle (press)");
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(backend) = self.backend.read().await.as_ref() {
+                backend.toggle_master_mute();
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_session_volume(
+        &self,
+        target: SessionTarget,
+        normalized: Option<f32>,
+        ctx: &ExecutionContext,
+        action: &str,
+    ) -> Result<()> {
+        let Some(scalar) = normalized else {
+            return Ok(());
+        };
+        let Some(process_name_lc) = self.resolve_target(target, ctx, action).await else {
+            debug!("session_volume {:?}: no process bound", target);
+            return Ok(());
+        };
+        debug!("session_volume {} <- {:.3}", process_name_lc, scalar);
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(backend) = self.backend.read().await.as_ref() {
+                backend.set_session_scalar(process_name_lc, scalar);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_session_mute(
+        &self,
+        target: SessionTarget,
+        is_release: bool,
+        ctx: &ExecutionContext,
+        action: &str,
+    ) -> Result<()> {
+        if is_release {
+            return Ok(());
+        }
+        let Some(process_name_lc) = self.resolve_target(target, ctx, action).await else {
+            return Ok(());
+        };
+        debug!("session_mute {} toggle", process_name_lc);
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(backend) = self.backend.read().await.as_ref() {
+                backend.toggle_session_mute(process_name_lc);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve a `SessionTarget` to a concrete lowercase process name,
+    /// exactly like the WinAudio driver.
+    async fn resolve_target(
+        &self,
+        target: SessionTarget,
+        ctx: &ExecutionContext,
+        action: &str,
+    ) -> Option<String> {
+        match target {
+            SessionTarget::Pinned(fader) => {
+                let cfg = self.config.read().await;
+                mapping::pinned_target(&cfg.pinned_apps, fader)
+            },
+            SessionTarget::Discovered(slot) => {
+                let pinned_lc = self.pinned_lc_cache.load();
+                let discovery = self.discovery.read().await;
+                mapping::discovered_target(&pinned_lc, &discovery, slot)
+            },
+            SessionTarget::Auto => {
+                let control_id = ctx.control_id.as_deref()?;
+                let router = self.router.read().await.clone()?;
+                let page = router.get_active_page().await?;
+                let auto_idx = auto_strip_index(&page, DRIVER_NAME, action, control_id)?;
+                let pinned_lc = self.pinned_lc_cache.load();
+                let discovery = self.discovery.read().await;
+                mapping::discovered_target(&pinned_lc, &discovery, auto_idx)
+            },
+        }
+    }
+
+    /// Re-emit master + per-stream state whenever a page binding
+    /// `app: "pulseaudio"` becomes active: subscription events only fire
+    /// on actual changes, never on page activation.
+    async fn spawn_page_watcher(&self) {
+        let router_arc = self.router.read().await.clone();
+        let Some(router) = router_arc else {
+            debug!("PulseAudio: no router wired, skipping page watcher");
+            return;
+        };
+        let Some(live_tx) = router.live_tx_snapshot().await else {
+            debug!("PulseAudio: live_tx not yet wired, skipping page watcher");
+            return;
+        };
+        let mut rx = live_tx.subscribe();
+        let mut shutdown_rx = self.page_watcher_shutdown.subscribe();
+        #[cfg(target_os = "linux")]
+        let backend = self.backend.clone();
+        let config = self.config.clone();
+        let pinned_lc_cache = self.pinned_lc_cache.clone();
+        let discovery = self.discovery.clone();
+        let router_for_render = self.router.clone();
+        let led_tx = self.led_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    recv = rx.recv() => {
+                        let event = match recv {
+                            Ok(ev) => ev,
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                                warn!("PulseAudio page watcher lagged {} live events; continuing", n);
+                                continue;
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        };
+                        let crate::event_bus::LiveEvent::PageChanged { index, .. } = event else {
+                            continue;
+                        };
+                        if !page_eligible_at_index(&router_for_render, index).await {
+                            continue;
+                        }
+                        debug!("PulseAudio: page activated, refreshing master + streams");
+                        #[cfg(target_os = "linux")]
+                        {
+                            refresh_full_state(&backend, &pinned_lc_cache, &discovery).await;
+                        }
+                        render_lcd_if_active(
+                            &router_for_render,
+                            &led_tx,
+                            &config,
+                            &pinned_lc_cache,
+                            &discovery,
+                        )
+                        .await;
+                    }
+                }
+            }
+            debug!("PulseAudio page watcher stopped");
+        });
+    }
+}
+
+/// Re-enumerate discovery, then trigger master + per-stream feedback.
+#[cfg(target_os = "linux")]
+async fn refresh_full_state(
+    backend: &Arc<RwLock<Option<backend::BackendHandle>>>,
+    pinned_lc_cache: &Arc<ArcSwap<HashSet<String>>>,
+    discovery: &Arc<RwLock<mapping::DiscoveryState>>,
+) {
+    let guard = backend.read().await;
+    let Some(handle) = guard.as_ref() else {
+        return;
+    };
+
+    let names = handle.enumerate_sessions().await;
+    let pinned_lc = pinned_lc_cache.load();
+    {
+        let mut state = discovery.write().await;
+        state.observe(&names, &pinned_lc);
+        debug!(
+            "PulseAudio refresh: {} active stream process(es), discovery order={:?}",
+            names.len(),
+            state.discovered_order
+        );
+    }
+
+    handle.refresh_master();
+    handle.refresh_sessions();
+}
+
+async fn render_lcd_if_active(
+    router: &RouterSlot,
+    led_tx: &LedSender,
+    config: &Arc<RwLock<PulseAudioConfig>>,
+    pinned_lc_cache: &Arc<ArcSwap<HashSet<String>>>,
+    discovery: &Arc<RwLock<mapping::DiscoveryState>>,
+) {
+    let pinned_apps = config.read().await.pinned_apps.clone();
+    render_session_lcd_if_active(
+        DRIVER_NAME,
+        router,
+        led_tx,
+        &pinned_apps,
+        pinned_lc_cache,
+        discovery,
+    )
+    .await;
+}
+
+/// Pump backend `AudioEvent`s into the unified feedback channel as
+/// synthetic `"pulseaudio"` MIDI messages, coalesced per logical action
+/// at `FLUSH_INTERVAL_MS` like the WinAudio consumer (pavucontrol sliders
+/// emit one change event per step).
+#[cfg(target_os = "linux")]
+#[allow(clippy::too_many_arguments)]
+async fn run_event_consumer(
+    mut event_rx: mpsc::Receiver<backend::AudioEvent>,
+    feedback_tx: mpsc::Sender<(String, Vec<u8>)>,
+    config: Arc<RwLock<PulseAudioConfig>>,
+    pinned_lc_cache: Arc<ArcSwap<HashSet<String>>>,
+    discovery: Arc<RwLock<mapping::DiscoveryState>>,
+    router: RouterSlot,
+    led_tx: LedSender,
+) {
+    use crate::drivers::winaudio::{flush_pending, FeedbackKey, PendingFeedback};
+    use std::collections::HashMap;
+    use tokio::time::{interval, Duration, MissedTickBehavior};
+
+    const FLUSH_INTERVAL_MS: u64 = 50;
+
+    debug!("PulseAudio event consumer task started");
+
+    let mut pending: HashMap<FeedbackKey, PendingFeedback> = HashMap::new();
+
+    let mut ticker = interval(Duration::from_millis(FLUSH_INTERVAL_MS));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            biased;
+            event = event_rx.recv() => {
+                let Some(event) = event else {
+                    debug!("PulseAudio event consumer: source closed");
+                    break;
+                };
+                match event {
+                    backend::AudioEvent::MasterVolumeChanged { scalar, mute } => {
+                        pending.insert(FeedbackKey::Master, PendingFeedback { scalar, mute });
+                    },
+                    backend::AudioEvent::SessionVolumeSnapshot { process_name_lc, scalar, mute } => {
+                        let cfg = config.read().await;
+                        let pinned_lc = pinned_lc_cache.load();
+                        let disc = discovery.read().await;
+                        let target = mapping::target_for_process(
+                            &cfg.pinned_apps,
+                            &pinned_lc,
+                            &disc,
+                            &process_name_lc,
+                        );
+                        drop(disc);
+                        drop(cfg);
+                        let Some(target) = target else {
+                            debug!("stream snapshot for '{}' has no fader slot, ignored", process_name_lc);
+                            continue;
+                        };
+                        pending.insert(FeedbackKey::Session(target), PendingFeedback { scalar, mute });
+                    },
+                    backend::AudioEvent::ActiveSessionsChanged { names_lc } => {
+                        let active_changed = {
+                            let pinned_lc = pinned_lc_cache.load();
+                            let mut state = discovery.write().await;
+                            state.observe(&names_lc, &pinned_lc);
+                            state.set_active(&names_lc)
+                        };
+                        if !active_changed {
+                            continue;
+                        }
+                        let router = router.clone();
+                        let led_tx = led_tx.clone();
+                        let config = config.clone();
+                        let pinned_lc_cache = pinned_lc_cache.clone();
+                        let discovery = discovery.clone();
+                        tokio::spawn(async move {
+                            render_lcd_if_active(&router, &led_tx, &config, &pinned_lc_cache, &discovery).await;
+                        });
+                    },
+                }
+            }
+            _ = ticker.tick() => {
+                if pending.is_empty() {
+                    continue;
+                }
+                let drained: Vec<(FeedbackKey, PendingFeedback)> = pending.drain().collect();
+                if !flush_pending(DRIVER_NAME, &drained, &feedback_tx, &router).await {
+                    debug!("PulseAudio feedback channel closed, exiting consumer");
+                    return;
+                }
+            }
+        }
+    }
+}
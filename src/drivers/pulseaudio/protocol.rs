@@ -0,0 +1,551 @@
+//! PulseAudio native protocol wire format.
+//!
+//! Minimal subset needed for mixer control: packet framing, tagstruct
+//! encoding and the handful of introspection / volume commands used by the
+//! driver. `pipewire-pulse` implements the same protocol, so PipeWire hosts
+//! are covered too.
+//!
+//! Every packet is a 20-byte big-endian descriptor (`length`, `channel`,
+//! `offset_hi`, `offset_lo`, `flags`) followed by `length` bytes of payload.
+//! Control packets use channel `u32::MAX` and carry a tagstruct: a sequence
+//! of one-byte type tags each followed by its big-endian value.
+
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+
+/// Protocol version advertised in `AUTH`. The server answers with its own
+/// version and both sides use the minimum.
+pub(super) const PROTOCOL_VERSION: u32 = 32;
+
+/// Oldest server version whose reply layout the parsers understand
+/// (version 21 added format info to sink inputs; every field before it
+/// is then unconditional).
+pub(super) const MIN_PROTOCOL_VERSION: u32 = 21;
+
+/// Length of the auth cookie (`~/.config/pulse/cookie`).
+pub(super) const COOKIE_LEN: usize = 256;
+
+/// `PA_VOLUME_NORM`: 100% volume.
+pub(super) const VOLUME_NORM: u32 = 0x10000;
+
+/// `PA_INVALID_INDEX`: look the object up by name instead.
+pub(super) const INVALID_INDEX: u32 = u32::MAX;
+
+/// Special sink name resolved by the server to the current default sink.
+pub(super) const DEFAULT_SINK: &str = "@DEFAULT_SINK@";
+
+/// Channel id of control (non-audio) packets.
+pub(super) const CONTROL_CHANNEL: u32 = u32::MAX;
+
+/// Size of the packet descriptor preceding every payload.
+pub(super) const FRAME_HEADER_LEN: usize = 20;
+
+/// Upper bound on a control packet payload. Introspection replies are a
+/// few KiB; anything larger means a desynced stream.
+pub(super) const MAX_PAYLOAD_LEN: usize = 4 * 1024 * 1024;
+
+/// Command ids (`PA_COMMAND_*`) used by the driver.
+pub(super) mod command {
+    pub const ERROR: u32 = 0;
+    pub const REPLY: u32 = 2;
+    pub const AUTH: u32 = 8;
+    pub const SET_CLIENT_NAME: u32 = 9;
+    pub const GET_SINK_INFO: u32 = 21;
+    pub const GET_SINK_INPUT_INFO_LIST: u32 = 30;
+    pub const SUBSCRIBE: u32 = 35;
+    pub const SET_SINK_VOLUME: u32 = 36;
+    pub const SET_SINK_INPUT_VOLUME: u32 = 37;
+    pub const SET_SINK_MUTE: u32 = 39;
+    pub const SUBSCRIBE_EVENT: u32 = 66;
+    pub const SET_SINK_INPUT_MUTE: u32 = 69;
+}
+
+/// Subscription masks (`PA_SUBSCRIPTION_MASK_*`).
+pub(super) mod subscription {
+    pub const MASK_SINK: u32 = 0x0001;
+    pub const MASK_SINK_INPUT: u32 = 0x0004;
+    pub const MASK_SERVER: u32 = 0x0080;
+}
+
+mod tag {
+    pub const STRING: u8 = b't';
+    pub const STRING_NULL: u8 = b'N';
+    pub const U32: u8 = b'L';
+    pub const U8: u8 = b'B';
+    pub const U64: u8 = b'R';
+    pub const S64: u8 = b'r';
+    pub const SAMPLE_SPEC: u8 = b'a';
+    pub const ARBITRARY: u8 = b'x';
+    pub const BOOLEAN_TRUE: u8 = b'1';
+    pub const BOOLEAN_FALSE: u8 = b'0';
+    pub const USEC: u8 = b'U';
+    pub const CHANNEL_MAP: u8 = b'm';
+    pub const CVOLUME: u8 = b'v';
+    pub const PROPLIST: u8 = b'P';
+    pub const FORMAT_INFO: u8 = b'f';
+}
+
+/// Tagstruct builder for an outgoing control packet.
+pub(super) struct TagWriter {
+    buf: Vec<u8>,
+}
+
+impl TagWriter {
+    /// Start a command packet: `command`, then the request `tag` echoed
+    /// back in the server's reply.
+    pub(super) fn command(command: u32, tag: u32) -> Self {
+        let mut w = Self { buf: Vec::new() };
+        w.put_u32(command);
+        w.put_u32(tag);
+        w
+    }
+
+    pub(super) fn put_u32(&mut self, v: u32) {
+        self.buf.push(tag::U32);
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    pub(super) fn put_bool(&mut self, v: bool) {
+        self.buf.push(if v {
+            tag::BOOLEAN_TRUE
+        } else {
+            tag::BOOLEAN_FALSE
+        });
+    }
+
+    pub(super) fn put_string(&mut self, v: Option<&str>) {
+        match v {
+            Some(s) => {
+                self.buf.push(tag::STRING);
+                self.buf.extend_from_slice(s.as_bytes());
+                self.buf.push(0);
+            },
+            None => self.buf.push(tag::STRING_NULL),
+        }
+    }
+
+    pub(super) fn put_arbitrary(&mut self, data: &[u8]) {
+        self.buf.push(tag::ARBITRARY);
+        self.buf
+            .extend_from_slice(&(data.len() as u32).to_be_bytes());
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Per-channel volume with every channel set to `volume`.
+    pub(super) fn put_cvolume(&mut self, channels: u8, volume: u32) {
+        self.buf.push(tag::CVOLUME);
+        self.buf.push(channels);
+        for _ in 0..channels {
+            self.buf.extend_from_slice(&volume.to_be_bytes());
+        }
+    }
+
+    /// Property list of string values (NUL-terminated on the wire).
+    pub(super) fn put_proplist(&mut self, props: &[(&str, &str)]) {
+        self.buf.push(tag::PROPLIST);
+        for (key, value) in props {
+            self.put_string(Some(key));
+            let mut data = value.as_bytes().to_vec();
+            data.push(0);
+            self.put_u32(data.len() as u32);
+            self.put_arbitrary(&data);
+        }
+        self.put_string(None);
+    }
+
+    /// Finish the packet: descriptor + payload, ready to write.
+    pub(super) fn into_frame(self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + self.buf.len());
+        frame.extend_from_slice(&(self.buf.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&CONTROL_CHANNEL.to_be_bytes());
+        frame.extend_from_slice(&[0u8; 12]);
+        frame.extend_from_slice(&self.buf);
+        frame
+    }
+}
+
+/// Decode a packet descriptor into `(payload_len, channel)`.
+pub(super) fn parse_frame_header(header: &[u8; FRAME_HEADER_LEN]) -> (usize, u32) {
+    let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let channel = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    (len, channel)
+}
+
+/// Cursor over a received tagstruct payload.
+pub(super) struct TagReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TagReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("pulse tagstruct truncated at byte {}", self.pos))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn expect_tag(&mut self, expected: u8) -> Result<()> {
+        let got = self.take(1)?[0];
+        if got != expected {
+            bail!(
+                "pulse tagstruct: expected tag '{}', got '{}' at byte {}",
+                expected as char,
+                got as char,
+                self.pos - 1
+            );
+        }
+        Ok(())
+    }
+
+    fn raw_u32(&mut self) -> Result<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(super) fn get_u32(&mut self) -> Result<u32> {
+        self.expect_tag(tag::U32)?;
+        self.raw_u32()
+    }
+
+    pub(super) fn get_u8(&mut self) -> Result<u8> {
+        self.expect_tag(tag::U8)?;
+        Ok(self.take(1)?[0])
+    }
+
+    pub(super) fn get_bool(&mut self) -> Result<bool> {
+        match self.take(1)?[0] {
+            tag::BOOLEAN_TRUE => Ok(true),
+            tag::BOOLEAN_FALSE => Ok(false),
+            other => bail!("pulse tagstruct: expected boolean, got '{}'", other as char),
+        }
+    }
+
+    pub(super) fn get_string(&mut self) -> Result<Option<String>> {
+        match self.take(1)?[0] {
+            tag::STRING_NULL => Ok(None),
+            tag::STRING => {
+                let rest = &self.data[self.pos..];
+                let nul = rest
+                    .iter()
+                    .position(|b| *b == 0)
+                    .ok_or_else(|| anyhow!("pulse tagstruct: unterminated string"))?;
+                let s = String::from_utf8_lossy(&rest[..nul]).into_owned();
+                self.pos += nul + 1;
+                Ok(Some(s))
+            },
+            other => bail!("pulse tagstruct: expected string, got '{}'", other as char),
+        }
+    }
+
+    pub(super) fn get_arbitrary(&mut self) -> Result<&'a [u8]> {
+        self.expect_tag(tag::ARBITRARY)?;
+        let len = self.raw_u32()? as usize;
+        self.take(len)
+    }
+
+    /// Skip a 64-bit value (`U64`, `S64` or `USEC`).
+    pub(super) fn skip_u64(&mut self) -> Result<()> {
+        match self.take(1)?[0] {
+            tag::U64 | tag::S64 | tag::USEC => self.take(8).map(|_| ()),
+            other => bail!(
+                "pulse tagstruct: expected 64-bit value, got '{}'",
+                other as char
+            ),
+        }
+    }
+
+    pub(super) fn skip_sample_spec(&mut self) -> Result<()> {
+        self.expect_tag(tag::SAMPLE_SPEC)?;
+        // format (u8), channels (u8), rate (u32)
+        self.take(6).map(|_| ())
+    }
+
+    pub(super) fn skip_channel_map(&mut self) -> Result<()> {
+        self.expect_tag(tag::CHANNEL_MAP)?;
+        let channels = self.take(1)?[0] as usize;
+        self.take(channels).map(|_| ())
+    }
+
+    pub(super) fn get_cvolume(&mut self) -> Result<Vec<u32>> {
+        self.expect_tag(tag::CVOLUME)?;
+        let channels = self.take(1)?[0];
+        (0..channels).map(|_| self.raw_u32()).collect()
+    }
+
+    /// Property list as string values (trailing NUL stripped). Binary
+    /// values are decoded lossily; the driver only reads text properties.
+    pub(super) fn get_proplist(&mut self) -> Result<HashMap<String, String>> {
+        self.expect_tag(tag::PROPLIST)?;
+        let mut props = HashMap::new();
+        while let Some(key) = self.get_string()? {
+            let len = self.get_u32()? as usize;
+            let data = self.get_arbitrary()?;
+            if data.len() != len {
+                bail!("pulse proplist: length mismatch for '{}'", key);
+            }
+            let value = data.strip_suffix(&[0]).unwrap_or(data);
+            props.insert(key, String::from_utf8_lossy(value).into_owned());
+        }
+        Ok(props)
+    }
+
+    pub(super) fn skip_format_info(&mut self) -> Result<()> {
+        self.expect_tag(tag::FORMAT_INFO)?;
+        self.get_u8()?;
+        self.get_proplist().map(|_| ())
+    }
+}
+
+/// Split a control payload into `(command, tag, body)`.
+pub(super) fn parse_packet(payload: &[u8]) -> Result<(u32, u32, TagReader<'_>)> {
+    let mut r = TagReader::new(payload);
+    let command = r.get_u32()?;
+    let tag = r.get_u32()?;
+    Ok((command, tag, r))
+}
+
+/// One playback stream (`sink input`), reduced to what the mixer needs.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SinkInput {
+    pub index: u32,
+    /// Per-channel volume (`VOLUME_NORM` = 100%).
+    pub volume: Vec<u32>,
+    pub mute: bool,
+    /// Lowercased `application.process.binary` (falls back to
+    /// `application.name`). `None` for anonymous streams.
+    pub process_name_lc: Option<String>,
+}
+
+/// Parse one entry of a `GET_SINK_INPUT_INFO_LIST` reply (protocol
+/// version >= [`MIN_PROTOCOL_VERSION`]).
+pub(super) fn read_sink_input(r: &mut TagReader<'_>) -> Result<SinkInput> {
+    let index = r.get_u32()?;
+    r.get_string()?; // media name
+    r.get_u32()?; // owner module
+    r.get_u32()?; // client
+    r.get_u32()?; // sink
+    r.skip_sample_spec()?;
+    r.skip_channel_map()?;
+    let volume = r.get_cvolume()?;
+    r.skip_u64()?; // buffer latency
+    r.skip_u64()?; // sink latency
+    r.get_string()?; // resample method
+    r.get_string()?; // driver
+    let mute = r.get_bool()?;
+    let props = r.get_proplist()?;
+    r.get_bool()?; // corked
+    r.get_bool()?; // has_volume
+    r.get_bool()?; // volume_writable
+    r.skip_format_info()?;
+
+    let process_name_lc = props
+        .get("application.process.binary")
+        .or_else(|| props.get("application.name"))
+        .map(|s| s.to_lowercase());
+
+    Ok(SinkInput {
+        index,
+        volume,
+        mute,
+        process_name_lc,
+    })
+}
+
+/// Parse every entry of a `GET_SINK_INPUT_INFO_LIST` reply body.
+pub(super) fn read_sink_input_list(r: &mut TagReader<'_>) -> Result<Vec<SinkInput>> {
+    let mut inputs = Vec::new();
+    while !r.is_empty() {
+        inputs.push(read_sink_input(r)?);
+    }
+    Ok(inputs)
+}
+
+/// Master (default sink) volume state. Only the leading fields of the
+/// `GET_SINK_INFO` reply are decoded; the rest is ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SinkVolume {
+    pub volume: Vec<u32>,
+    pub mute: bool,
+}
+
+/// Parse the head of a `GET_SINK_INFO` reply body.
+pub(super) fn read_sink_volume(r: &mut TagReader<'_>) -> Result<SinkVolume> {
+    r.get_u32()?; // index
+    r.get_string()?; // name
+    r.get_string()?; // description
+    r.skip_sample_spec()?;
+    r.skip_channel_map()?;
+    r.get_u32()?; // owner module
+    let volume = r.get_cvolume()?;
+    let mute = r.get_bool()?;
+    Ok(SinkVolume { volume, mute })
+}
+
+/// Facility of a subscription event (`PA_SUBSCRIPTION_EVENT_*`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Facility {
+    Sink,
+    SinkInput,
+    Server,
+    Other,
+}
+
+/// Parse a `SUBSCRIBE_EVENT` body into its facility (the event type and
+/// object index are not needed: the driver re-reads the whole state).
+pub(super) fn read_subscribe_event(r: &mut TagReader<'_>) -> Result<Facility> {
+    let event = r.get_u32()?;
+    r.get_u32()?; // object index
+    Ok(match event & 0x000F {
+        0x0000 => Facility::Sink,
+        0x0002 => Facility::SinkInput,
+        0x0007 => Facility::Server,
+        _ => Facility::Other,
+    })
+}
+
+/// Loudest channel as a `[0.0, 1.0]` fader scalar. Boosted volumes
+/// (> 100%) pin the fader at the top.
+pub(super) fn volume_to_scalar(volume: &[u32]) -> f32 {
+    let max = volume.iter().copied().max().unwrap_or(0);
+    (max as f32 / VOLUME_NORM as f32).clamp(0.0, 1.0)
+}
+
+/// `[0.0, 1.0]` fader scalar to a raw volume (linear in the server's
+/// cubic volume scale, i.e. the percentage `pactl` shows).
+pub(super) fn scalar_to_volume(scalar: f32) -> u32 {
+    (scalar.clamp(0.0, 1.0) * VOLUME_NORM as f32).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(w: TagWriter) -> Vec<u8> {
+        w.into_frame()[FRAME_HEADER_LEN..].to_vec()
+    }
+
+    #[test]
+    fn frame_header_round_trips() {
+        let frame = TagWriter::command(command::SUBSCRIBE, 7).into_frame();
+        let header: [u8; FRAME_HEADER_LEN] = frame[..FRAME_HEADER_LEN].try_into().unwrap();
+        let (len, channel) = parse_frame_header(&header);
+        assert_eq!(len, frame.len() - FRAME_HEADER_LEN);
+        assert_eq!(channel, CONTROL_CHANNEL);
+
+        let (cmd, tag, r) = parse_packet(&frame[FRAME_HEADER_LEN..]).unwrap();
+        assert_eq!((cmd, tag), (command::SUBSCRIBE, 7));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn proplist_round_trips() {
+        let mut w = TagWriter::command(command::SET_CLIENT_NAME, 1);
+        w.put_proplist(&[("application.name", "xtouch-gw")]);
+        let payload = body(w);
+        let (_, _, mut r) = parse_packet(&payload).unwrap();
+        let props = r.get_proplist().unwrap();
+        assert_eq!(props.get("application.name").unwrap(), "xtouch-gw");
+    }
+
+    #[test]
+    fn cvolume_sets_every_channel() {
+        let mut w = TagWriter::command(command::SET_SINK_INPUT_VOLUME, 3);
+        w.put_u32(12);
+        w.put_cvolume(2, VOLUME_NORM / 2);
+        let payload = body(w);
+        let (_, _, mut r) = parse_packet(&payload).unwrap();
+        assert_eq!(r.get_u32().unwrap(), 12);
+        assert_eq!(r.get_cvolume().unwrap(), vec![VOLUME_NORM / 2; 2]);
+    }
+
+    /// Encode a sink input entry the way the server does (version >= 21).
+    fn sink_input_entry(w: &mut TagWriter, index: u32, binary: &str, vol: u32, mute: bool) {
+        w.put_u32(index);
+        w.put_string(Some("Playback"));
+        w.put_u32(INVALID_INDEX);
+        w.put_u32(4);
+        w.put_u32(0);
+        w.buf
+            .extend_from_slice(&[tag::SAMPLE_SPEC, 3, 2, 0, 0, 0xBB, 0x80]);
+        w.buf.extend_from_slice(&[tag::CHANNEL_MAP, 2, 1, 2]);
+        w.put_cvolume(2, vol);
+        w.buf.push(tag::USEC);
+        w.buf.extend_from_slice(&0u64.to_be_bytes());
+        w.buf.push(tag::USEC);
+        w.buf.extend_from_slice(&0u64.to_be_bytes());
+        w.put_string(Some("speex-float-1"));
+        w.put_string(Some("protocol-native.c"));
+        w.put_bool(mute);
+        w.put_proplist(&[
+            ("application.name", "Firefox"),
+            ("application.process.binary", binary),
+        ]);
+        w.put_bool(false);
+        w.put_bool(true);
+        w.put_bool(true);
+        w.buf.extend_from_slice(&[tag::FORMAT_INFO, tag::U8, 1]);
+        w.put_proplist(&[]);
+    }
+
+    #[test]
+    fn parses_sink_input_list() {
+        let mut w = TagWriter::command(command::REPLY, 5);
+        sink_input_entry(&mut w, 41, "Firefox", VOLUME_NORM, false);
+        sink_input_entry(&mut w, 42, "spotify", VOLUME_NORM / 4, true);
+        let payload = body(w);
+        let (cmd, tag, mut r) = parse_packet(&payload).unwrap();
+        assert_eq!((cmd, tag), (command::REPLY, 5));
+
+        let inputs = read_sink_input_list(&mut r).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0].index, 41);
+        assert_eq!(inputs[0].process_name_lc.as_deref(), Some("firefox"));
+        assert!(!inputs[0].mute);
+        assert_eq!(inputs[1].process_name_lc.as_deref(), Some("spotify"));
+        assert!(inputs[1].mute);
+        assert!((volume_to_scalar(&inputs[1].volume) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn truncated_payload_is_an_error() {
+        let mut w = TagWriter::command(command::REPLY, 5);
+        sink_input_entry(&mut w, 41, "firefox", VOLUME_NORM, false);
+        let mut payload = body(w);
+        payload.truncate(payload.len() - 3);
+        let (_, _, mut r) = parse_packet(&payload).unwrap();
+        assert!(read_sink_input_list(&mut r).is_err());
+    }
+
+    #[test]
+    fn subscribe_event_facility() {
+        let mut w = TagWriter::command(command::SUBSCRIBE_EVENT, INVALID_INDEX);
+        w.put_u32(0x0010 | 0x0002); // CHANGE | SINK_INPUT
+        w.put_u32(41);
+        let payload = body(w);
+        let (_, _, mut r) = parse_packet(&payload).unwrap();
+        assert_eq!(read_subscribe_event(&mut r).unwrap(), Facility::SinkInput);
+    }
+
+    #[test]
+    fn volume_scalar_conversion() {
+        assert_eq!(scalar_to_volume(1.0), VOLUME_NORM);
+        assert_eq!(scalar_to_volume(0.0), 0);
+        assert_eq!(scalar_to_volume(2.0), VOLUME_NORM);
+        assert_eq!(volume_to_scalar(&[VOLUME_NORM * 2]), 1.0);
+        assert_eq!(volume_to_scalar(&[]), 0.0);
+        assert!((volume_to_scalar(&[VOLUME_NORM / 2, VOLUME_NORM / 4]) - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,50 @@
+//! Action catalog for the user variables driver, built from the declared
+//! variable names.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the vars action catalog. The `name` param defaults to the first
+/// declared variable (any name works: undeclared variables are created).
+pub fn vars_catalog(declared: &[&str]) -> Vec<ActionDescriptor> {
+    let name_param = || {
+        let param = ParamDescriptor::new("name", ParamKind::String);
+        match declared.first() {
+            Some(first) => param.with_default(json!(first)),
+            None => param,
+        }
+    };
+    let number = |name: &str, default: serde_json::Value| {
+        ParamDescriptor::new(name, ParamKind::Number).with_default(default)
+    };
+
+    vec![
+        ActionDescriptor::simple("set", "Set")
+            .with_param(name_param())
+            .with_param(ParamDescriptor::new("value", ParamKind::String)),
+        ActionDescriptor::simple("toggle", "Toggle")
+            .with_description("Flip between true and false.")
+            .with_param(name_param()),
+        ActionDescriptor::simple("increment", "Increment")
+            .with_description("Add `step`, clamped to min / max when given.")
+            .with_param(name_param())
+            .with_param(number("step", json!(1)))
+            .with_param(ParamDescriptor::new("min", ParamKind::Number))
+            .with_param(ParamDescriptor::new("max", ParamKind::Number)),
+        ActionDescriptor::simple("decrement", "Decrement")
+            .with_description("Subtract `step`, clamped to min / max when given.")
+            .with_param(name_param())
+            .with_param(number("step", json!(1)))
+            .with_param(ParamDescriptor::new("min", ParamKind::Number))
+            .with_param(ParamDescriptor::new("max", ParamKind::Number)),
+        ActionDescriptor::simple("cycle", "Cycle")
+            .with_description(
+                "Step to the next value of the list given after the name \
+                 (default: the variable's `values`), wrapping around.",
+            )
+            .with_param(name_param()),
+        ActionDescriptor::simple("reset", "Reset")
+            .with_description("Back to the declared `initial` value (null if none).")
+            .with_param(name_param()),
+    ]
+}
@@ -0,0 +1,423 @@
+//! User variables driver
+//!
+//! Named variables changed from mappings (`app: "vars"`,
+//! `params: [name, ...]`) and published as `vars.<name>` indicator signals:
+//! software latches and mode selectors for `indicator:`, `when:` and
+//! `${vars.<name>}` params, without borrowing state from OBS or QLC+.
+//!
+//! Actions:
+//! - `set`: `[name, value]`
+//! - `toggle`: flip between `true` and `false`
+//! - `increment` / `decrement`: `[name, step = 1, min, max]`
+//! - `cycle`: `[name, v1, v2, ...]`, or the declared `values`
+//! - `reset`: back to the declared `initial`
+//!
+//! Values live in the router's state database (tree `vars`) and survive
+//! restarts, unless the variable is declared with `persist: false`.
+
+mod catalog;
+mod ops;
+
+use crate::config::{is_valid_var_name, VarConfig, VarsConfig};
+use crate::drivers::{Driver, ExecutionContext, IndicatorCallback};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{debug, info, warn};
+
+/// Driver name used for `app: "vars"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "vars";
+
+/// Name of the router state tree holding persisted values.
+pub const STATE_TREE: &str = "vars";
+
+/// Variable indicator signal names: `vars.<name>`.
+pub mod signals {
+    pub const PREFIX: &str = "vars";
+
+    /// Full signal name for a variable.
+    pub fn name(variable: &str) -> String {
+        format!("{}.{}", PREFIX, variable)
+    }
+}
+
+/// Parsed driver action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VarsAction {
+    Set,
+    Toggle,
+    Increment,
+    Decrement,
+    Cycle,
+    Reset,
+}
+
+impl VarsAction {
+    fn parse(action: &str) -> Result<Self> {
+        Ok(match action {
+            "set" => Self::Set,
+            "toggle" => Self::Toggle,
+            "increment" => Self::Increment,
+            "decrement" => Self::Decrement,
+            "cycle" => Self::Cycle,
+            "reset" => Self::Reset,
+            _ => bail!("Unknown vars action '{}'", action),
+        })
+    }
+}
+
+pub struct VarsDriver {
+    config: VarsConfig,
+    /// Persistent store (`None`: values only live in memory).
+    store: Option<sled::Tree>,
+    initialized: AtomicBool,
+    /// Current values. Signals are emitted while holding this lock so two
+    /// quick changes can't be published out of order. Also the replay
+    /// source for late subscribers.
+    values: parking_lot::Mutex<HashMap<String, Value>>,
+    indicator_emitters: parking_lot::RwLock<Vec<IndicatorCallback>>,
+}
+
+impl VarsDriver {
+    /// Build the driver, restoring persisted values from `store` and
+    /// falling back to each declared variable's `initial`.
+    pub fn new(config: VarsConfig, store: Option<sled::Tree>) -> Self {
+        let mut values = HashMap::new();
+        if let Some(store) = &store {
+            for entry in store.iter() {
+                let (key, bytes) = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        warn!("vars: failed to read persisted value: {}", e);
+                        continue;
+                    },
+                };
+                let Ok(name) = std::str::from_utf8(&key) else {
+                    continue;
+                };
+                if config.variables.get(name).is_some_and(|v| !v.persist) {
+                    continue;
+                }
+                match serde_json::from_slice::<Value>(&bytes) {
+                    Ok(value) => {
+                        values.insert(name.to_string(), value);
+                    },
+                    Err(e) => warn!("vars.{}: ignoring unreadable persisted value: {}", name, e),
+                }
+            }
+        }
+        for (name, declared) in &config.variables {
+            if let Some(initial) = &declared.initial {
+                values
+                    .entry(name.clone())
+                    .or_insert_with(|| initial.clone());
+            }
+        }
+
+        Self {
+            config,
+            store,
+            initialized: AtomicBool::new(false),
+            values: parking_lot::Mutex::new(values),
+            indicator_emitters: parking_lot::RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Apply `action` to variable `name`. Returns the new value, or `None`
+    /// when it didn't change.
+    fn apply(&self, action: VarsAction, name: &str, params: &[Value]) -> Result<Option<Value>> {
+        let declared = self.config.variables.get(name);
+        let mut values = self.values.lock();
+        let current = values.get(name).cloned().unwrap_or(Value::Null);
+        let next = match action {
+            VarsAction::Set => params.get(1).cloned().unwrap_or(Value::Null),
+            VarsAction::Toggle => ops::toggled(&current),
+            VarsAction::Increment | VarsAction::Decrement => {
+                let step = number_param(name, params, 1)?.unwrap_or(1.0);
+                let step = if action == VarsAction::Decrement {
+                    -step
+                } else {
+                    step
+                };
+                ops::incremented(
+                    &current,
+                    step,
+                    number_param(name, params, 2)?,
+                    number_param(name, params, 3)?,
+                )
+            },
+            VarsAction::Cycle => {
+                let list = match params.get(1..) {
+                    Some(list) if !list.is_empty() => list,
+                    _ => declared.map(|d| d.values.as_slice()).unwrap_or_default(),
+                };
+                ops::cycled(&current, list).ok_or_else(|| {
+                    anyhow!(
+                        "vars.{}: cycle needs values (after the name, or `values` in config)",
+                        name
+                    )
+                })?
+            },
+            VarsAction::Reset => declared
+                .and_then(|d| d.initial.clone())
+                .unwrap_or(Value::Null),
+        };
+
+        if values.get(name) == Some(&next) {
+            return Ok(None);
+        }
+        values.insert(name.to_string(), next.clone());
+        self.persist(name, &next, declared);
+        let signal = signals::name(name);
+        for emit in self.indicator_emitters.read().iter() {
+            emit(signal.clone(), next.clone());
+        }
+        Ok(Some(next))
+    }
+
+    /// Write a value to the store (best-effort: a failed write only costs
+    /// the value after a restart).
+    fn persist(&self, name: &str, value: &Value, declared: Option<&VarConfig>) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if declared.is_some_and(|d| !d.persist) {
+            return;
+        }
+        let result = serde_json::to_vec(value)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(store.insert(name.as_bytes(), bytes)?));
+        if let Err(e) = result {
+            warn!("vars.{}: failed to persist value: {}", name, e);
+        }
+    }
+}
+
+/// Optional numeric param (`null` / missing = `None`; numeric strings
+/// accepted, e.g. from `${...}` inside text).
+fn number_param(name: &str, params: &[Value], index: usize) -> Result<Option<f64>> {
+    match params.get(index) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => Ok(n.as_f64()),
+        Some(Value::String(s)) if s.trim().parse::<f64>().is_ok() => Ok(s.trim().parse().ok()),
+        Some(other) => bail!(
+            "vars.{}: param {} must be a number, got {}",
+            name,
+            index + 1,
+            other
+        ),
+    }
+}
+
+#[async_trait]
+impl Driver for VarsDriver {
+    fn name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    async fn init(&self, _ctx: ExecutionContext) -> Result<()> {
+        info!(
+            "Vars driver initializing ({} declared, {} with a value)",
+            self.config.variables.len(),
+            self.values.lock().len()
+        );
+        self.initialized.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            warn!("Vars driver not initialized, dropping action '{}'", action);
+            return Ok(());
+        }
+
+        let action = VarsAction::parse(action)?;
+        let Some(name) = params.first().and_then(Value::as_str) else {
+            bail!("vars actions expect a variable name as first param");
+        };
+        if !is_valid_var_name(name) {
+            bail!(
+                "vars: invalid variable name '{}' (letters, digits, '_' and '-' only)",
+                name
+            );
+        }
+        // `set` is idempotent and may carry a fader's `${value}`, where 0 is
+        // a real value rather than a release.
+        if action != VarsAction::Set && ctx.is_button_release() {
+            return Ok(());
+        }
+
+        match self.apply(action, name, &params)? {
+            Some(value) => debug!("vars.{} = {}", name, value),
+            None => debug!("vars.{} unchanged ({:?})", name, action),
+        }
+
+        if let Some(ref tracker) = ctx.activity_tracker {
+            tracker.record(DRIVER_NAME, crate::tray::ActivityDirection::Outbound);
+        }
+        Ok(())
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("Vars sync (no-op)");
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.initialized.store(false, Ordering::Release);
+        if let Some(store) = &self.store {
+            if let Err(e) = store.flush_async().await {
+                warn!("vars: failed to flush persisted values: {}", e);
+            }
+        }
+        // Re-registration re-subscribes fresh callbacks.
+        self.indicator_emitters.write().clear();
+        debug!("Vars driver shut down");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("Vars driver: new indicator subscription");
+        for (name, value) in self.values.lock().iter() {
+            callback(signals::name(name), value.clone());
+        }
+        self.indicator_emitters.write().push(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        let mut names: Vec<&str> = self.config.variables.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        catalog::vars_catalog(&names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn make_ctx(value: Option<Value>) -> ExecutionContext {
+        let config = crate::config::AppConfig {
+            midi: crate::config::MidiConfig {
+                input_port: "test_in".to_string(),
+                output_port: "test_out".to_string(),
+                apps: None,
+            },
+            obs: None,
+            xtouch: None,
+            paging: None,
+            gamepad: None,
+            pages_global: None,
+            winaudio: None,
+            pulseaudio: None,
+            vmix: None,
+            mpris: None,
+            mmc: None,
+            process: None,
+            vars: None,
+            macros: None,
+            page_templates: None,
+            pages: vec![],
+            tray: None,
+        };
+        ExecutionContext {
+            config: Arc::new(tokio::sync::RwLock::new(config)),
+            active_page: None,
+            value,
+            control_id: None,
+            activity_tracker: None,
+            camera_targets: None,
+        }
+    }
+
+    fn declared(yaml: &str) -> VarsConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    /// Collect emitted signals.
+    fn recorder(driver: &VarsDriver) -> Arc<parking_lot::Mutex<Vec<(String, Value)>>> {
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        driver.subscribe_indicators(Arc::new(move |signal, value| {
+            sink.lock().push((signal, value));
+        }));
+        seen
+    }
+
+    #[tokio::test]
+    async fn actions_emit_changes_only() {
+        let driver = VarsDriver::new(
+            declared("variables:\n  group:\n    initial: A\n    values: [A, B]\n"),
+            None,
+        );
+        let seen = recorder(&driver);
+        driver.init(make_ctx(None)).await.unwrap();
+        let run = |action: &'static str, params: Vec<Value>| {
+            driver.execute(action, params, make_ctx(Some(json!(127))))
+        };
+
+        run("cycle", vec![json!("group")]).await.unwrap();
+        run("toggle", vec![json!("latch")]).await.unwrap();
+        run(
+            "increment",
+            vec![json!("count"), json!(2), json!(0), json!(3)],
+        )
+        .await
+        .unwrap();
+        run(
+            "increment",
+            vec![json!("count"), json!(2), json!(0), json!(3)],
+        )
+        .await
+        .unwrap();
+        run("set", vec![json!("latch"), json!(true)]).await.unwrap();
+        run("reset", vec![json!("group")]).await.unwrap();
+        assert!(run("cycle", vec![json!("count")]).await.is_err());
+        assert!(run("set", vec![json!("bad.name"), json!(1)]).await.is_err());
+
+        // Button release: ignored except for `set`.
+        driver
+            .execute("toggle", vec![json!("latch")], make_ctx(Some(json!(0))))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *seen.lock(),
+            vec![
+                ("vars.group".to_string(), json!("A")),
+                ("vars.group".to_string(), json!("B")),
+                ("vars.latch".to_string(), json!(true)),
+                ("vars.count".to_string(), json!(2)),
+                ("vars.count".to_string(), json!(3)),
+                ("vars.group".to_string(), json!("A")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn values_persist_unless_opted_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = declared("variables:\n  scratch:\n    initial: 0\n    persist: false\n");
+        {
+            let db = sled::open(dir.path().join("vars.sled")).unwrap();
+            let driver = VarsDriver::new(config.clone(), Some(db.open_tree(STATE_TREE).unwrap()));
+            driver.init(make_ctx(None)).await.unwrap();
+            for name in ["mode", "scratch"] {
+                driver
+                    .execute("set", vec![json!(name), json!(5)], make_ctx(None))
+                    .await
+                    .unwrap();
+            }
+            driver.shutdown().await.unwrap();
+        }
+
+        let db = sled::open(dir.path().join("vars.sled")).unwrap();
+        let driver = VarsDriver::new(config, Some(db.open_tree(STATE_TREE).unwrap()));
+        let values = driver.values.lock();
+        assert_eq!(values.get("mode"), Some(&json!(5)));
+        assert_eq!(values.get("scratch"), Some(&json!(0)));
+    }
+}
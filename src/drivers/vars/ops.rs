@@ -0,0 +1,84 @@
+//! Value operations behind the `toggle`, `increment` and `cycle` actions.
+
+use serde_json::Value;
+
+/// `true` unless the value is falsy (same rules as `truthy` indicators).
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Null => false,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+/// Boolean opposite of `current` (a never-set variable becomes `true`).
+pub(super) fn toggled(current: &Value) -> Value {
+    Value::Bool(!is_truthy(current))
+}
+
+/// `current + step`, clamped to `[min, max]`. Non-numeric values count
+/// as 0. Whole results stay integers so `${vars.x}` reads `3`, not `3.0`.
+pub(super) fn incremented(current: &Value, step: f64, min: Option<f64>, max: Option<f64>) -> Value {
+    let mut next = current.as_f64().unwrap_or(0.0) + step;
+    if let Some(max) = max {
+        next = next.min(max);
+    }
+    if let Some(min) = min {
+        next = next.max(min);
+    }
+    if next.fract() == 0.0 && next.abs() < 1e15 {
+        Value::from(next as i64)
+    } else {
+        serde_json::Number::from_f64(next).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Value after `current` in `values`, wrapping around; the first one when
+/// `current` isn't in the list. `None` for an empty list.
+pub(super) fn cycled(current: &Value, values: &[Value]) -> Option<Value> {
+    let next = match values.iter().position(|v| same(v, current)) {
+        Some(index) => (index + 1) % values.len(),
+        None => 0,
+    };
+    values.get(next).cloned()
+}
+
+/// Equality where `1` and `1.0` match (persisted numbers may round-trip
+/// as floats).
+fn same(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn toggle_and_increment() {
+        assert_eq!(toggled(&Value::Null), json!(true));
+        assert_eq!(toggled(&json!(true)), json!(false));
+        assert_eq!(toggled(&json!(1)), json!(false));
+
+        assert_eq!(incremented(&Value::Null, 1.0, None, None), json!(1));
+        assert_eq!(incremented(&json!(4), 1.0, None, Some(4.0)), json!(4));
+        assert_eq!(incremented(&json!(0), -1.0, Some(0.0), None), json!(0));
+        assert_eq!(incremented(&json!(0.5), 0.25, None, None), json!(0.75));
+    }
+
+    #[test]
+    fn cycle_wraps_and_restarts_from_unknown() {
+        let values = [json!("A"), json!("B"), json!("C")];
+        assert_eq!(cycled(&json!("A"), &values), Some(json!("B")));
+        assert_eq!(cycled(&json!("C"), &values), Some(json!("A")));
+        assert_eq!(cycled(&json!("Z"), &values), Some(json!("A")));
+        assert_eq!(cycled(&json!(2.0), &[json!(1), json!(2)]), Some(json!(1)));
+        assert_eq!(cycled(&Value::Null, &[]), None);
+    }
+}
@@ -0,0 +1,260 @@
+//! Driver trait implementation for vMix
+//!
+//! Every action name is forwarded verbatim as a vMix `FUNCTION`, so any of
+//! the several hundred shortcut functions can be bound from YAML without a
+//! dedicated dispatch entry.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+use super::driver::VmixDriver;
+use super::protocol;
+use super::{Driver, ExecutionContext, IndicatorCallback};
+
+/// Full-scale `Value` of vMix functions that are driven by a fader.
+/// `None` for trigger-style functions.
+fn fader_value_range(function: &str) -> Option<f64> {
+    match function {
+        "SetFader" => Some(255.0),
+        "SetVolume" | "SetMasterVolume" | "SetHeadphonesVolume" => Some(100.0),
+        f if f.starts_with("SetBus") && f.ends_with("Volume") => Some(100.0),
+        _ => None,
+    }
+}
+
+/// Scale a raw 14-bit fader reading from the router into a vMix `Value`.
+fn fader_query_value(function: &str, raw: Option<f64>) -> Option<String> {
+    let range = fader_value_range(function)?;
+    let scalar = (raw? / 16383.0).clamp(0.0, 1.0);
+    Some(format!("{}", (scalar * range).round() as i64))
+}
+
+/// Build the `FUNCTION` query from YAML params.
+///
+/// Accepts either the positional form `[input, value]` or a single object
+/// `[{Input: 1, Duration: 500}]` for functions needing other keys. A
+/// fader-derived value only fills `Value` when the params don't set it.
+fn build_query(params: &[Value], fader_value: Option<String>) -> Result<Vec<(String, String)>> {
+    let mut query: Vec<(String, String)> = Vec::new();
+
+    match params.first() {
+        Some(Value::Object(map)) => {
+            for (key, value) in map {
+                let value = protocol::param_to_query_value(value)
+                    .ok_or_else(|| anyhow!("vMix param '{}' must be a string or number", key))?;
+                query.push((key.clone(), value));
+            }
+        },
+        Some(_) => {
+            for (key, value) in ["Input", "Value"].iter().zip(params.iter()) {
+                let value = protocol::param_to_query_value(value)
+                    .ok_or_else(|| anyhow!("vMix param '{}' must be a string or number", key))?;
+                query.push((key.to_string(), value));
+            }
+        },
+        None => {},
+    }
+
+    if let Some(value) = fader_value {
+        if !query.iter().any(|(k, _)| k == "Value") {
+            query.push(("Value".to_string(), value));
+        }
+    }
+
+    Ok(query)
+}
+
+#[async_trait]
+impl Driver for VmixDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn init(&self, ctx: ExecutionContext) -> Result<()> {
+        info!("Initializing vMix TCP driver");
+
+        // Re-arm shutdown/reconnect guards in case this driver was previously
+        // unregistered (e.g. profile switch).
+        *self.shutdown_flag.lock() = false;
+        self.reconnecting
+            .store(false, std::sync::atomic::Ordering::Release);
+
+        if let Some(tracker) = ctx.activity_tracker {
+            *self.activity_tracker.write() = Some(tracker);
+        }
+
+        self.spawn_page_watcher().await;
+
+        match self.connect().await {
+            Ok(_) => {
+                info!("vMix connected on init");
+            },
+            Err(e) => {
+                warn!("vMix connection failed on init: {}", e);
+                warn!("Will retry automatically in background");
+
+                self.emit_status(crate::tray::ConnectionStatus::Disconnected);
+
+                let driver_clone = self.clone_for_task();
+                tokio::spawn(async move {
+                    driver_clone.schedule_reconnect().await;
+                });
+            },
+        }
+
+        // Always succeed - driver is registered even if disconnected
+        Ok(())
+    }
+
+    async fn execute(&self, action: &str, params: Vec<Value>, ctx: ExecutionContext) -> Result<()> {
+        if self.writer.lock().await.is_none() {
+            warn!("vMix not connected, action dropped");
+
+            if !self.reconnecting.load(std::sync::atomic::Ordering::Acquire) {
+                debug!("Triggering background reconnection");
+                let driver_clone = self.clone_for_task();
+                tokio::spawn(async move {
+                    driver_clone.schedule_reconnect().await;
+                });
+            }
+
+            return Err(anyhow!("vMix not connected"));
+        }
+
+        if let Some(ref tracker) = ctx.activity_tracker {
+            tracker.record(super::DRIVER_NAME, crate::tray::ActivityDirection::Outbound);
+        }
+
+        let fader_value = fader_query_value(action, ctx.value.as_ref().and_then(|v| v.as_f64()));
+
+        // Trigger-style functions are press-only; fader functions must see
+        // every position including 0.
+        if fader_value.is_none() && ctx.is_button_release() {
+            return Ok(());
+        }
+
+        let query = build_query(&params, fader_value)?;
+        let line = protocol::build_function_command(action, &query);
+        debug!("vMix → {}", line.trim_end());
+        self.send_line(&line).await
+    }
+
+    async fn sync(&self) -> Result<()> {
+        debug!("vMix driver sync - resubscribing for fresh tally/activator state");
+        if self.writer.lock().await.is_none() {
+            return Ok(());
+        }
+        self.last_emitted.write().clear();
+        self.send_line("SUBSCRIBE TALLY\r\nSUBSCRIBE ACTS\r\n")
+            .await
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        info!("Shutting down vMix TCP driver");
+        *self.shutdown_flag.lock() = true;
+        if let Some(tx) = self.page_watcher_shutdown.lock().take() {
+            let _ = tx.send(true);
+        }
+
+        // Retire the reader task, then drop the socket.
+        self.session
+            .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        self.writer.lock().await.take();
+
+        // Re-registration re-subscribes fresh callbacks.
+        self.indicator_emitters.write().clear();
+        self.status_callbacks.write().clear();
+
+        info!("vMix TCP driver shutdown complete");
+        Ok(())
+    }
+
+    fn subscribe_indicators(&self, callback: IndicatorCallback) {
+        debug!("vMix driver: new indicator subscription");
+
+        // Replay current tally/activator state to the new subscriber
+        for (signal, value) in self.last_emitted.read().iter() {
+            callback(signal.clone(), value.clone());
+        }
+
+        self.indicator_emitters.write().push(callback);
+    }
+
+    fn connection_status(&self) -> crate::tray::ConnectionStatus {
+        self.current_status.read().clone()
+    }
+
+    fn subscribe_connection_status(&self, callback: crate::tray::StatusCallback) {
+        debug!("vMix driver: new connection status subscription");
+
+        let current = self.current_status.read().clone();
+        callback(current);
+
+        self.status_callbacks.write().push(callback);
+    }
+
+    fn action_catalog(&self) -> Vec<crate::api_editor::ActionDescriptor> {
+        super::catalog::vmix_catalog()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn positional_params_map_to_input_and_value() {
+        let query = build_query(&[json!(3), json!("Hello")], None).unwrap();
+        assert_eq!(
+            query,
+            vec![
+                ("Input".to_string(), "3".to_string()),
+                ("Value".to_string(), "Hello".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn object_params_are_passed_through() {
+        let query = build_query(&[json!({"Input": "Cam 1", "Duration": 500})], None).unwrap();
+        assert!(query.contains(&("Input".to_string(), "Cam 1".to_string())));
+        assert!(query.contains(&("Duration".to_string(), "500".to_string())));
+    }
+
+    #[test]
+    fn fader_value_fills_missing_value() {
+        let value = fader_query_value("SetVolume", Some(16383.0));
+        assert_eq!(value.as_deref(), Some("100"));
+        let query = build_query(&[json!(1)], value).unwrap();
+        assert_eq!(query[1], ("Value".to_string(), "100".to_string()));
+    }
+
+    #[test]
+    fn explicit_value_wins_over_fader() {
+        let value = fader_query_value("SetVolume", Some(0.0));
+        let query = build_query(&[json!(1), json!(80)], value).unwrap();
+        assert_eq!(query.len(), 2);
+        assert_eq!(query[1], ("Value".to_string(), "80".to_string()));
+    }
+
+    #[test]
+    fn trigger_functions_have_no_fader_value() {
+        assert_eq!(fader_query_value("Cut", Some(127.0)), None);
+        assert_eq!(
+            fader_query_value("SetFader", Some(16383.0)).as_deref(),
+            Some("255")
+        );
+        assert_eq!(
+            fader_query_value("SetBusAVolume", Some(0.0)).as_deref(),
+            Some("0")
+        );
+    }
+
+    #[test]
+    fn rejects_non_scalar_params() {
+        assert!(build_query(&[json!([1, 2])], None).is_err());
+    }
+}
@@ -0,0 +1,40 @@
+//! Static action catalog for the vMix driver.
+//!
+//! vMix exposes several hundred shortcut functions and the driver forwards
+//! any action name verbatim, so the catalog only lists the common ones. Other
+//! functions can be typed manually in YAML.
+
+use crate::api_editor::action_catalog::{ActionDescriptor, ParamDescriptor, ParamKind};
+use serde_json::json;
+
+/// Build the static vMix action catalog.
+pub fn vmix_catalog() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::simple("Cut", "Cut")
+            .with_description("Cut the preview (or the given input) to program."),
+        ActionDescriptor::simple("Fade", "Fade")
+            .with_description("Fade the preview to program.")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String))
+            .with_param(
+                ParamDescriptor::new("Duration", ParamKind::Integer).with_default(json!(500)),
+            ),
+        ActionDescriptor::simple("PreviewInput", "Preview input")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String)),
+        ActionDescriptor::simple("ActiveInput", "Program input")
+            .with_description("Cut the given input straight to program.")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String)),
+        ActionDescriptor::simple("SetVolume", "Input volume (fader)")
+            .with_description("Bind to a fader; the position becomes the 0-100 volume.")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String)),
+        ActionDescriptor::simple("SetMasterVolume", "Master volume (fader)"),
+        ActionDescriptor::simple("AudioToggle", "Toggle input audio")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String)),
+        ActionDescriptor::simple("MasterAudioToggle", "Toggle master audio"),
+        ActionDescriptor::simple("SetFader", "T-bar (fader)")
+            .with_description("Bind to a fader; the position becomes the 0-255 T-bar value."),
+        ActionDescriptor::simple("OverlayInput1", "Toggle overlay 1")
+            .with_param(ParamDescriptor::new("Input", ParamKind::String)),
+        ActionDescriptor::simple("StartStopRecording", "Start/stop recording"),
+        ActionDescriptor::simple("StartStopStreaming", "Start/stop streaming"),
+    ]
+}
@@ -0,0 +1,192 @@
+//! vMix connection management and event handling
+//!
+//! Handles the TCP session, subscriptions, the line reader task and
+//! reconnection with backoff.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::TcpStream;
+use tokio::time::sleep;
+use tracing::{debug, info};
+
+use super::driver::VmixDriver;
+
+/// Connect timeout. vMix answers instantly on a LAN; anything slower is
+/// treated as unreachable so the reconnect loop keeps its cadence.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// RAII releaser for the `reconnecting` single-flight guard. Ensures the
+/// flag is cleared on every exit path including panic unwinds.
+struct ReconnectGuard<'a>(&'a AtomicBool);
+impl Drop for ReconnectGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl VmixDriver {
+    /// Emit connection status to all subscribers
+    pub(super) fn emit_status(&self, status: crate::tray::ConnectionStatus) {
+        *self.current_status.write() = status.clone();
+
+        // Best-effort tap into the editor live bus (if wired).
+        if let Some(tx) = self.live_tx.read().clone() {
+            let live_status = match &status {
+                crate::tray::ConnectionStatus::Connected => crate::event_bus::ConnectionStatus::Up,
+                _ => crate::event_bus::ConnectionStatus::Down,
+            };
+            let detail = match &status {
+                crate::tray::ConnectionStatus::Reconnecting { attempt } => {
+                    Some(format!("reconnect attempt #{}", attempt))
+                },
+                _ => None,
+            };
+            let _ = tx.send(crate::event_bus::LiveEvent::Connection {
+                target: super::DRIVER_NAME.into(),
+                status: live_status,
+                detail,
+                ts: crate::event_bus::now_ms(),
+            });
+        }
+
+        for callback in self.status_callbacks.read().iter() {
+            callback(status.clone());
+        }
+    }
+
+    /// Connect to the vMix TCP API and subscribe to tally + activators
+    pub(super) async fn connect(&self) -> Result<()> {
+        info!("🎥 Connecting to vMix at {}:{}", self.host, self.port);
+
+        let stream = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            TcpStream::connect((self.host.as_str(), self.port)),
+        )
+        .await
+        .context("Timed out connecting to vMix")?
+        .context("Failed to connect to vMix TCP API")?;
+        stream.set_nodelay(true).ok();
+
+        let (read_half, mut write_half) = stream.into_split();
+        write_half
+            .write_all(b"SUBSCRIBE TALLY\r\nSUBSCRIBE ACTS\r\n")
+            .await
+            .context("Failed to subscribe to vMix events")?;
+
+        let session = self.session.fetch_add(1, Ordering::AcqRel) + 1;
+        *self.writer.lock().await = Some(write_half);
+        *self.reconnect_count.lock() = 0;
+
+        // Fresh session: vMix resends a full tally snapshot on subscribe, and
+        // activators from the previous session may refer to removed inputs.
+        self.last_emitted.write().clear();
+
+        self.spawn_event_listener(read_half, session);
+
+        self.emit_status(crate::tray::ConnectionStatus::Connected);
+
+        info!("✅ vMix TCP API connected");
+        Ok(())
+    }
+
+    /// Spawn the background task reading vMix lines for `session`.
+    ///
+    /// Hands the listener a cheap `clone_for_task` of the driver so it can
+    /// emit signals and trigger reconnects without re-passing every Arc field.
+    pub(super) fn spawn_event_listener(&self, read_half: OwnedReadHalf, session: u64) {
+        let driver = self.clone_for_task();
+        tokio::spawn(super::event_listener::run_event_listener(
+            driver, read_half, session,
+        ));
+    }
+
+    /// Write one command line to vMix.
+    pub(super) async fn send_line(&self, line: &str) -> Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard.as_mut().context("vMix not connected")?;
+        if let Err(e) = writer.write_all(line.as_bytes()).await {
+            // Drop the dead half so the next action triggers a reconnect.
+            *guard = None;
+            return Err(e).context("Failed to write to vMix");
+        }
+        Ok(())
+    }
+
+    /// Schedule reconnection with backoff.
+    ///
+    /// Uses the `reconnecting: AtomicBool` flag as a single-flight guard so
+    /// the reader task and action-triggered retries cannot stack
+    /// overlapping reconnect loops.
+    pub(super) async fn schedule_reconnect(&self) {
+        if self
+            .reconnecting
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            debug!("vMix reconnect already in progress, skipping");
+            return;
+        }
+        let _guard = ReconnectGuard(&self.reconnecting);
+
+        loop {
+            if *self.shutdown_flag.lock() {
+                return;
+            }
+
+            let retry_count = {
+                let mut count = self.reconnect_count.lock();
+                *count += 1;
+                *count
+            };
+
+            let delay_ms = std::cmp::min(30_000, 1000 * retry_count);
+            debug!("⏳ vMix reconnect #{} in {}ms", retry_count, delay_ms);
+
+            self.emit_status(crate::tray::ConnectionStatus::Reconnecting {
+                attempt: retry_count,
+            });
+
+            sleep(Duration::from_millis(delay_ms as u64)).await;
+
+            if *self.shutdown_flag.lock() {
+                return;
+            }
+
+            match self.connect().await {
+                Ok(_) => {
+                    info!("✅ vMix reconnection successful");
+                    return;
+                },
+                Err(e) => {
+                    debug!("vMix reconnect #{} failed: {}", retry_count, e);
+                },
+            }
+        }
+    }
+
+    /// Emit a signal to all indicator subscribers
+    pub(super) fn emit_signal(&self, signal: &str, value: Value) {
+        let emitters = self.indicator_emitters.read();
+        for emit in emitters.iter() {
+            emit(signal.to_string(), value.clone());
+        }
+    }
+
+    /// Emit `value` for `signal` unless it matches the last emitted value.
+    /// Returns `true` when the signal was emitted.
+    pub(super) fn emit_if_changed(&self, signal: String, value: Value) -> bool {
+        {
+            let mut last = self.last_emitted.write();
+            if last.get(&signal) == Some(&value) {
+                return false;
+            }
+            last.insert(signal.clone(), value.clone());
+        }
+        self.emit_signal(&signal, value);
+        true
+    }
+}
@@ -0,0 +1,137 @@
+//! vMix Driver core struct and initialization
+//!
+//! Defines the VmixDriver struct with all its state and provides constructors.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::Arc;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::sync::{mpsc, watch, RwLock};
+
+/// Synthetic feedback channel used to inject `("vmix", midi_bytes)` updates
+/// into the unified router feedback pipeline.
+pub(super) type FeedbackSender = mpsc::Sender<(String, Vec<u8>)>;
+
+/// vMix TCP API driver
+pub struct VmixDriver {
+    pub(super) name: String,
+    pub(super) host: String,
+    pub(super) port: u16,
+
+    // Write half of the TCP session (`None` while disconnected)
+    pub(super) writer: Arc<tokio::sync::Mutex<Option<OwnedWriteHalf>>>,
+    /// Bumped on every successful connect. A reader task whose captured
+    /// session no longer matches exits without touching the new session.
+    pub(super) session: Arc<AtomicU64>,
+
+    // Indicator emission (using parking_lot for sync access)
+    pub(super) indicator_emitters: Arc<parking_lot::RwLock<Vec<super::IndicatorCallback>>>,
+    /// Last value emitted per signal. Tally snapshots are resent in full on
+    /// every change, so this keeps unchanged inputs from re-fanning-out, and
+    /// doubles as the replay source for new subscribers and page changes.
+    pub(super) last_emitted: Arc<parking_lot::RwLock<HashMap<String, serde_json::Value>>>,
+
+    // Connection status tracking
+    pub(super) status_callbacks: Arc<parking_lot::RwLock<Vec<crate::tray::StatusCallback>>>,
+    pub(super) current_status: Arc<parking_lot::RwLock<crate::tray::ConnectionStatus>>,
+
+    // Activity tracking
+    pub(super) activity_tracker:
+        Arc<parking_lot::RwLock<Option<Arc<crate::tray::ActivityTracker>>>>,
+
+    // Reconnection state
+    pub(super) reconnect_count: Arc<Mutex<usize>>,
+    pub(super) shutdown_flag: Arc<Mutex<bool>>,
+    /// Stops the page watcher of the current `init`: signalled on
+    /// `shutdown()`, replaced (so dropped) by the next `init`.
+    pub(super) page_watcher_shutdown: Arc<Mutex<Option<watch::Sender<bool>>>>,
+    /// Single-flight guard for the reconnect task (see `schedule_reconnect`).
+    pub(super) reconnecting: Arc<AtomicBool>,
+
+    // Fader/LED feedback wiring (populated post-construction)
+    pub(super) router: Arc<RwLock<Option<Arc<crate::router::Router>>>>,
+    pub(super) feedback_tx: Arc<RwLock<Option<FeedbackSender>>>,
+    pub(super) control_db: Arc<RwLock<Option<Arc<crate::control_mapping::ControlMappingDB>>>>,
+
+    // Optional live event broadcaster (editor `/api/live` WS).
+    pub(super) live_tx: Arc<parking_lot::RwLock<Option<crate::event_bus::LiveEventTx>>>,
+}
+
+impl VmixDriver {
+    /// Create a new vMix driver
+    pub fn new(host: String, port: u16) -> Self {
+        Self {
+            name: super::DRIVER_NAME.to_string(),
+            host,
+            port,
+            writer: Arc::new(tokio::sync::Mutex::new(None)),
+            session: Arc::new(AtomicU64::new(0)),
+            indicator_emitters: Arc::new(parking_lot::RwLock::new(Vec::new())),
+            last_emitted: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            status_callbacks: Arc::new(parking_lot::RwLock::new(Vec::new())),
+            current_status: Arc::new(parking_lot::RwLock::new(
+                crate::tray::ConnectionStatus::Disconnected,
+            )),
+            activity_tracker: Arc::new(parking_lot::RwLock::new(None)),
+            reconnect_count: Arc::new(Mutex::new(0)),
+            shutdown_flag: Arc::new(Mutex::new(false)),
+            page_watcher_shutdown: Arc::new(Mutex::new(None)),
+            reconnecting: Arc::new(AtomicBool::new(false)),
+            router: Arc::new(RwLock::new(None)),
+            feedback_tx: Arc::new(RwLock::new(None)),
+            control_db: Arc::new(RwLock::new(None)),
+            live_tx: Arc::new(parking_lot::RwLock::new(None)),
+        }
+    }
+
+    /// Create from config
+    pub fn from_config(config: &crate::config::VmixConfig) -> Self {
+        Self::new(config.host.clone(), config.port)
+    }
+
+    /// Inject the live event bus sender (editor `/api/live` WS).
+    pub fn set_live_tx(&self, tx: crate::event_bus::LiveEventTx) {
+        *self.live_tx.write() = Some(tx);
+    }
+
+    /// Wire the driver to the router so it can resolve the active page
+    /// to MIDI control specs for volume/mute feedback.
+    pub async fn set_router(&self, router: Arc<crate::router::Router>) {
+        *self.router.write().await = Some(router);
+    }
+
+    /// Wire the driver to the unified feedback channel.
+    pub async fn set_feedback_sender(&self, tx: FeedbackSender) {
+        *self.feedback_tx.write().await = Some(tx);
+    }
+
+    /// Share the application-wide control mapping DB.
+    pub async fn set_control_db(&self, db: Arc<crate::control_mapping::ControlMappingDB>) {
+        *self.control_db.write().await = Some(db);
+    }
+
+    /// Clone all Arc fields for spawning background tasks (reader, reconnect, etc.)
+    pub(super) fn clone_for_task(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            writer: Arc::clone(&self.writer),
+            session: Arc::clone(&self.session),
+            indicator_emitters: Arc::clone(&self.indicator_emitters),
+            last_emitted: Arc::clone(&self.last_emitted),
+            status_callbacks: Arc::clone(&self.status_callbacks),
+            current_status: Arc::clone(&self.current_status),
+            activity_tracker: Arc::clone(&self.activity_tracker),
+            reconnect_count: Arc::clone(&self.reconnect_count),
+            shutdown_flag: Arc::clone(&self.shutdown_flag),
+            page_watcher_shutdown: Arc::clone(&self.page_watcher_shutdown),
+            reconnecting: Arc::clone(&self.reconnecting),
+            router: Arc::clone(&self.router),
+            feedback_tx: Arc::clone(&self.feedback_tx),
+            control_db: Arc::clone(&self.control_db),
+            live_tx: Arc::clone(&self.live_tx),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! vMix TCP line reader
+//!
+//! Consumes tally/activator lines for one TCP session, turns them into
+//! indicator signals and audio feedback, and kicks off reconnection when
+//! the socket closes.
+
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::tcp::OwnedReadHalf;
+use tracing::{debug, trace, warn};
+
+use super::driver::VmixDriver;
+use super::protocol::{self, VmixMessage};
+
+/// Run the vMix reader loop (spawned as a tokio task).
+///
+/// `session` is the generation captured at connect time: once a newer
+/// session exists (reconnect, shutdown), this task exits without touching
+/// the shared writer or status.
+pub(super) async fn run_event_listener(driver: VmixDriver, read_half: OwnedReadHalf, session: u64) {
+    let mut lines = BufReader::new(read_half).lines();
+
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => {
+                if driver.session.load(Ordering::Acquire) != session {
+                    return;
+                }
+                handle_line(&driver, &line).await;
+            },
+            Ok(None) => {
+                debug!("vMix closed the connection");
+                break;
+            },
+            Err(e) => {
+                debug!("vMix read error: {}", e);
+                break;
+            },
+        }
+    }
+
+    // Only the current session owns the writer / status.
+    if driver.session.load(Ordering::Acquire) != session {
+        return;
+    }
+    *driver.writer.lock().await = None;
+
+    if *driver.shutdown_flag.lock() {
+        return;
+    }
+
+    warn!("vMix connection lost");
+    driver.emit_status(crate::tray::ConnectionStatus::Disconnected);
+    tokio::spawn(async move {
+        driver.schedule_reconnect().await;
+    });
+}
+
+async fn handle_line(driver: &VmixDriver, line: &str) {
+    if let Some(ref tracker) = *driver.activity_tracker.read() {
+        tracker.record(super::DRIVER_NAME, crate::tray::ActivityDirection::Inbound);
+    }
+
+    match protocol::parse_line(line) {
+        VmixMessage::Tally(states) => {
+            for (idx, state) in states.iter().enumerate() {
+                driver.emit_if_changed(
+                    protocol::tally_signal(idx + 1),
+                    Value::String(state.as_str().to_string()),
+                );
+            }
+        },
+        VmixMessage::Activator { name, input, value } => {
+            let value = protocol::activator_value(&value);
+            let changed = driver.emit_if_changed(
+                protocol::activator_signal(&name, input.as_deref()),
+                value.clone(),
+            );
+            if changed {
+                driver
+                    .send_activator_feedback(&name, input.as_deref(), &value)
+                    .await;
+            }
+        },
+        VmixMessage::Function { ok: false, detail } => {
+            warn!("vMix FUNCTION failed: {}", detail);
+        },
+        VmixMessage::Function { ok: true, .. } => {},
+        VmixMessage::Other(raw) => trace!("vMix: {}", raw),
+    }
+}
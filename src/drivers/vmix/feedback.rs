@@ -0,0 +1,313 @@
+//! vMix volume/mute feedback onto X-Touch faders and button LEDs
+//!
+//! Audio activators are resolved against the active page: the control bound
+//! to the matching vMix function (e.g. `SetVolume` for `InputVolume`) with
+//! the same `Input` param receives the value as fader/LED feedback through
+//! the unified router pipeline, exactly like `winaudio`.
+
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::driver::VmixDriver;
+
+/// How an activator value is rendered on the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FeedbackKind {
+    /// 0..1 volume → fader position.
+    Volume,
+    /// 1 = audio on, 0 = muted → LED lit while muted.
+    Mute,
+}
+
+/// Map an audio activator to its feedback kind and the vMix functions whose
+/// controls should receive it. `None` for non-audio activators.
+pub(super) fn feedback_target(activator: &str) -> Option<(FeedbackKind, &'static [&'static str])> {
+    match activator {
+        "InputVolume" => Some((FeedbackKind::Volume, &["SetVolume"])),
+        "MasterVolume" => Some((FeedbackKind::Volume, &["SetMasterVolume"])),
+        "InputAudio" => Some((FeedbackKind::Mute, &["AudioToggle", "AudioOn", "AudioOff"])),
+        "MasterAudio" => Some((
+            FeedbackKind::Mute,
+            &["MasterAudioToggle", "MasterAudioOn", "MasterAudioOff"],
+        )),
+        _ => None,
+    }
+}
+
+/// Find the control on `page` (then global controls) bound to one of
+/// `functions`, targeting `input` when the activator carries one.
+pub(super) fn find_control_id(
+    page: &crate::config::PageConfig,
+    global: Option<&crate::config::GlobalPageDefaults>,
+    functions: &[&str],
+    input: Option<&str>,
+) -> Option<String> {
+    let matches = |m: &crate::config::ControlMapping| {
+        m.app == super::DRIVER_NAME
+            && m.action.as_deref().is_some_and(|a| functions.contains(&a))
+            && match input {
+                None => true,
+                Some(want) => mapping_input(m.params.as_deref()).as_deref() == Some(want),
+            }
+    };
+
+    page.controls
+        .iter()
+        .chain(global.and_then(|g| g.controls.as_ref()))
+        .flat_map(|controls| controls.iter())
+        .find(|(_, m)| matches(m))
+        .map(|(id, _)| id.clone())
+}
+
+/// Extract the `Input` a mapping targets, from either the positional form
+/// (`params: [1]`) or the object form (`params: [{Input: 1}]`).
+fn mapping_input(params: Option<&[Value]>) -> Option<String> {
+    match params?.first()? {
+        Value::Object(map) => map
+            .get("Input")
+            .and_then(super::protocol::param_to_query_value),
+        other => super::protocol::param_to_query_value(other),
+    }
+}
+
+/// Encode a feedback value for `spec`.
+pub(super) fn feedback_bytes(
+    spec: &crate::control_mapping::MidiSpec,
+    kind: FeedbackKind,
+    value: f64,
+) -> Vec<u8> {
+    use crate::control_mapping::MidiSpec;
+    use crate::midi::{convert, MidiMessage};
+
+    match kind {
+        FeedbackKind::Mute => spec.led_bytes(value == 0.0),
+        FeedbackKind::Volume => {
+            let scalar = value.clamp(0.0, 1.0);
+            match *spec {
+                MidiSpec::PitchBend { channel } => MidiMessage::PitchBend {
+                    channel: channel & 0x0F,
+                    value: convert::denormalize_to_14bit(scalar),
+                }
+                .to_bytes(),
+                MidiSpec::ControlChange { cc } => MidiMessage::ControlChange {
+                    channel: 0,
+                    cc,
+                    value: convert::denormalize_to_7bit(scalar),
+                }
+                .to_bytes(),
+                MidiSpec::Note { note } => MidiSpec::Note { note }.led_bytes(scalar > 0.0),
+            }
+        },
+    }
+}
+
+impl VmixDriver {
+    /// Forward an audio activator to the bound control on the active page.
+    pub(super) async fn send_activator_feedback(
+        &self,
+        activator: &str,
+        input: Option<&str>,
+        value: &Value,
+    ) {
+        let Some((kind, functions)) = feedback_target(activator) else {
+            return;
+        };
+        let Some(value) = value.as_f64() else {
+            return;
+        };
+        let Some(tx) = self.feedback_tx.read().await.clone() else {
+            return;
+        };
+        let Some(router) = self.router.read().await.clone() else {
+            return;
+        };
+        let Some(page) = router.get_active_page().await else {
+            return;
+        };
+        let Some(db) = self.control_db.read().await.clone() else {
+            debug!("vMix: control DB not yet wired, dropping feedback");
+            return;
+        };
+
+        let (mcu_mode, global) = {
+            let config = router.config.read().await;
+            (config.is_mcu_mode(), config.pages_global.clone())
+        };
+        let Some(control_id) = find_control_id(&page, global.as_ref(), functions, input) else {
+            return;
+        };
+        let Some(spec) = db.get_midi_spec(&control_id, mcu_mode) else {
+            return;
+        };
+
+        let bytes = feedback_bytes(&spec, kind, value);
+        if let Err(e) = tx.send((super::DRIVER_NAME.to_string(), bytes)).await {
+            debug!("vMix: feedback channel closed: {}", e);
+        }
+    }
+
+    /// Re-send cached audio activators every time the user activates a
+    /// page, so faders bound to vMix inputs land on the current levels.
+    pub(super) async fn spawn_page_watcher(&self) {
+        let router_arc = self.router.read().await.clone();
+        let Some(router) = router_arc else {
+            debug!("vMix: no router wired, skipping page watcher");
+            return;
+        };
+        let Some(live_tx) = router.live_tx_snapshot().await else {
+            debug!("vMix: live_tx not yet wired, skipping page watcher");
+            return;
+        };
+        let mut rx = live_tx.subscribe();
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::watch::channel(false);
+        // Dropping the previous init's sender stops its watcher.
+        *self.page_watcher_shutdown.lock() = Some(shutdown_tx);
+        let driver = Arc::new(self.clone_for_task());
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    changed = shutdown_rx.changed() => {
+                        if changed.is_err() || *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    recv = rx.recv() => {
+                        let event = match recv {
+                            Ok(ev) => ev,
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                                debug!("vMix page watcher lagged {} live events; continuing", n);
+                                continue;
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        };
+                        if matches!(event, crate::event_bus::LiveEvent::PageChanged { .. }) {
+                            driver.replay_audio_feedback().await;
+                        }
+                    }
+                }
+            }
+            debug!("vMix page watcher stopped");
+        });
+    }
+
+    async fn replay_audio_feedback(&self) {
+        let prefix = format!("{}.", super::signals::ACTIVATOR_PREFIX);
+        let cached: Vec<(String, Value)> = self
+            .last_emitted
+            .read()
+            .iter()
+            .filter(|(signal, _)| signal.starts_with(&prefix))
+            .map(|(s, v)| (s.clone(), v.clone()))
+            .collect();
+
+        for (signal, value) in cached {
+            let rest = &signal[prefix.len()..];
+            let (name, input) = match rest.split_once('.') {
+                Some((name, input)) => (name, Some(input)),
+                None => (rest, None),
+            };
+            if feedback_target(name).is_some() {
+                self.send_activator_feedback(name, input, &value).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ControlMapping, PageConfig};
+    use crate::control_mapping::MidiSpec;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn vmix_control(action: &str, params: Vec<Value>) -> ControlMapping {
+        serde_yaml::from_value(
+            serde_yaml::to_value(json!({
+                "app": "vmix",
+                "action": action,
+                "params": params,
+            }))
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn page(controls: Vec<(&str, ControlMapping)>) -> PageConfig {
+        PageConfig {
+            name: "vmix".into(),
+            controls: Some(
+                controls
+                    .into_iter()
+                    .map(|(id, m)| (id.to_string(), m))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_input_volume_to_matching_fader() {
+        let page = page(vec![
+            ("fader1", vmix_control("SetVolume", vec![json!(1)])),
+            (
+                "fader2",
+                vmix_control("SetVolume", vec![json!({"Input": "2"})]),
+            ),
+        ]);
+        let (_, functions) = feedback_target("InputVolume").unwrap();
+        assert_eq!(
+            find_control_id(&page, None, functions, Some("2")).as_deref(),
+            Some("fader2")
+        );
+        assert_eq!(
+            find_control_id(&page, None, functions, Some("1")).as_deref(),
+            Some("fader1")
+        );
+        assert_eq!(find_control_id(&page, None, functions, Some("3")), None);
+    }
+
+    #[test]
+    fn master_volume_ignores_input() {
+        let page = page(vec![("fader9", vmix_control("SetMasterVolume", vec![]))]);
+        let (_, functions) = feedback_target("MasterVolume").unwrap();
+        assert_eq!(
+            find_control_id(&page, None, functions, None).as_deref(),
+            Some("fader9")
+        );
+    }
+
+    #[test]
+    fn non_audio_activators_have_no_feedback() {
+        assert!(feedback_target("Input").is_none());
+        assert!(feedback_target("InputPreview").is_none());
+    }
+
+    #[test]
+    fn mute_led_lit_when_audio_off() {
+        let spec = MidiSpec::Note { note: 16 };
+        assert_eq!(
+            feedback_bytes(&spec, FeedbackKind::Mute, 0.0),
+            vec![0x90, 16, 127]
+        );
+        assert_eq!(
+            feedback_bytes(&spec, FeedbackKind::Mute, 1.0),
+            vec![0x90, 16, 0]
+        );
+    }
+
+    #[test]
+    fn volume_maps_to_pitch_bend() {
+        let spec = MidiSpec::PitchBend { channel: 0 };
+        assert_eq!(
+            feedback_bytes(&spec, FeedbackKind::Volume, 1.0),
+            vec![0xE0, 0x7F, 0x7F]
+        );
+        assert_eq!(
+            feedback_bytes(&spec, FeedbackKind::Volume, 0.0),
+            vec![0xE0, 0, 0]
+        );
+    }
+}
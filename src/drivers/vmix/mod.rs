@@ -0,0 +1,36 @@
+//! vMix TCP API Driver
+//!
+//! Provides integration with vMix via its line-based TCP API (port 8099):
+//! - `FUNCTION` calls by name with `Input` / `Value` query params
+//! - `SUBSCRIBE TALLY` → per-input `vmix.tally.<input>` signals
+//! - `SUBSCRIBE ACTS` → `vmix.activator.<name>[.<input>]` signals
+//! - Input/master volume and mute feedback onto faders and button LEDs
+//! - Automatic reconnection
+
+// Module declarations
+mod actions;
+pub mod catalog;
+mod connection;
+mod driver;
+mod event_listener;
+mod feedback;
+mod protocol;
+
+// Re-export main types
+pub use driver::VmixDriver;
+
+// Re-export Driver trait and types from parent
+use super::{Driver, ExecutionContext, IndicatorCallback};
+
+/// Driver name used for `app: "vmix"` in YAML control mappings.
+pub const DRIVER_NAME: &str = "vmix";
+
+/// vMix indicator signal names (shared between emitter and consumers).
+pub mod signals {
+    /// Per-input tally: `vmix.tally.<input>` = `"off"` | `"program"` | `"preview"`.
+    pub const TALLY_PREFIX: &str = "vmix.tally";
+    /// Activator events: `vmix.activator.<name>.<input>` (or
+    /// `vmix.activator.<name>` for input-less activators such as
+    /// `MasterVolume`). Values are numbers when vMix sends a numeric payload.
+    pub const ACTIVATOR_PREFIX: &str = "vmix.activator";
+}
@@ -0,0 +1,257 @@
+//! vMix TCP API wire format
+//!
+//! The API is line-based (`\r\n` terminated). Every response starts with the
+//! command name followed by `OK` or `ER`:
+//!
+//! ```text
+//! FUNCTION OK Completed
+//! TALLY OK 0121            (one digit per input: 0=off, 1=program, 2=preview)
+//! ACTS OK InputVolume 1 0.5
+//! ACTS OK MasterVolume 0.8
+//! ```
+
+use serde_json::Value;
+
+/// Tally state of a single vMix input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TallyState {
+    Off,
+    Program,
+    Preview,
+}
+
+impl TallyState {
+    fn from_digit(c: char) -> Self {
+        match c {
+            '1' => TallyState::Program,
+            '2' => TallyState::Preview,
+            _ => TallyState::Off,
+        }
+    }
+
+    /// Signal value emitted for this state.
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            TallyState::Off => "off",
+            TallyState::Program => "program",
+            TallyState::Preview => "preview",
+        }
+    }
+}
+
+/// A parsed line received from vMix.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum VmixMessage {
+    /// Full tally snapshot, index 0 = input 1.
+    Tally(Vec<TallyState>),
+    /// Activator event. `input` is `None` for global activators.
+    Activator {
+        name: String,
+        input: Option<String>,
+        value: String,
+    },
+    /// Result of a `FUNCTION` call.
+    Function { ok: bool, detail: String },
+    /// Anything else (`VERSION`, `SUBSCRIBE OK`, ...).
+    Other(String),
+}
+
+/// Parse one response line (without its line terminator).
+pub(super) fn parse_line(line: &str) -> VmixMessage {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let mut parts = line.splitn(3, ' ');
+    let command = parts.next().unwrap_or_default();
+    let status = parts.next().unwrap_or_default();
+    let rest = parts.next().unwrap_or_default();
+
+    match (command, status) {
+        ("TALLY", "OK") => {
+            VmixMessage::Tally(rest.trim().chars().map(TallyState::from_digit).collect())
+        },
+        ("ACTS", "OK") => {
+            let tokens: Vec<&str> = rest.split_whitespace().collect();
+            match tokens.as_slice() {
+                [name, value] => VmixMessage::Activator {
+                    name: (*name).to_string(),
+                    input: None,
+                    value: (*value).to_string(),
+                },
+                [name, input, value, ..] => VmixMessage::Activator {
+                    name: (*name).to_string(),
+                    input: Some((*input).to_string()),
+                    value: (*value).to_string(),
+                },
+                _ => VmixMessage::Other(line.to_string()),
+            }
+        },
+        ("FUNCTION", "OK") | ("FUNCTION", "ER") => VmixMessage::Function {
+            ok: status == "OK",
+            detail: rest.to_string(),
+        },
+        _ => VmixMessage::Other(line.to_string()),
+    }
+}
+
+/// Build a `FUNCTION` command line. Query values are percent-encoded as
+/// vMix expects URL-style parameters.
+pub(super) fn build_function_command(function: &str, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return format!("FUNCTION {}\r\n", function);
+    }
+    let query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("FUNCTION {} {}\r\n", function, query)
+}
+
+/// Signal name for the tally of a 1-based input number.
+pub(super) fn tally_signal(input: usize) -> String {
+    format!("{}.{}", super::signals::TALLY_PREFIX, input)
+}
+
+/// Signal name for an activator event.
+pub(super) fn activator_signal(name: &str, input: Option<&str>) -> String {
+    match input {
+        Some(input) => format!("{}.{}.{}", super::signals::ACTIVATOR_PREFIX, name, input),
+        None => format!("{}.{}", super::signals::ACTIVATOR_PREFIX, name),
+    }
+}
+
+/// Convert an activator payload to a signal value: numeric payloads become
+/// JSON numbers (so `equals: 1` and `truthy` work), anything else a string.
+pub(super) fn activator_value(raw: &str) -> Value {
+    raw.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(|n| {
+            // Keep integral payloads as integers so `equals: 1` matches.
+            match n.as_f64() {
+                Some(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+                    Value::Number((f as i64).into())
+                },
+                _ => Value::Number(n),
+            }
+        })
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Stringify a YAML param for use as a query value.
+pub(super) fn param_to_query_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn percent_encode(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for b in raw.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            },
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_tally_snapshot() {
+        assert_eq!(
+            parse_line("TALLY OK 0121\r\n"),
+            VmixMessage::Tally(vec![
+                TallyState::Off,
+                TallyState::Program,
+                TallyState::Preview,
+                TallyState::Program,
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_input_activator() {
+        assert_eq!(
+            parse_line("ACTS OK InputVolume 3 0.75"),
+            VmixMessage::Activator {
+                name: "InputVolume".into(),
+                input: Some("3".into()),
+                value: "0.75".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_global_activator() {
+        assert_eq!(
+            parse_line("ACTS OK MasterVolume 1"),
+            VmixMessage::Activator {
+                name: "MasterVolume".into(),
+                input: None,
+                value: "1".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_function_error() {
+        assert_eq!(
+            parse_line("FUNCTION ER Input not found"),
+            VmixMessage::Function {
+                ok: false,
+                detail: "Input not found".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_lines_are_other() {
+        assert!(matches!(
+            parse_line("VERSION OK 27.0.0.49"),
+            VmixMessage::Other(_)
+        ));
+        assert!(matches!(parse_line("ACTS OK"), VmixMessage::Other(_)));
+    }
+
+    #[test]
+    fn function_command_encodes_query() {
+        let cmd = build_function_command(
+            "SetText",
+            &[
+                ("Input".into(), "Lower Third".into()),
+                ("Value".into(), "a&b".into()),
+            ],
+        );
+        assert_eq!(cmd, "FUNCTION SetText Input=Lower%20Third&Value=a%26b\r\n");
+        assert_eq!(build_function_command("Cut", &[]), "FUNCTION Cut\r\n");
+    }
+
+    #[test]
+    fn signal_names() {
+        assert_eq!(tally_signal(2), "vmix.tally.2");
+        assert_eq!(
+            activator_signal("Input", Some("4")),
+            "vmix.activator.Input.4"
+        );
+        assert_eq!(
+            activator_signal("MasterVolume", None),
+            "vmix.activator.MasterVolume"
+        );
+    }
+
+    #[test]
+    fn activator_values_are_typed() {
+        assert_eq!(activator_value("1"), json!(1));
+        assert_eq!(activator_value("0.5"), json!(0.5));
+        assert_eq!(activator_value("Camera"), json!("Camera"));
+    }
+}
@@ -63,12 +63,9 @@ pub fn parse_session_target(params: &[Value]) -> Result<SessionTarget> {
             }
             Ok(SessionTarget::Pinned(n))
         },
-        "discovered" => {
-            if n >= 8 {
-                return Err(anyhow!("discovered slot {} must be < 8", n));
-            }
-            Ok(SessionTarget::Discovered(n))
-        },
+        // Banked pages address slots past the first eight; the YAML itself
+        // is held to `< 8` by config validation.
+        "discovered" => Ok(SessionTarget::Discovered(n)),
         other => Err(anyhow!(
             "unknown session target kind '{}': expected 'auto', 'pinned' or 'discovered'",
             other
@@ -93,6 +90,12 @@ mod tests {
         assert_eq!(p, SessionTarget::Discovered(2));
     }
 
+    #[test]
+    fn parses_banked_discovered_slot() {
+        let p = parse_session_target(&[json!("discovered:12")]).unwrap();
+        assert_eq!(p, SessionTarget::Discovered(12));
+    }
+
     #[test]
     fn parses_auto() {
         let p = parse_session_target(&[json!("auto")]).unwrap();
@@ -17,7 +17,7 @@ mod catalog;
 mod com_handlers;
 #[cfg(target_os = "windows")]
 mod com_thread;
-mod mapping;
+pub(crate) mod mapping;
 #[cfg(target_os = "windows")]
 mod master;
 #[cfg(target_os = "windows")]
@@ -25,7 +25,7 @@ mod session;
 #[cfg(target_os = "windows")]
 mod session_events;
 
-use crate::config::{ControlMapping, PageConfig, WinAudioConfig};
+use crate::config::{ControlMapping, PageConfig, PinnedApp, WinAudioConfig};
 use crate::drivers::{Driver, ExecutionContext};
 use crate::xtouch::{build_lcd_colors_sysex, build_lcd_strip_sysex};
 use anyhow::Result;
@@ -55,9 +55,15 @@ type FeedbackSender = Arc<RwLock<Option<mpsc::Sender<FeedbackMsg>>>>;
 /// longer load-bearing — renaming `"Windows Audio"` to anything else
 /// keeps the driver wired (#39).
 fn page_uses_winaudio(page: &PageConfig) -> bool {
+    page_uses_app(page, DRIVER_NAME)
+}
+
+/// True if at least one control on `page` binds `app`. Shared with the
+/// PulseAudio driver, which follows the same page-eligibility model.
+pub(crate) fn page_uses_app(page: &PageConfig, app: &str) -> bool {
     page.controls
         .as_ref()
-        .is_some_and(|c| c.values().any(|m| m.app == DRIVER_NAME))
+        .is_some_and(|c| c.values().any(|m| m.app == app))
 }
 
 /// True if the page at `index` in the router's current config uses the
@@ -231,7 +237,7 @@ impl Driver for WinAudioDriver {
                     let router = self.router.clone();
                     let led_tx = self.led_tx.clone();
                     tokio::spawn(async move {
-                        wait_for_profile_loaded(live_rx).await;
+                        wait_for_profile_loaded("WinAudio", live_rx).await;
                         refresh_full_state(&com, &pinned_lc_cache, &disc).await;
                         // If the active page already maps winaudio at
                         // startup (no PageChanged event will fire to wake
@@ -420,7 +426,7 @@ impl WinAudioDriver {
                 let control_id = ctx.control_id.as_deref()?;
                 let router = self.router.read().await.clone()?;
                 let page = router.get_active_page().await?;
-                let auto_idx = auto_strip_index(&page, action, control_id)?;
+                let auto_idx = auto_strip_index(&page, DRIVER_NAME, action, control_id)?;
                 let pinned_lc = self.pinned_lc_cache.load();
                 let discovery = self.discovery.read().await;
                 mapping::discovered_target(&pinned_lc, &discovery, auto_idx)
@@ -550,7 +556,7 @@ async fn refresh_full_state(
 /// Convert a raw 14-bit PitchBend value from the router into a `[0.0, 1.0]`
 /// scalar. The router forwards `ctx.value` verbatim as the integer 14-bit
 /// reading.
-fn normalize_fader_value(v: f64) -> f32 {
+pub(crate) fn normalize_fader_value(v: f64) -> f32 {
     ((v / 16383.0) as f32).clamp(0.0, 1.0)
 }
 
@@ -559,13 +565,17 @@ fn normalize_fader_value(v: f64) -> f32 {
 /// elapses. Replaces the legacy 800 ms post-init sleep (#36).
 ///
 /// If `live_rx` is `None` (live bus not wired in tests, unusual prod
-/// path), fall straight through.
-async fn wait_for_profile_loaded(
+/// path), fall straight through. `driver` only labels the log lines.
+pub(crate) async fn wait_for_profile_loaded(
+    driver: &str,
     live_rx: Option<tokio::sync::broadcast::Receiver<crate::event_bus::LiveEvent>>,
 ) {
     const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
     let Some(mut rx) = live_rx else {
-        debug!("WinAudio init: live bus unavailable, skipping ProfileLoaded await");
+        debug!(
+            "{} init: live bus unavailable, skipping ProfileLoaded await",
+            driver
+        );
         return;
     };
     let fut = async {
@@ -577,8 +587,8 @@ async fn wait_for_profile_loaded(
     };
     if tokio::time::timeout(TIMEOUT, fut).await.is_err() {
         debug!(
-            "WinAudio init: ProfileLoaded not received within {:?}, proceeding with refresh anyway",
-            TIMEOUT
+            "{} init: ProfileLoaded not received within {:?}, proceeding with refresh anyway",
+            driver, TIMEOUT
         );
     }
 }
@@ -614,18 +624,18 @@ mod normalize_tests {
 /// buffer. Each tuple maps to at most one fader/LED on the active page;
 /// any matching action+target rebinding in YAML is honored without code
 /// changes.
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", target_os = "linux"))]
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum FeedbackKey {
+pub(crate) enum FeedbackKey {
     Master,
     /// Session slot target string, e.g. `"pinned:1"` or `"discovered:0"`.
     Session(String),
 }
 
-#[cfg(target_os = "windows")]
-struct PendingFeedback {
-    scalar: f32,
-    mute: bool,
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub(crate) struct PendingFeedback {
+    pub(crate) scalar: f32,
+    pub(crate) mute: bool,
 }
 
 /// Pump `AudioEvent`s emitted by the COM thread into the router's
@@ -694,7 +704,7 @@ async fn run_event_consumer(
                     continue;
                 }
                 let drained: Vec<(FeedbackKey, PendingFeedback)> = pending.drain().collect();
-                if !flush_pending(&drained, &feedback_tx, &router).await {
+                if !flush_pending(DRIVER_NAME, &drained, &feedback_tx, &router).await {
                     debug!("WinAudio feedback channel closed, exiting consumer");
                     return;
                 }
@@ -778,17 +788,19 @@ async fn buffer_event(
 }
 
 /// Resolve every pending event to MIDI bytes (via active page +
-/// `control_mapping.csv`) and emit them. Returns `false` if the
-/// feedback channel is closed.
-#[cfg(target_os = "windows")]
-async fn flush_pending(
+/// `control_mapping.csv`) and emit them as `app` feedback. Returns
+/// `false` if the feedback channel is closed.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub(crate) async fn flush_pending(
+    app: &str,
     drained: &[(FeedbackKey, PendingFeedback)],
     feedback_tx: &mpsc::Sender<(String, Vec<u8>)>,
     router: &Arc<RwLock<Option<Arc<crate::router::Router>>>>,
 ) -> bool {
     let Some(router_arc) = router.read().await.clone() else {
         debug!(
-            "WinAudio flush: no router wired, dropping {} event(s)",
+            "{} flush: no router wired, dropping {} event(s)",
+            app,
             drained.len()
         );
         return true;
@@ -799,7 +811,7 @@ async fn flush_pending(
     let mcu_mode = router_arc.config.read().await.is_mcu_mode();
 
     let Ok(db) = crate::control_mapping::load_default_mappings() else {
-        warn!("WinAudio flush: control_mapping DB unavailable");
+        warn!("{} flush: control_mapping DB unavailable", app);
         return true;
     };
 
@@ -810,17 +822,17 @@ async fn flush_pending(
         };
 
         // Volume → PitchBend or CC.
-        if let Some(spec) = resolve_action_spec(&page, volume_action, target, db, mcu_mode) {
+        if let Some(spec) = resolve_action_spec(&page, app, volume_action, target, db, mcu_mode) {
             let bytes = bytes_for_volume(&spec, p.scalar);
-            if !try_send(feedback_tx, bytes).await {
+            if !try_send(app, feedback_tx, bytes).await {
                 return false;
             }
         }
 
         // Mute → Note (LED).
-        if let Some(spec) = resolve_action_spec(&page, mute_action, target, db, mcu_mode) {
+        if let Some(spec) = resolve_action_spec(&page, app, mute_action, target, db, mcu_mode) {
             let bytes = spec.led_bytes(p.mute);
-            if !try_send(feedback_tx, bytes).await {
+            if !try_send(app, feedback_tx, bytes).await {
                 return false;
             }
         }
@@ -831,21 +843,23 @@ async fn flush_pending(
 /// Find the page control bound to `(action, target)` and resolve it to
 /// a hardware MIDI spec via `control_mapping.csv`. Page controls are
 /// checked first, then global controls.
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", target_os = "linux"))]
 fn resolve_action_spec(
     page: &crate::config::PageConfig,
+    app: &str,
     action: &str,
     target: Option<&str>,
     db: &crate::control_mapping::ControlMappingDB,
     mcu_mode: bool,
 ) -> Option<crate::control_mapping::MidiSpec> {
-    let control_id = find_winaudio_control_id(page, action, target)?;
+    let control_id = find_session_control_id(page, app, action, target)?;
     db.get_midi_spec(&control_id, mcu_mode)
 }
 
-#[cfg(target_os = "windows")]
-fn find_winaudio_control_id(
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn find_session_control_id(
     page: &crate::config::PageConfig,
+    app: &str,
     action: &str,
     target: Option<&str>,
 ) -> Option<String> {
@@ -853,7 +867,7 @@ fn find_winaudio_control_id(
     controls
         .iter()
         .find(|(_, m)| {
-            m.app == DRIVER_NAME
+            m.app == app
                 && m.action.as_deref() == Some(action)
                 && match target {
                     None => true,
@@ -869,8 +883,8 @@ fn find_winaudio_control_id(
         .map(|(id, _)| id.clone())
 }
 
-#[cfg(target_os = "windows")]
-fn bytes_for_volume(spec: &crate::control_mapping::MidiSpec, scalar: f32) -> Vec<u8> {
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub(crate) fn bytes_for_volume(spec: &crate::control_mapping::MidiSpec, scalar: f32) -> Vec<u8> {
     use crate::control_mapping::MidiSpec;
     use crate::midi::{convert, MidiMessage};
     let scalar = scalar.clamp(0.0, 1.0) as f64;
@@ -890,12 +904,9 @@ fn bytes_for_volume(spec: &crate::control_mapping::MidiSpec, scalar: f32) -> Vec
     }
 }
 
-#[cfg(target_os = "windows")]
-async fn try_send(feedback_tx: &mpsc::Sender<(String, Vec<u8>)>, raw: Vec<u8>) -> bool {
-    feedback_tx
-        .send((DRIVER_NAME.to_string(), raw))
-        .await
-        .is_ok()
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+async fn try_send(app: &str, feedback_tx: &mpsc::Sender<(String, Vec<u8>)>, raw: Vec<u8>) -> bool {
+    feedback_tx.send((app.to_string(), raw)).await.is_ok()
 }
 
 // -- Auto target resolution + dynamic LCD render -----------------------------
@@ -912,16 +923,21 @@ fn strip_index_of(control_id: &str) -> Option<u8> {
 }
 
 /// Position of `control_id` among the page controls bound to
-/// `winaudio.<action>` with `params: ["auto"]`, ordered by ascending
+/// `<app>.<action>` with `params: ["auto"]`, ordered by ascending
 /// strip number. The HashMap-backed `controls` field has no inherent
 /// declaration order, so we use the strip number as a deterministic
-/// key. Returns `None` if `control_id` isn't an `auto`-bound winaudio
-/// control on this page.
-fn auto_strip_index(page: &PageConfig, action: &str, control_id: &str) -> Option<u8> {
+/// key. Returns `None` if `control_id` isn't an `auto`-bound session
+/// control of `app` on this page.
+pub(crate) fn auto_strip_index(
+    page: &PageConfig,
+    app: &str,
+    action: &str,
+    control_id: &str,
+) -> Option<u8> {
     let controls = page.controls.as_ref()?;
     let mut auto_strips: Vec<(u8, &str)> = controls
         .iter()
-        .filter(|(_, m)| is_auto_winaudio_action(m, action))
+        .filter(|(_, m)| is_auto_session_action(m, app, action))
         .filter_map(|(id, _)| strip_index_of(id).map(|n| (n, id.as_str())))
         .collect();
     auto_strips.sort_by_key(|(n, _)| *n);
@@ -931,8 +947,8 @@ fn auto_strip_index(page: &PageConfig, action: &str, control_id: &str) -> Option
         .map(|p| p as u8)
 }
 
-fn is_auto_winaudio_action(m: &ControlMapping, action: &str) -> bool {
-    if m.app != DRIVER_NAME {
+fn is_auto_session_action(m: &ControlMapping, app: &str, action: &str) -> bool {
+    if m.app != app {
         return false;
     }
     if m.action.as_deref() != Some(action) {
@@ -958,6 +974,29 @@ async fn render_lcd_if_active(
     config: &Arc<RwLock<WinAudioConfig>>,
     pinned_lc_cache: &Arc<ArcSwap<HashSet<String>>>,
     discovery: &Arc<RwLock<mapping::DiscoveryState>>,
+) {
+    let pinned_apps = config.read().await.pinned_apps.clone();
+    render_session_lcd_if_active(
+        DRIVER_NAME,
+        router,
+        led_tx,
+        &pinned_apps,
+        pinned_lc_cache,
+        discovery,
+    )
+    .await;
+}
+
+/// Driver-agnostic body of [`render_lcd_if_active`]: renders the session
+/// strips of `app` if the active page binds it. Shared with the
+/// PulseAudio driver.
+pub(crate) async fn render_session_lcd_if_active(
+    app: &str,
+    router: &Arc<RwLock<Option<Arc<crate::router::Router>>>>,
+    led_tx: &Arc<RwLock<Option<mpsc::Sender<Vec<u8>>>>>,
+    pinned_apps: &[PinnedApp],
+    pinned_lc_cache: &Arc<ArcSwap<HashSet<String>>>,
+    discovery: &Arc<RwLock<mapping::DiscoveryState>>,
 ) {
     let Some(router_arc) = router.read().await.clone() else {
         return;
@@ -965,17 +1004,17 @@ async fn render_lcd_if_active(
     let Some(page) = router_arc.get_active_page().await else {
         return;
     };
-    if !page_uses_winaudio(&page) {
+    if !page_uses_app(&page, app) {
         return;
     }
     let Some(tx) = led_tx.read().await.clone() else {
-        debug!("WinAudio render: no led_tx wired, skipping LCD render");
+        debug!("{} render: no led_tx wired, skipping LCD render", app);
         return;
     };
-    render_winaudio_lcd(&page, &tx, config, pinned_lc_cache, discovery).await;
+    render_session_lcd(app, &page, &tx, pinned_apps, pinned_lc_cache, discovery).await;
 }
 
-/// Compute and push the 8 LCD strips for the winaudio page. Strips
+/// Compute and push the 8 LCD strips for a session page. Strips
 /// whose process is not currently active render as black + empty.
 /// Pinned apps with an explicit YAML color use it; otherwise the
 /// cycle color from `assigned_color` is used.
@@ -983,14 +1022,14 @@ async fn render_lcd_if_active(
 /// Emits raw SysEx via `led_tx` rather than calling
 /// `apply_lcd_for_page`: keeps the 7-segment display untouched and
 /// avoids needing a non-Sync `Arc<XTouchDriver>` reference.
-async fn render_winaudio_lcd(
+async fn render_session_lcd(
+    app: &str,
     page: &PageConfig,
     led_tx: &mpsc::Sender<Vec<u8>>,
-    config: &Arc<RwLock<WinAudioConfig>>,
+    pinned_apps: &[PinnedApp],
     pinned_lc_cache: &Arc<ArcSwap<HashSet<String>>>,
     discovery: &Arc<RwLock<mapping::DiscoveryState>>,
 ) {
-    let cfg = config.read().await;
     let pinned_lc = pinned_lc_cache.load();
     let disc = discovery.read().await;
 
@@ -999,8 +1038,15 @@ async fn render_winaudio_lcd(
 
     for strip_idx in 1u8..=8 {
         let control_id = format!("fader{strip_idx}");
-        let process_lc =
-            resolve_strip_process(&control_id, page, &cfg, &pinned_lc, &disc, "session_volume");
+        let process_lc = resolve_strip_process(
+            &control_id,
+            page,
+            app,
+            pinned_apps,
+            &pinned_lc,
+            &disc,
+            "session_volume",
+        );
 
         let Some(process_lc) = process_lc else {
             continue;
@@ -1009,54 +1055,54 @@ async fn render_winaudio_lcd(
             continue;
         }
 
-        labels[(strip_idx - 1) as usize] = label_for_process(&cfg.pinned_apps, &process_lc);
-        colors[(strip_idx - 1) as usize] = color_for_process(&cfg.pinned_apps, &disc, &process_lc);
+        labels[(strip_idx - 1) as usize] = label_for_process(pinned_apps, &process_lc);
+        colors[(strip_idx - 1) as usize] = color_for_process(pinned_apps, &disc, &process_lc);
     }
 
     drop(disc);
-    drop(cfg);
 
     for (i, label) in labels.iter().enumerate() {
         let (upper, lower) = build_lcd_strip_sysex(i as u8, label, "");
         if led_tx.send(upper).await.is_err() {
-            debug!("WinAudio LCD: led_tx closed, dropping render");
+            debug!("{} LCD: led_tx closed, dropping render", app);
             return;
         }
         if led_tx.send(lower).await.is_err() {
-            debug!("WinAudio LCD: led_tx closed, dropping render");
+            debug!("{} LCD: led_tx closed, dropping render", app);
             return;
         }
     }
     let color_msg = build_lcd_colors_sysex(&colors);
     if led_tx.send(color_msg).await.is_err() {
-        debug!("WinAudio LCD: led_tx closed, dropping color update");
+        debug!("{} LCD: led_tx closed, dropping color update", app);
     }
 }
 
 /// Resolve `fader{N}` on the active page to a process name, walking
 /// the same path as runtime action dispatch (`pinned`, `discovered`,
-/// `auto`). Returns `None` if the strip isn't bound to a winaudio
-/// session action or no process is currently mapped to it.
+/// `auto`). Returns `None` if the strip isn't bound to a session
+/// action of `app` or no process is currently mapped to it.
 fn resolve_strip_process(
     control_id: &str,
     page: &PageConfig,
-    cfg: &WinAudioConfig,
+    app: &str,
+    pinned_apps: &[PinnedApp],
     pinned_lc: &HashSet<String>,
     disc: &mapping::DiscoveryState,
     action: &str,
 ) -> Option<String> {
     let controls = page.controls.as_ref()?;
     let m = controls.get(control_id)?;
-    if m.app != DRIVER_NAME || m.action.as_deref() != Some(action) {
+    if m.app != app || m.action.as_deref() != Some(action) {
         return None;
     }
     let params = m.params.as_deref().unwrap_or(&[]);
     let target = parse_session_target(params).ok()?;
     match target {
-        SessionTarget::Pinned(fader) => mapping::pinned_target(&cfg.pinned_apps, fader),
+        SessionTarget::Pinned(fader) => mapping::pinned_target(pinned_apps, fader),
         SessionTarget::Discovered(slot) => mapping::discovered_target(pinned_lc, disc, slot),
         SessionTarget::Auto => {
-            let auto_idx = auto_strip_index(page, action, control_id)?;
+            let auto_idx = auto_strip_index(page, app, action, control_id)?;
             mapping::discovered_target(pinned_lc, disc, auto_idx)
         },
     }
@@ -1064,7 +1110,7 @@ fn resolve_strip_process(
 
 /// LCD label for a process: pinned `display_name` if set, otherwise
 /// `derive_label`.
-fn label_for_process(pinned: &[crate::config::PinnedApp], process_lc: &str) -> String {
+fn label_for_process(pinned: &[PinnedApp], process_lc: &str) -> String {
     if let Some(pin) = pinned
         .iter()
         .find(|p| p.process_name.to_lowercase() == process_lc)
@@ -1081,11 +1127,7 @@ fn label_for_process(pinned: &[crate::config::PinnedApp], process_lc: &str) -> S
 /// cycle color from `assigned_color`. Falls back to white (7) if a
 /// process somehow has no assigned color (defensive — should not
 /// happen with normal lifecycle).
-fn color_for_process(
-    pinned: &[crate::config::PinnedApp],
-    disc: &mapping::DiscoveryState,
-    process_lc: &str,
-) -> u8 {
+fn color_for_process(pinned: &[PinnedApp], disc: &mapping::DiscoveryState, process_lc: &str) -> u8 {
     if let Some(pin) = pinned
         .iter()
         .find(|p| p.process_name.to_lowercase() == process_lc)
@@ -1112,6 +1154,15 @@ mod auto_strip_tests {
             overlay: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         }
     }
 
@@ -1143,11 +1194,29 @@ mod auto_strip_tests {
             ..Default::default()
         };
 
-        assert_eq!(auto_strip_index(&page, "session_volume", "fader4"), Some(0));
-        assert_eq!(auto_strip_index(&page, "session_volume", "fader5"), Some(1));
-        assert_eq!(auto_strip_index(&page, "session_volume", "fader6"), Some(2));
-        assert_eq!(auto_strip_index(&page, "session_volume", "fader7"), Some(3));
-        assert_eq!(auto_strip_index(&page, "session_volume", "fader1"), None);
-        assert_eq!(auto_strip_index(&page, "session_mute", "mute4"), Some(0));
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_volume", "fader4"),
+            Some(0)
+        );
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_volume", "fader5"),
+            Some(1)
+        );
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_volume", "fader6"),
+            Some(2)
+        );
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_volume", "fader7"),
+            Some(3)
+        );
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_volume", "fader1"),
+            None
+        );
+        assert_eq!(
+            auto_strip_index(&page, DRIVER_NAME, "session_mute", "mute4"),
+            Some(0)
+        );
     }
 }
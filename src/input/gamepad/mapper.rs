@@ -124,6 +124,7 @@ impl GamepadMapper {
 
         // Convert pressed state to numeric value (1.0 = pressed, 0.0 = released)
         let value = if pressed { 1.0 } else { 0.0 };
+        router.mark_input().await;
 
         // Best-effort: emit live HwEvent for editor WS subscribers.
         router
@@ -229,6 +230,7 @@ impl GamepadMapper {
                 "Axis event: {} = {:.3} (raw: {:.3})",
                 control_id, final_value, raw_value
             );
+            router.mark_input().await;
 
             // Best-effort: emit live HwEvent for editor WS subscribers.
             router
@@ -129,6 +129,9 @@ async fn main() -> Result<()> {
     // when launched from the Start Menu shortcut).
     let profile_root = app_paths.base_dir().join("profiles");
     let watched_path = std::path::PathBuf::from(&config_path);
+    // The `process` allowlist sits next to the watched config, outside the
+    // profile root the editor writes to.
+    let process_allowlist = watched_path.with_file_name(crate::config::ProcessConfig::FILE_NAME);
     let profile_store = Arc::new(crate::config::profiles::ProfileStore::new(
         profile_root,
         watched_path,
@@ -154,6 +157,12 @@ async fn main() -> Result<()> {
     let (config_watcher, initial_config) = ConfigWatcher::new(config_path.clone()).await?;
     debug!("Configuration loaded successfully with hot-reload enabled");
 
+    // Control layout of the configured surface
+    let device = initial_config.device();
+    info!("Control surface: {:?}", device);
+    control_mapping::select_device(device);
+    warm_default_mappings()?;
+
     // Ensure sled subdirectory exists
     let sled_path = app_paths.sled_db_path();
     if let Some(parent) = sled_path.parent() {
@@ -211,6 +220,7 @@ async fn main() -> Result<()> {
         tray_update_tx,
         profile_store,
         live_tx,
+        process_allowlist,
     )
     .await?;
 
@@ -319,6 +319,41 @@ impl MidiMessage {
             _ => None,
         }
     }
+
+    /// Same message carrying a new normalized value (0.0-1.0), the inverse
+    /// of [`normalized_value`](Self::normalized_value). Note Off becomes a
+    /// Note On (a non-zero velocity can't be expressed as Note Off).
+    /// Returns None for messages without continuous values
+    pub fn with_normalized_value(&self, normalized: f64) -> Option<Self> {
+        let value7 = convert::denormalize_to_7bit(normalized);
+        Some(match *self {
+            MidiMessage::PitchBend { channel, .. } => MidiMessage::PitchBend {
+                channel,
+                value: convert::denormalize_to_14bit(normalized),
+            },
+            MidiMessage::ControlChange { channel, cc, .. } => MidiMessage::ControlChange {
+                channel,
+                cc,
+                value: value7,
+            },
+            MidiMessage::NoteOn { channel, note, .. }
+            | MidiMessage::NoteOff { channel, note, .. } => MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity: value7,
+            },
+            MidiMessage::ChannelPressure { channel, .. } => MidiMessage::ChannelPressure {
+                channel,
+                pressure: value7,
+            },
+            MidiMessage::PolyPressure { channel, note, .. } => MidiMessage::PolyPressure {
+                channel,
+                note,
+                pressure: value7,
+            },
+            _ => return None,
+        })
+    }
 }
 
 impl fmt::Display for MidiMessage {
@@ -675,4 +710,36 @@ mod tests {
         let sysex = MidiMessage::SysEx { data: vec![0x7E] };
         assert!(sysex.normalized_value().is_none());
     }
+
+    #[test]
+    fn test_with_normalized_value() {
+        let pb = MidiMessage::PitchBend {
+            channel: 2,
+            value: 0,
+        };
+        assert_eq!(
+            pb.with_normalized_value(1.0),
+            Some(MidiMessage::PitchBend {
+                channel: 2,
+                value: 16383
+            })
+        );
+
+        let off = MidiMessage::NoteOff {
+            channel: 0,
+            note: 16,
+            velocity: 0,
+        };
+        assert_eq!(
+            off.with_normalized_value(1.0),
+            Some(MidiMessage::NoteOn {
+                channel: 0,
+                note: 16,
+                velocity: 127
+            })
+        );
+
+        let sysex = MidiMessage::SysEx { data: vec![0x7E] };
+        assert!(sysex.with_normalized_value(0.5).is_none());
+    }
 }
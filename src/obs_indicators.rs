@@ -13,7 +13,7 @@ use crate::api;
 use crate::config::CameraControlConfig;
 use crate::control_mapping::{ControlMappingDB, MidiSpec};
 use crate::drivers::IndicatorCallback;
-use crate::router::Router;
+use crate::router::{LedState, Router};
 
 /// Build the OBS indicator callback closure.
 ///
@@ -82,11 +82,20 @@ async fn handle_indicator_signal(
     signal: &str,
     value: &serde_json::Value,
 ) {
-    // Evaluate which controls should be lit
-    let lit_controls = router.evaluate_indicators(signal, value).await;
+    // Keep the latest value for `when:` conditions, then evaluate which
+    // controls should be lit or blink
+    router.record_signal(signal, value).await;
+    let led_states = router.evaluate_indicators(signal, value).await;
 
     // Send LED updates to channel for each control
-    send_led_updates(&lit_controls, control_db, is_mcu_mode, led_tx);
+    send_led_updates(&led_states, control_db, is_mcu_mode, led_tx);
+
+    // Re-send the LCD strips whose label or color reads this signal
+    for msg in router.lcd_updates_for_signal(signal).await {
+        if let Err(e) = led_tx.try_send(msg) {
+            warn!("Failed to send LCD update to channel: {}", e);
+        }
+    }
 
     // Handle program scene change broadcasts
     handle_program_scene_change(signal, value, camera_control, api_state);
@@ -95,17 +104,16 @@ async fn handle_indicator_signal(
     handle_preview_scene_change(signal, value, camera_control, gamepad_config, api_state);
 }
 
-/// Send LED on/off messages for evaluated indicator controls.
+/// Send LED off/on/blink messages for evaluated indicator controls.
 fn send_led_updates(
-    lit_controls: &HashMap<String, bool>,
+    led_states: &HashMap<String, LedState>,
     control_db: &ControlMappingDB,
     is_mcu_mode: bool,
     led_tx: &mpsc::Sender<Vec<u8>>,
 ) {
-    for (control_id, should_be_lit) in lit_controls.iter() {
+    for (control_id, state) in led_states.iter() {
         if let Some(MidiSpec::Note { note }) = control_db.get_midi_spec(control_id, is_mcu_mode) {
-            let velocity = if *should_be_lit { 127 } else { 0 };
-            let midi_msg = vec![0x90, note, velocity]; // Note On, channel 1
+            let midi_msg = vec![0x90, note, state.velocity()]; // Note On, channel 1
 
             if let Err(e) = led_tx.try_send(midi_msg) {
                 warn!("Failed to send LED update to channel: {}", e);
@@ -0,0 +1,257 @@
+//! Fader banking: strips over more than eight channels
+//!
+//! A page with `bank:` keeps a channel offset, moved by the BANK (a full
+//! bank: the device's strip count) and CHANNEL (1) buttons. The router
+//! resolves the active page through its bank ([`banked_page`]): strip
+//! controls address channel `N + offset` and the LCD takes the matching
+//! labels and colors, so dispatch, feedback, page refresh (state replay)
+//! and LCD rendering all follow the current bank. Offsets are kept per page
+//! name, across page flips and reloads.
+
+use super::lcd::LCD_STRIPS;
+use crate::config::{
+    ActionStep, ControlMapping, MidiSpec, MidiType, PageConfig, SESSION_DRIVER_NAMES,
+    WINAUDIO_SESSION_ACTIONS,
+};
+use crate::control_mapping::{load_default_mappings, MidiSpec as HwSpec};
+use crate::drivers::winaudio::{auto_strip_index, parse_session_target, SessionTarget};
+use serde_json::Value;
+use std::borrow::Cow;
+use tracing::info;
+
+/// Strip controls: the prefix is followed by the strip number (`fader3`,
+/// `fader3_touch`, `vpot3_push`).
+const STRIP_PREFIXES: [&str; 6] = ["fader", "vpot", "mute", "solo", "rec", "select"];
+
+/// Strip number (1-8) of a strip control id.
+fn strip_of(control_id: &str) -> Option<u8> {
+    let rest = STRIP_PREFIXES
+        .iter()
+        .find_map(|prefix| control_id.strip_prefix(prefix))?;
+    let number = rest.split('_').next()?;
+    number
+        .parse()
+        .ok()
+        .filter(|n| (1..=LCD_STRIPS as u8).contains(n))
+}
+
+/// `spec` moved `offset` channels; `None` past the last address.
+fn shift_spec(spec: &MidiSpec, offset: u8) -> Option<MidiSpec> {
+    let shift = |number: Option<u8>, max: u8| match number {
+        Some(n) => n.checked_add(offset).filter(|n| *n <= max).map(Some),
+        None => Some(None),
+    };
+    let mut spec = spec.clone();
+    match spec.midi_type {
+        MidiType::Cc => spec.cc = shift(spec.cc, 127)?,
+        MidiType::Note => spec.note = shift(spec.note, 127)?,
+        // 1-based channel, like the config's CC / note channels
+        MidiType::Pb => spec.channel = shift(spec.channel, 16)?,
+        MidiType::Passthrough => {},
+    }
+    Some(spec)
+}
+
+/// Move the `discovered:` session slot in `params` by `offset`. An `auto`
+/// target becomes the discovered slot it stands for, `auto_slot`.
+fn shift_session(
+    app: &str,
+    action: Option<&str>,
+    params: &mut [Value],
+    auto_slot: Option<u8>,
+    offset: u8,
+) {
+    let is_session = SESSION_DRIVER_NAMES.contains(&app)
+        && action.is_some_and(|action| WINAUDIO_SESSION_ACTIONS.contains(&action));
+    if !is_session {
+        return;
+    }
+    let slot = match parse_session_target(params) {
+        Ok(SessionTarget::Discovered(slot)) => Some(slot),
+        Ok(SessionTarget::Auto) => auto_slot,
+        _ => None,
+    };
+    if let Some(slot) = slot.and_then(|slot| slot.checked_add(offset)) {
+        params[0] = Value::String(format!("discovered:{}", slot));
+    }
+}
+
+fn shift_step(step: &mut ActionStep, offset: u8) -> Option<()> {
+    if let Some(spec) = &step.midi {
+        step.midi = Some(shift_spec(spec, offset)?);
+    }
+    if let Some(params) = &mut step.params {
+        shift_session(&step.app, step.action.as_deref(), params, None, offset);
+    }
+    step.else_steps
+        .iter_mut()
+        .try_for_each(|step| shift_step(step, offset))
+}
+
+fn shift_mapping(
+    mapping: &ControlMapping,
+    auto_slot: Option<u8>,
+    offset: u8,
+) -> Option<ControlMapping> {
+    let mut mapping = mapping.clone();
+    if let Some(spec) = &mapping.midi {
+        mapping.midi = Some(shift_spec(spec, offset)?);
+    }
+    if let Some(params) = &mut mapping.params {
+        shift_session(
+            &mapping.app,
+            mapping.action.as_deref(),
+            params,
+            auto_slot,
+            offset,
+        );
+    }
+    mapping
+        .also
+        .iter_mut()
+        .flatten()
+        .chain(&mut mapping.else_steps)
+        .chain(&mut mapping.on_touch)
+        .chain(&mut mapping.on_release)
+        .try_for_each(|step| shift_step(step, offset))?;
+    Some(mapping)
+}
+
+/// `page` with its strips moved `offset` channels. A strip control whose
+/// address would go past the last one is dropped.
+pub(crate) fn banked_page(page: &PageConfig, offset: u8) -> PageConfig {
+    let mut banked = page.clone();
+    banked.controls = page.controls.as_ref().map(|controls| {
+        controls
+            .iter()
+            .filter_map(|(id, mapping)| {
+                if strip_of(id).is_none() {
+                    return Some((id.clone(), mapping.clone()));
+                }
+                let auto_slot = mapping
+                    .action
+                    .as_deref()
+                    .and_then(|action| auto_strip_index(page, &mapping.app, action, id));
+                shift_mapping(mapping, auto_slot, offset).map(|mapping| (id.clone(), mapping))
+            })
+            .collect()
+    });
+    if let Some(lcd) = &mut banked.lcd {
+        let skip = usize::from(offset);
+        if let Some(labels) = &mut lcd.labels {
+            labels.drain(..skip.min(labels.len()));
+        }
+        if let Some(colors) = &mut lcd.colors {
+            colors.drain(..skip.min(colors.len()));
+        }
+    }
+    banked
+}
+
+impl super::Router {
+    /// Bank offset of the page named `page` (0 until it is banked).
+    pub(crate) async fn bank_offset(&self, page: &str) -> u8 {
+        self.bank_offsets
+            .read()
+            .await
+            .get(page)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// `page` as seen through its current bank.
+    pub(crate) async fn bank_view<'a>(&self, page: &'a PageConfig) -> Cow<'a, PageConfig> {
+        if page.bank.is_none() {
+            return Cow::Borrowed(page);
+        }
+        match self.bank_offset(&page.name).await {
+            0 => Cow::Borrowed(page),
+            offset => Cow::Owned(banked_page(page, offset)),
+        }
+    }
+
+    /// Handle BANK / CHANNEL buttons on a banked page. Returns whether
+    /// `raw` was consumed: BANK at either end falls through (page
+    /// navigation by default), CHANNEL always stays with the bank.
+    pub(super) async fn bank_input(&self, raw: &[u8], is_mcu_mode: bool) -> bool {
+        let Ok(db) = load_default_mappings() else {
+            return false;
+        };
+        let Some(control_id) = HwSpec::from_raw(raw)
+            .ok()
+            .and_then(|spec| db.find_control_by_midi(&spec, is_mcu_mode))
+        else {
+            return false;
+        };
+        let strips = self.config.read().await.device().strips();
+        let delta: i16 = match control_id {
+            "bank_left" => -i16::from(strips),
+            "bank_right" => i16::from(strips),
+            "channel_left" => -1,
+            "channel_right" => 1,
+            _ => return false,
+        };
+        let Some(page) = self.get_active_page().await else {
+            return false;
+        };
+        let Some(bank) = &page.bank else {
+            return false;
+        };
+        let stays = control_id.starts_with("channel");
+        if raw.get(2).is_none_or(|velocity| *velocity == 0) {
+            return stays;
+        }
+
+        let max = i16::from(bank.channels.saturating_sub(strips));
+        let shifted = {
+            let mut offsets = self.bank_offsets.write().await;
+            let offset = offsets.entry(page.name.clone()).or_default();
+            let new = (i16::from(*offset) + delta).clamp(0, max) as u8;
+            let changed = new != *offset;
+            *offset = new;
+            changed.then_some(new)
+        };
+        let Some(offset) = shifted else {
+            return stays;
+        };
+
+        info!(
+            "Page '{}': channels {}-{}",
+            page.name,
+            offset + 1,
+            offset + strips
+        );
+        self.refresh_page().await;
+        self.emit_page_changed().await;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_controls_are_recognized() {
+        assert_eq!(strip_of("fader3"), Some(3));
+        assert_eq!(strip_of("fader3_touch"), Some(3));
+        assert_eq!(strip_of("vpot8_push"), Some(8));
+        assert_eq!(strip_of("select1"), Some(1));
+        assert_eq!(strip_of("fader_master"), None);
+        assert_eq!(strip_of("f1"), None);
+        assert_eq!(strip_of("rewind"), None);
+    }
+
+    #[test]
+    fn specs_shift_within_range() {
+        let spec = |yaml: &str| serde_yaml::from_str::<MidiSpec>(yaml).unwrap();
+        let cc = shift_spec(&spec("{ type: cc, channel: 1, cc: 30 }"), 8).unwrap();
+        assert_eq!((cc.channel, cc.cc), (Some(1), Some(38)));
+        let pb = shift_spec(&spec("{ type: pb, channel: 2 }"), 8).unwrap();
+        assert_eq!(pb.channel, Some(10));
+        let pb = shift_spec(&spec("{ type: pb, channel: 7 }"), 9).unwrap();
+        assert_eq!(pb.channel, Some(16));
+        assert!(shift_spec(&spec("{ type: pb, channel: 8 }"), 9).is_none());
+        assert!(shift_spec(&spec("{ type: note, channel: 1, note: 120 }"), 8).is_none());
+    }
+}
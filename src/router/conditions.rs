@@ -0,0 +1,170 @@
+//! Conditional dispatch (`when:` / `else` on controls and steps)
+//!
+//! Conditions reuse the indicator semantics (`signal` + `equals`/`truthy`/`in`)
+//! and are evaluated against the last value each driver emitted for the
+//! signal. A button release follows the branch taken by its press: the signal
+//! values seen on press are latched per control until the release, so a press
+//! that flips the signal (e.g. toggling studio mode) can't strand a note-on.
+//!
+//! The same conditions back macro `wait_for` steps, woken on every signal
+//! update.
+
+use crate::config::{ActionStep, ControlMapping, IndicatorConfig};
+use crate::event_bus::HwEventKind;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+
+/// Latest value per signal name (e.g. `"obs.studioMode" -> true`).
+pub(crate) type SignalValues = HashMap<String, Value>;
+
+impl super::Router {
+    /// Remember the latest value emitted for a driver signal.
+    ///
+    /// Called for every indicator emission, before LEDs are evaluated.
+    pub async fn record_signal(&self, signal: &str, value: &Value) {
+        self.signal_values
+            .write()
+            .await
+            .insert(signal.to_string(), value.clone());
+        self.signal_changed.notify_waiters();
+    }
+
+    /// Wait until `when` holds on the signal store, at most `timeout`.
+    /// Returns `false` on timeout.
+    pub async fn wait_for_condition(
+        &self,
+        when: &IndicatorConfig,
+        timeout: Option<Duration>,
+    ) -> bool {
+        let wait = async {
+            loop {
+                // Register before checking so an update in between isn't missed.
+                let changed = self.signal_changed.notified();
+                tokio::pin!(changed);
+                changed.as_mut().enable();
+                if self.condition_holds(when, &*self.signal_values.read().await) {
+                    return;
+                }
+                changed.await;
+            }
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.is_ok(),
+            None => {
+                wait.await;
+                true
+            },
+        }
+    }
+
+    /// Signal values to evaluate `mapping`'s conditions against.
+    ///
+    /// Press: current values, latched for the matching release. Release: the
+    /// latched values (current ones if the press wasn't seen). Anything else
+    /// (faders, encoders): current values.
+    pub(super) async fn condition_signals(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        kind: Option<HwEventKind>,
+    ) -> SignalValues {
+        if !has_conditions(mapping) {
+            return SignalValues::new();
+        }
+        if matches!(kind, Some(HwEventKind::Release)) {
+            if let Some(latched) = self.condition_latch.write().await.remove(control_id) {
+                return latched;
+            }
+        }
+        let current = self.signal_values.read().await.clone();
+        if matches!(kind, Some(HwEventKind::Press)) {
+            self.condition_latch
+                .write()
+                .await
+                .insert(control_id.to_string(), current.clone());
+        }
+        current
+    }
+
+    /// Steps a control dispatches: primary + `also` when the control's `when`
+    /// holds (or is absent), its `else` steps otherwise. Step-level
+    /// conditions are resolved too.
+    pub(super) fn select_control_steps(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        signals: &SignalValues,
+    ) -> Vec<ActionStep> {
+        if let Some(when) = &mapping.when {
+            if !self.condition_holds(when, signals) {
+                debug!(
+                    "Control '{}': condition is false, running `else`",
+                    control_id
+                );
+                return mapping
+                    .else_steps
+                    .iter()
+                    .flat_map(|step| self.select_steps(step, signals))
+                    .collect();
+            }
+        }
+
+        let primary = mapping.primary_step();
+        let mut steps = self.select_steps(&primary, signals);
+        if let Some(also) = &mapping.also {
+            steps.extend(
+                also.iter()
+                    .flat_map(|step| self.select_steps(step, signals)),
+            );
+        }
+        steps
+    }
+
+    /// Resolve a step's `when`: the step itself, or its `else` steps
+    /// (recursively) when the condition is false.
+    pub(super) fn select_steps(
+        &self,
+        step: &ActionStep,
+        signals: &SignalValues,
+    ) -> Vec<ActionStep> {
+        match &step.when {
+            Some(when) if !self.condition_holds(when, signals) => step
+                .else_steps
+                .iter()
+                .flat_map(|step| self.select_steps(step, signals))
+                .collect(),
+            _ => vec![step.clone()],
+        }
+    }
+
+    /// Evaluate a condition like an indicator: its signal's value tests and
+    /// its `all`/`any`/`not` sub-conditions must all hold. A signal never
+    /// emitted counts as `null` (falsy).
+    pub(super) fn condition_holds(&self, when: &IndicatorConfig, signals: &SignalValues) -> bool {
+        if !when.signal.is_empty() {
+            let value = signals.get(&when.signal).unwrap_or(&Value::Null);
+            if !self.evaluate_indicator_condition(when, value) {
+                return false;
+            }
+        } else if !when.is_compound() {
+            return false;
+        }
+        when.all.iter().all(|c| self.condition_holds(c, signals))
+            && (when.any.is_empty() || when.any.iter().any(|c| self.condition_holds(c, signals)))
+            && !when
+                .not
+                .as_deref()
+                .is_some_and(|c| self.condition_holds(c, signals))
+    }
+}
+
+/// Whether a control carries any `when:` (control-level or on a step).
+fn has_conditions(mapping: &ControlMapping) -> bool {
+    mapping.when.is_some()
+        || mapping
+            .also
+            .as_ref()
+            .is_some_and(|steps| steps.iter().any(|step| step.when.is_some()))
+}
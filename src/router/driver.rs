@@ -208,7 +208,11 @@ impl super::Router {
         drop(config);
 
         // Resolve $camera placeholders for dynamic gamepad targeting
-        let mut params = self.resolve_camera_params(control_id, raw_params).await?;
+        let params = self.resolve_camera_params(control_id, raw_params).await?;
+        // Then `${...}` placeholders (signals, value, control_id, page)
+        let mut params = self
+            .interpolate_step_params(control_id, params, value.as_ref())
+            .await;
 
         // Append extra parameters if provided (e.g., target="preview")
         if let Some(extra) = extra_params {
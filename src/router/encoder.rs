@@ -0,0 +1,181 @@
+//! Absolute encoders: V-Pots driving an app parameter
+//!
+//! A control with `encoder:` keeps a 0.0-1.0 position per `control_id ->
+//! app` (its feedback source). In MCU mode, relative ticks (1-63 clockwise,
+//! 65-127 counter-clockwise) move it by `step`; in CTRL mode the X-Touch
+//! already sends the absolute value (0-127), which sets it. The control
+//! dispatches the position as an absolute CC (0-127). Feedback from the
+//! source app sets the position. Either way the ring shows it: in the
+//! configured mode in MCU mode, as the control's own CC value in CTRL mode.
+
+use crate::config::{ControlMapping, EncoderConfig, PageConfig};
+use crate::control_mapping::{load_default_mappings, MidiSpec};
+use crate::midi::convert::denormalize_to_7bit;
+use crate::xtouch::build_ring_cc;
+
+/// MCU CC of the first V-Pot rotation (`vpot1_rotate`).
+const MCU_VPOT_CC: u8 = 16;
+
+/// Signed tick count of a relative encoder value (bit 6 = counter-clockwise).
+fn relative_ticks(value: u8) -> i32 {
+    let count = i32::from(value & 0x3F);
+    if value & 0x40 != 0 {
+        -count
+    } else {
+        count
+    }
+}
+
+/// Position after an encoder message carrying `value`.
+fn moved_position(position: f64, value: u8, encoder: &EncoderConfig, is_mcu_mode: bool) -> f64 {
+    if !is_mcu_mode {
+        return f64::from(value) / 127.0;
+    }
+    (position + f64::from(relative_ticks(value)) * encoder.step).clamp(0.0, 1.0)
+}
+
+fn position_key(control_id: &str, mapping: &ControlMapping) -> String {
+    format!("{}->{}", control_id, mapping.feedback_app())
+}
+
+/// Message showing `position` on the ring of `control_id`.
+fn ring_message(
+    control_id: &str,
+    encoder: &EncoderConfig,
+    position: f64,
+    is_mcu_mode: bool,
+) -> Option<Vec<u8>> {
+    let db = load_default_mappings().ok()?;
+    let MidiSpec::ControlChange { cc } = db.get_midi_spec(control_id, is_mcu_mode)? else {
+        return None;
+    };
+    if !is_mcu_mode {
+        return Some(vec![0xB0, cc, denormalize_to_7bit(position)]);
+    }
+    let index = cc.checked_sub(MCU_VPOT_CC).filter(|index| *index < 8)?;
+    Some(build_ring_cc(
+        index,
+        encoder.ring,
+        position,
+        encoder.center_led,
+    ))
+}
+
+impl super::Router {
+    /// Absolute message to dispatch for a tick of encoder control
+    /// `control_id`: the same CC, carrying the new position. Queues the ring
+    /// update. `None` when the control has no `encoder` or `raw` isn't a CC.
+    pub(super) async fn encoder_move(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        raw: &[u8],
+    ) -> Option<Vec<u8>> {
+        let encoder = mapping.encoder.as_ref()?;
+        let [status @ 0xB0..=0xBF, cc, value] = *raw else {
+            return None;
+        };
+
+        let is_mcu_mode = self.config.read().await.is_mcu_mode();
+        let position = {
+            let mut positions = self.encoder_positions.write().await;
+            let position = positions
+                .entry(position_key(control_id, mapping))
+                .or_insert(encoder.initial);
+            *position = moved_position(*position, value, encoder, is_mcu_mode);
+            *position
+        };
+
+        if let Some(ring) = ring_message(control_id, encoder, position, is_mcu_mode) {
+            self.pending_midi_messages.lock().await.push(ring);
+        }
+        Some(vec![status, cc, denormalize_to_7bit(position)])
+    }
+
+    /// Record the feedback `position` of encoder control `control_id`.
+    /// Returns the ring message in MCU mode; `None` otherwise (CTRL mode
+    /// takes the control's native CC, or the control has no `encoder`).
+    pub(super) async fn encoder_feedback(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        position: f64,
+        is_mcu_mode: bool,
+    ) -> Option<Vec<u8>> {
+        let encoder = mapping.encoder.as_ref()?;
+        let position = position.clamp(0.0, 1.0);
+        self.encoder_positions
+            .write()
+            .await
+            .insert(position_key(control_id, mapping), position);
+        if !is_mcu_mode {
+            return None;
+        }
+        ring_message(control_id, encoder, position, true)
+    }
+
+    /// Ring messages for every encoder control of `page` (page controls
+    /// override global ones), for a page refresh.
+    pub(super) async fn encoder_rings(&self, page: &PageConfig) -> Vec<Vec<u8>> {
+        let config = self.config.read().await;
+        let is_mcu_mode = config.is_mcu_mode();
+        let global = config
+            .pages_global
+            .as_ref()
+            .and_then(|g| g.controls.as_ref());
+        let page_controls = page.controls.as_ref();
+        let positions = self.encoder_positions.read().await;
+
+        let controls = page_controls.into_iter().flatten().chain(
+            global
+                .into_iter()
+                .flatten()
+                .filter(|(id, _)| page_controls.is_none_or(|c| !c.contains_key(*id))),
+        );
+        controls
+            .filter_map(|(id, mapping)| {
+                let encoder = mapping.encoder.as_ref()?;
+                let position = positions
+                    .get(&position_key(id, mapping))
+                    .copied()
+                    .unwrap_or(encoder.initial);
+                ring_message(id, encoder, position, is_mcu_mode)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_ticks_decode_direction() {
+        assert_eq!(relative_ticks(1), 1);
+        assert_eq!(relative_ticks(5), 5);
+        assert_eq!(relative_ticks(65), -1);
+        assert_eq!(relative_ticks(69), -5);
+        assert_eq!(relative_ticks(0), 0);
+    }
+
+    fn encoder() -> EncoderConfig {
+        serde_yaml::from_str("step: 0.1\n").unwrap()
+    }
+
+    #[test]
+    fn mcu_ticks_move_the_position() {
+        let encoder = encoder();
+        assert!((moved_position(0.5, 2, &encoder, true) - 0.7).abs() < 1e-9);
+        assert!((moved_position(0.5, 65, &encoder, true) - 0.4).abs() < 1e-9);
+        assert_eq!(moved_position(0.95, 3, &encoder, true), 1.0);
+    }
+
+    #[test]
+    fn ctrl_values_are_absolute() {
+        let encoder = encoder();
+        assert_eq!(moved_position(0.5, 127, &encoder, false), 1.0);
+        assert_eq!(moved_position(0.5, 0, &encoder, false), 0.0);
+        // 65 is a position in CTRL mode, not a counter-clockwise tick
+        assert!((moved_position(0.9, 65, &encoder, false) - 65.0 / 127.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,100 @@
+//! Feedback fan-out: one continuous control driving several apps
+//!
+//! A control's targets are its primary effect plus its `also` steps. Only the
+//! `feedback_from` target (default: `app`) moves the surface; feedback from
+//! the other targets is dropped in `feedback.rs`.
+//!
+//! With `link: true`, a move shifts every target by the control's delta from
+//! that target's own last position (its feedback, or the last value sent)
+//! instead of sending them all the absolute position, so relative offsets
+//! between targets survive. Positions are kept unclamped within one travel
+//! past each end, so an offset squeezed at an end comes back on the way out.
+
+use crate::config::{ActionStep, ControlMapping};
+use crate::midi::MidiMessage;
+use std::collections::HashMap;
+
+/// How far past an end a linked target's position may drift (in travels).
+const LINK_OVERSHOOT: f64 = 1.0;
+
+/// Positions (0.0-1.0 surface scale) of a linked control.
+#[derive(Debug, Default)]
+pub(crate) struct LinkState {
+    /// Last control position (hardware move or feedback source).
+    control: Option<f64>,
+    /// Position per target app.
+    targets: HashMap<String, f64>,
+}
+
+/// Position of a continuous message (PB or CC), at full resolution.
+pub(super) fn position(msg: &MidiMessage) -> Option<f64> {
+    match *msg {
+        MidiMessage::PitchBend { value, .. } => Some(f64::from(value) / 16383.0),
+        MidiMessage::ControlChange { value, .. } => Some(f64::from(value) / 127.0),
+        _ => None,
+    }
+}
+
+impl super::Router {
+    /// Raw message to dispatch each of `steps` with, for a move of linked
+    /// control `control_id`. `None` when the control isn't linked or `raw`
+    /// isn't a continuous move: every step gets `raw`.
+    pub(super) async fn linked_moves(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        steps: &[ActionStep],
+        raw: &[u8],
+    ) -> Option<Vec<Vec<u8>>> {
+        if !mapping.link {
+            return None;
+        }
+        let msg = MidiMessage::parse(raw)?;
+        let moved_to = position(&msg)?;
+
+        let mut links = self.link_states.write().await;
+        let state = links.entry(control_id.to_string()).or_default();
+        let delta = state.control.map(|last| moved_to - last);
+        state.control = Some(moved_to);
+
+        let moves = steps
+            .iter()
+            .map(|step| {
+                let target = match (delta, state.targets.get(&step.app)) {
+                    (Some(delta), Some(&last)) => {
+                        (last + delta).clamp(-LINK_OVERSHOOT, 1.0 + LINK_OVERSHOOT)
+                    },
+                    _ => moved_to,
+                };
+                state.targets.insert(step.app.clone(), target);
+                msg.with_normalized_value(target.clamp(0.0, 1.0))
+                    .map_or_else(|| raw.to_vec(), |m| m.to_bytes())
+            })
+            .collect();
+        Some(moves)
+    }
+
+    /// Record a linked target's position from its feedback. The feedback
+    /// source also sets the control position, since the surface follows it.
+    pub(super) async fn record_link_feedback(
+        &self,
+        control_id: &str,
+        app: &str,
+        position: f64,
+        is_source: bool,
+    ) {
+        let mut links = self.link_states.write().await;
+        let state = links.entry(control_id.to_string()).or_default();
+        // Keep an overshoot the app can't report (it clamps at the ends).
+        let position = match state.targets.get(app) {
+            Some(&last) if (last <= 0.0 && position <= 0.0) || (last >= 1.0 && position >= 1.0) => {
+                last
+            },
+            _ => position,
+        };
+        state.targets.insert(app.to_string(), position);
+        if is_source {
+            state.control = Some(position.clamp(0.0, 1.0));
+        }
+    }
+}
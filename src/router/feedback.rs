@@ -39,6 +39,9 @@ impl super::Router {
                 return None;
             },
         };
+        // Strips address the channels of the current bank
+        let banked_page = self.bank_view(active_page).await;
+        let active_page = &*banked_page;
 
         let apps_on_page = self.get_apps_for_page(active_page, &config_snapshot);
         if !apps_on_page.contains(app_name) {
@@ -55,25 +58,13 @@ impl super::Router {
             app_name, active_page.name
         );
 
-        // CRITICAL: Schedule motor setpoints AFTER page filtering
-        // Only schedule if the app is actually on this page (prevents off-page movements)
-        if let crate::midi::MidiMessage::PitchBend { channel, value } = input_msg {
-            let channel1 = channel + 1; // Convert 0-based to 1-based
-            debug!(
-                "← Scheduling fader setpoint from {}: ch={} value14={}",
-                app_name, channel1, value
-            );
-            self.fader_setpoint.schedule(channel1, value, None);
-            self.emit_fader_live(channel1, value).await;
-        }
-
-        // Helper to check if a mapping matches the incoming message
-        let matches_mapping = |mapping: &crate::config::ControlMapping| -> bool {
-            if mapping.app != app_name {
+        // Helper to check if a control target (`app` + `midi:` spec) matches the incoming message
+        let matches_target = |app: &str, midi_spec: Option<&crate::config::MidiSpec>| -> bool {
+            if app != app_name {
                 return false;
             }
 
-            if let Some(midi_spec) = &mapping.midi {
+            if let Some(midi_spec) = midi_spec {
                 match midi_spec.midi_type {
                     crate::config::MidiType::Cc => {
                         if let (Some(target_ch), Some(target_cc)) =
@@ -113,37 +104,140 @@ impl super::Router {
             false
         };
 
-        // Search in active page controls (use the active_page we already have)
-        let mut found_control_id = None;
-
-        if let Some(controls) = &active_page.controls {
+        // Search in active page controls (use the active_page we already have),
+        // then in global controls. Done before scheduling setpoints: the
+        // mapping's `value:` transform decides where the fader lands.
+        // A control driving several apps only takes feedback from its
+        // `feedback_from` target; the other targets leave the surface alone.
+        // BUG-007 FIX: Use config_snapshot consistently
+        let mut found_control = None;
+        let mut other_target = None;
+        let global_controls = config_snapshot
+            .pages_global
+            .as_ref()
+            .and_then(|g| g.controls.as_ref());
+        'search: for controls in [active_page.controls.as_ref(), global_controls]
+            .into_iter()
+            .flatten()
+        {
             for (id, mapping) in controls {
-                if matches_mapping(mapping) {
-                    found_control_id = Some(id.clone());
-                    break;
+                let source = mapping.feedback_app();
+                for (app, midi_spec, transform) in mapping.targets() {
+                    if !matches_target(app, midi_spec) {
+                        continue;
+                    }
+                    if app == source {
+                        found_control = Some((id.clone(), transform.cloned(), mapping.clone()));
+                        break 'search;
+                    }
+                    other_target.get_or_insert((id.clone(), transform.cloned(), mapping.link));
                 }
             }
         }
 
-        // If not found, search in global controls
-        // BUG-007 FIX: Use config_snapshot consistently
-        if found_control_id.is_none() {
-            if let Some(global) = &config_snapshot.pages_global {
-                if let Some(controls) = &global.controls {
-                    for (id, mapping) in controls {
-                        if matches_mapping(mapping) {
-                            found_control_id = Some(id.clone());
-                            break;
-                        }
-                    }
+        if found_control.is_none() {
+            if let Some((control_id, transform, link)) = other_target {
+                if link {
+                    let position =
+                        transform.map_or(normalized_value, |t| t.reverse(normalized_value));
+                    self.record_link_feedback(&control_id, app_name, position, false)
+                        .await;
                 }
+                trace!(
+                    "App '{}' is not the feedback source of '{}', skipping X-Touch forward",
+                    app_name,
+                    control_id
+                );
+                return None;
             }
         }
 
         // BUG-007 FIX: Use config_snapshot consistently
         let is_mcu_mode = config_snapshot.is_mcu_mode();
 
-        if let Some(control_id) = found_control_id {
+        // Feedback not matched by a `midi:` spec is forwarded as-is: driver
+        // feedback already addresses the surface (e.g. a fader PB). Reverse
+        // the `value:` transform of the control it addresses, if any.
+        let mut input_msg = input_msg;
+        let mut forwarded = raw_data.to_vec();
+        if found_control.is_none() {
+            if let Some((control_id, mapping, transform)) = Self::surface_control_target(
+                app_name,
+                raw_data,
+                active_page,
+                &config_snapshot,
+                is_mcu_mode,
+            ) {
+                let position = transform
+                    .as_ref()
+                    .map_or(normalized_value, |t| t.reverse(normalized_value));
+                let is_source = mapping.feedback_app() == app_name;
+                if mapping.link {
+                    self.record_link_feedback(&control_id, app_name, position, is_source)
+                        .await;
+                }
+                if !is_source {
+                    trace!(
+                        "App '{}' is not the feedback source of '{}', skipping X-Touch forward",
+                        app_name,
+                        control_id
+                    );
+                    return None;
+                }
+                if let Some(ring) = self
+                    .encoder_feedback(&control_id, &mapping, position, is_mcu_mode)
+                    .await
+                {
+                    return Some(ring);
+                }
+                if transform.is_some() {
+                    if let Some(msg) = input_msg.with_normalized_value(position) {
+                        debug!(
+                            "← Feedback value transform: {} ({} -> {})",
+                            app_name, input_msg, msg
+                        );
+                        forwarded = msg.to_bytes();
+                        input_msg = msg;
+                    }
+                }
+            }
+        }
+
+        // CRITICAL: Schedule motor setpoints AFTER page filtering
+        // Only schedule if the app is actually on this page (prevents off-page movements).
+        // A transformed `midi:` mapping schedules its own (reversed) setpoint below.
+        let mapping_transformed = matches!(found_control, Some((_, Some(_), _)));
+        if let crate::midi::MidiMessage::PitchBend { channel, value } = input_msg {
+            if !mapping_transformed {
+                let channel1 = channel + 1; // Convert 0-based to 1-based
+                debug!(
+                    "← Scheduling fader setpoint from {}: ch={} value14={}",
+                    app_name, channel1, value
+                );
+                self.fader_setpoint.schedule(channel1, value, None);
+                self.emit_fader_live(channel1, value).await;
+            }
+        }
+
+        if let Some((control_id, transform, mapping)) = found_control {
+            let normalized_value = match &transform {
+                Some(transform) => transform.reverse(normalized_value),
+                None => normalized_value,
+            };
+            if mapping.link {
+                self.record_link_feedback(&control_id, app_name, normalized_value, true)
+                    .await;
+            }
+            if let Some(ring) = self
+                .encoder_feedback(&control_id, &mapping, normalized_value, is_mcu_mode)
+                .await
+            {
+                debug!(
+                    "← Feedback ring: {} -> {} ({:02X?})",
+                    app_name, control_id, ring
+                );
+                return Some(ring);
+            }
             // Load hardware mapping to find native message
             if let Ok(db) = load_default_mappings() {
                 if let Some(native_spec) = db.get_midi_spec(&control_id, is_mcu_mode) {
@@ -201,8 +295,37 @@ impl super::Router {
             }
         }
 
-        // No mapping found, pass through raw
-        Some(raw_data.to_vec())
+        // No mapping found, pass through (value-transformed for driver feedback).
+        // A fader PB is MCU only: CTRL mode moves the fader by its CC.
+        if !is_mcu_mode && matches!(input_msg, crate::midi::MidiMessage::PitchBend { .. }) {
+            return load_default_mappings().ok()?.mcu_to_ctrl(&forwarded);
+        }
+        Some(forwarded)
+    }
+
+    /// The surface control that a feedback message addresses, when that
+    /// control targets `app_name` as a driver action (no `midi:` spec) on the
+    /// active page: its id, mapping and the target's `value:` transform.
+    fn surface_control_target(
+        app_name: &str,
+        raw_data: &[u8],
+        page: &crate::config::PageConfig,
+        config: &crate::config::AppConfig,
+        is_mcu_mode: bool,
+    ) -> Option<(
+        String,
+        crate::config::ControlMapping,
+        Option<crate::config::ValueTransform>,
+    )> {
+        let spec = MidiSpec::from_raw(raw_data).ok()?;
+        let db = load_default_mappings().ok()?;
+        let control_id = db.find_control_by_midi(&spec, is_mcu_mode)?;
+        let mapping = Self::get_control_config(page, config, control_id)?;
+        let (_, _, transform) = mapping
+            .targets()
+            .find(|(app, midi_spec, _)| *app == app_name && midi_spec.is_none())?;
+        let transform = transform.cloned();
+        Some((control_id.to_string(), mapping, transform))
     }
 
     /// Ingest MIDI feedback from an application
@@ -22,7 +22,7 @@ use crate::state::{MidiStateEntry, MidiStatus};
 /// `dispatch_step` filters button releases and OBS ignores trigger actions on
 /// release (`ExecutionContext::is_button_release`), so the steps must look like
 /// a press. The note byte is irrelevant for driver actions.
-const SYNTHETIC_PRESS: [u8; 3] = [0x90, 0x00, 0x7F];
+pub(super) const SYNTHETIC_PRESS: [u8; 3] = [0x90, 0x00, 0x7F];
 
 impl super::Router {
     /// React to an app feedback `entry` by firing any matching toggle's
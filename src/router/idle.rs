@@ -0,0 +1,165 @@
+//! Screen saver: the surface goes idle without input
+//!
+//! Every X-Touch or gamepad event resets the idle timer. After
+//! `xtouch.idle.timeout_s` the LCD backlights go off (`dim`) and the idle
+//! `text` is shown across the strips, refreshed every poll so `${time}`
+//! ticks (the output cache drops unchanged cells). The next input restores
+//! the page display; the input itself is handled as usual. The state is
+//! published as the [`IDLE_SIGNAL`] signal.
+
+use super::interpolate::interpolate_text;
+use super::lcd::LCD_STRIPS;
+use crate::config::IdleConfig;
+use crate::control_mapping::{load_default_mappings, MidiSpec};
+use crate::xtouch::{build_lcd_colors_sysex, build_lcd_strip_sysex, STRIP_CHARS};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Signal holding `true` while the surface is idle.
+pub const IDLE_SIGNAL: &str = "surface.idle";
+
+/// Idle timer state.
+#[derive(Debug)]
+pub(crate) struct IdleState {
+    last_input: Instant,
+    idle: bool,
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        Self {
+            last_input: Instant::now(),
+            idle: false,
+        }
+    }
+}
+
+/// `text` centered across the strips, cut into their 7-character cells.
+fn spread(text: &str) -> [String; LCD_STRIPS] {
+    let width = LCD_STRIPS * STRIP_CHARS;
+    let text: String = text.chars().take(width).collect();
+    let line: Vec<char> = format!("{:^width$}", text).chars().collect();
+    std::array::from_fn(|strip| {
+        line[strip * STRIP_CHARS..(strip + 1) * STRIP_CHARS]
+            .iter()
+            .collect()
+    })
+}
+
+impl super::Router {
+    /// Reset the idle timer on hardware or gamepad input. Leaving idle
+    /// restores the page display.
+    pub async fn mark_input(&self) {
+        let woke = {
+            let mut state = self.idle.lock().await;
+            state.last_input = Instant::now();
+            std::mem::replace(&mut state.idle, false)
+        };
+        if !woke {
+            return;
+        }
+        info!("Surface active");
+        let leds = self.publish_idle(false).await;
+        self.pending_midi_messages.lock().await.extend(leds);
+        *self.display_needs_update.lock().await = true;
+        self.display_refresh_notify.notify_one();
+    }
+
+    /// Whether the screen saver is on.
+    pub async fn is_idle(&self) -> bool {
+        self.idle.lock().await.idle
+    }
+
+    /// Go idle once the timeout has passed. Returns the messages to send:
+    /// LEDs reading the idle signal on entry, then the screen saver frame
+    /// (on every poll while idle).
+    pub async fn poll_idle(&self) -> Vec<Vec<u8>> {
+        self.poll_idle_at(Instant::now()).await
+    }
+
+    pub(super) async fn poll_idle_at(&self, now: Instant) -> Vec<Vec<u8>> {
+        let config = {
+            let config = self.config.read().await;
+            config.xtouch.as_ref().and_then(|x| x.idle.clone())
+        };
+        let Some(config) = config else {
+            return Vec::new();
+        };
+        let entered = {
+            let mut state = self.idle.lock().await;
+            if !state.idle
+                && now.duration_since(state.last_input) < Duration::from_secs(config.timeout_s)
+            {
+                return Vec::new();
+            }
+            !std::mem::replace(&mut state.idle, true)
+        };
+
+        let mut messages = Vec::new();
+        if entered {
+            info!("Surface idle after {}s without input", config.timeout_s);
+            messages = self.publish_idle(true).await;
+        }
+        messages.extend(self.screen_saver(&config).await);
+        messages
+    }
+
+    /// Record the idle state as a signal; LED updates of the indicators
+    /// reading it.
+    async fn publish_idle(&self, idle: bool) -> Vec<Vec<u8>> {
+        let value = Value::Bool(idle);
+        self.record_signal(IDLE_SIGNAL, &value).await;
+        let is_mcu_mode = self.config.read().await.is_mcu_mode();
+        let Ok(db) = load_default_mappings() else {
+            return Vec::new();
+        };
+        self.evaluate_indicators(IDLE_SIGNAL, &value)
+            .await
+            .into_iter()
+            .filter_map(
+                |(control_id, state)| match db.get_midi_spec(&control_id, is_mcu_mode)? {
+                    MidiSpec::Note { note } => Some(vec![0x90, note, state.velocity()]),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
+    /// LCD messages of the screen saver.
+    async fn screen_saver(&self, config: &IdleConfig) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+        if config.dim {
+            messages.push(build_lcd_colors_sysex(&[0; LCD_STRIPS]));
+        }
+        if let Some(text) = &config.text {
+            let page = self.get_active_page().await.map(|p| p.name);
+            let mut signals = self.signal_values.read().await.clone();
+            let time = chrono::Local::now().format("%H:%M:%S").to_string();
+            signals.insert("time".to_string(), Value::String(time));
+            let text = interpolate_text(text, page.as_deref(), &signals);
+            let mut lines = text.splitn(2, '\n');
+            let upper = spread(lines.next().unwrap_or(""));
+            let lower = spread(lines.next().unwrap_or(""));
+            for (strip, (upper, lower)) in upper.iter().zip(&lower).enumerate() {
+                let (upper, lower) = build_lcd_strip_sysex(strip as u8, upper, lower);
+                messages.extend([upper, lower]);
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_centered_across_strips() {
+        let cells = spread("ON AIR");
+        assert_eq!(cells.concat().len(), LCD_STRIPS * STRIP_CHARS);
+        assert_eq!(cells[3], "    ON ");
+        assert_eq!(cells[4], "AIR    ");
+        assert_eq!(cells[0], "       ");
+    }
+}
@@ -1,19 +1,46 @@
 //! LED indicator evaluation and F-key management
 
+use crate::control_mapping::{load_default_mappings, MidiSpec};
 use anyhow::Result;
 use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use tracing::debug;
 
+/// LED state of an indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedState {
+    Off,
+    On,
+    Blink,
+}
+
+impl LedState {
+    /// Note On velocity for a button LED (velocity 1 blinks, as in MCU).
+    pub fn velocity(self) -> u8 {
+        match self {
+            LedState::Off => 0,
+            LedState::On => 127,
+            LedState::Blink => 1,
+        }
+    }
+}
+
 impl super::Router {
     /// Evaluate indicator conditions for a signal emission
     ///
-    /// Returns a HashMap of control_id -> should_be_lit for all controls
-    /// on the active page that have indicators matching the given signal.
+    /// Returns a HashMap of control_id -> LED state for all controls
+    /// on the active page (and global controls) whose indicator reads the
+    /// given signal. Other signals of compound indicators come from the
+    /// signal store.
     ///
     /// This is called by the indicator subscription handler when drivers
     /// emit signals (e.g., "obs.selectedScene", "obs.studioMode").
-    pub async fn evaluate_indicators(&self, signal: &str, value: &Value) -> HashMap<String, bool> {
+    pub async fn evaluate_indicators(
+        &self,
+        signal: &str,
+        value: &Value,
+    ) -> HashMap<String, LedState> {
         let mut result = HashMap::new();
 
         // Get active page controls
@@ -25,66 +52,75 @@ impl super::Router {
             None => return result,
         };
 
-        let controls = match &page.controls {
-            Some(c) => c,
-            None => return result,
-        };
-
         // Also check global controls
         let global_controls = config
             .pages_global
             .as_ref()
             .and_then(|g| g.controls.as_ref());
 
-        // Iterate through all controls (page + global)
-        // First check page controls
-        for (control_id, mapping) in controls.iter() {
+        // The emitted value wins over the store, in case it isn't recorded yet
+        let store = self.signal_values.read().await;
+        let signals = if store.get(signal) == Some(value) {
+            Cow::Borrowed(&*store)
+        } else {
+            let mut signals = store.clone();
+            signals.insert(signal.to_string(), value.clone());
+            Cow::Owned(signals)
+        };
+
+        // Iterate through all controls: page controls first, then global ones
+        for (control_id, mapping) in page.controls.iter().chain(global_controls).flatten() {
             let indicator = match &mapping.indicator {
                 Some(ind) => ind,
                 None => continue,
             };
 
-            // Check if this indicator matches the signal
-            if indicator.signal != signal {
+            // Check if this indicator reads the signal
+            if !indicator.reads_signal(signal) {
                 continue;
             }
 
-            // Evaluate the condition
-            let should_be_lit = self.evaluate_indicator_condition(indicator, value);
-            result.insert(control_id.clone(), should_be_lit);
+            result.insert(
+                control_id.clone(),
+                self.indicator_state(indicator, &signals),
+            );
         }
 
-        // Then check global controls
-        if let Some(global_ctrls) = global_controls {
-            for (control_id, mapping) in global_ctrls.iter() {
-                let indicator = match &mapping.indicator {
-                    Some(ind) => ind,
-                    None => continue,
-                };
-
-                // Check if this indicator matches the signal
-                if indicator.signal != signal {
-                    continue;
-                }
+        result
+    }
 
-                // Evaluate the condition
-                let should_be_lit = self.evaluate_indicator_condition(indicator, value);
-                result.insert(control_id.clone(), should_be_lit);
-            }
+    /// LED state of an indicator: on while its condition holds, blinking
+    /// while only its `blink` condition does, off otherwise.
+    pub(super) fn indicator_state(
+        &self,
+        indicator: &crate::config::IndicatorConfig,
+        signals: &super::conditions::SignalValues,
+    ) -> LedState {
+        if self.condition_holds(indicator, signals) {
+            LedState::On
+        } else if indicator
+            .blink
+            .as_deref()
+            .is_some_and(|blink| self.condition_holds(blink, signals))
+        {
+            LedState::Blink
+        } else {
+            LedState::Off
         }
-
-        result
     }
 
-    /// Helper to evaluate a single indicator condition
-    fn evaluate_indicator_condition(
+    /// Helper to evaluate the value tests of a single condition
+    ///
+    /// Every test that is set must hold. Also used for `when:` conditions
+    /// (see `conditions.rs`).
+    pub(super) fn evaluate_indicator_condition(
         &self,
         indicator: &crate::config::IndicatorConfig,
         value: &Value,
     ) -> bool {
-        if let Some(truthy) = indicator.truthy {
+        let matched = if let Some(truthy) = indicator.truthy {
             // Truthy check: LED on if value is truthy
-            if truthy {
+            Some(if truthy {
                 match value {
                     Value::Bool(b) => *b,
                     Value::Null => false,
@@ -95,10 +131,10 @@ impl super::Router {
                 }
             } else {
                 false
-            }
+            })
         } else if let Some(in_array) = &indicator.in_array {
             // "in" check: LED on if value matches any in array
-            in_array.iter().any(|v| {
+            Some(in_array.iter().any(|v| {
                 // String comparison: trim and compare
                 if let (Value::String(a), Value::String(b)) = (v, value) {
                     a.trim() == b.trim()
@@ -106,18 +142,38 @@ impl super::Router {
                     // Use serde_json equality (similar to Object.is)
                     *v == *value
                 }
-            })
-        } else if let Some(equals_value) = &indicator.equals {
-            // Equals check: LED on if value matches exactly
-            if let (Value::String(a), Value::String(b)) = (equals_value, value) {
-                a.trim() == b.trim()
-            } else {
-                *equals_value == *value
-            }
+            }))
         } else {
-            // No condition specified, default to off
-            false
-        }
+            // Equals check: LED on if value matches exactly
+            indicator.equals.as_ref().map(|equals_value| {
+                if let (Value::String(a), Value::String(b)) = (equals_value, value) {
+                    a.trim() == b.trim()
+                } else {
+                    *equals_value == *value
+                }
+            })
+        };
+
+        // Numeric comparisons (numbers or numeric strings)
+        let number = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+        let compared = [
+            indicator.gt.map(|gt| number.is_some_and(|n| n > gt)),
+            indicator.lt.map(|lt| number.is_some_and(|n| n < lt)),
+            indicator
+                .between
+                .map(|[min, max]| number.is_some_and(|n| (min..=max).contains(&n))),
+        ];
+
+        // No condition specified, default to off
+        let mut tests = std::iter::once(matched)
+            .chain(compared)
+            .flatten()
+            .peekable();
+        tests.peek().is_some() && tests.all(|held| held)
     }
 
     /// Update F1-F8 LEDs to reflect active page
@@ -163,27 +219,48 @@ impl super::Router {
 
     /// Update prev/next navigation button LEDs (always on)
     ///
-    /// Matches TypeScript updatePrevNextLeds() from xtouch/fkeys.ts
+    /// Matches TypeScript updatePrevNextLeds() from xtouch/fkeys.ts.
+    /// The notes are MCU note numbers, sent as the same buttons in CTRL mode.
     pub async fn update_prev_next_leds(
         &self,
         xtouch: &crate::xtouch::XTouchDriver,
         prev_note: u8,
         next_note: u8,
     ) -> Result<()> {
-        xtouch.set_button_led(prev_note, true).await?;
-        xtouch.set_button_led(next_note, true).await?;
+        let is_mcu_mode = self.config.read().await.is_mcu_mode();
+        for note in [prev_note, next_note] {
+            let note = if is_mcu_mode {
+                Some(note)
+            } else {
+                load_default_mappings()?
+                    .translate_spec(&MidiSpec::Note { note }, false)
+                    .and_then(|spec| match spec {
+                        MidiSpec::Note { note } => Some(note),
+                        _ => None,
+                    })
+            };
+            if let Some(note) = note {
+                xtouch.set_button_led(note, true).await?;
+            }
+        }
         Ok(())
     }
 
     /// Get F-key note numbers based on X-Touch mode
     fn get_fkey_notes(&self, mode: crate::config::XTouchMode) -> Vec<u8> {
-        // From xtouch-matching.csv for MCU mode:
-        // f1 = 54, f2 = 55, f3 = 56, f4 = 57, f5 = 58, f6 = 59, f7 = 60, f8 = 61
-        // These are the default note numbers for F1-F8 in both MCU and Ctrl modes
-        match mode {
-            crate::config::XTouchMode::Mcu | crate::config::XTouchMode::Ctrl => {
-                vec![54, 55, 56, 57, 58, 59, 60, 61]
-            },
-        }
+        // From xtouch-matching.csv: F1-F8 are notes 54-61 in MCU mode,
+        // 58-65 in CTRL mode
+        let is_mcu_mode = mode == crate::config::XTouchMode::Mcu;
+        let Ok(db) = load_default_mappings() else {
+            return Vec::new();
+        };
+        (1..=8)
+            .filter_map(
+                |n| match db.get_midi_spec(&format!("f{}", n), is_mcu_mode)? {
+                    MidiSpec::Note { note } => Some(note),
+                    _ => None,
+                },
+            )
+            .collect()
     }
 }
@@ -0,0 +1,249 @@
+//! `${...}` interpolation in action params
+//!
+//! Resolved at dispatch, after `$camera` placeholders:
+//! - `${value}`: the control's value as sent to the driver (after `value:`),
+//! - `${control_id}`: the triggering control,
+//! - `${page}`: the active page name,
+//! - `${<signal>}`: the last value of any driver signal (e.g.
+//!   `${obs.selectedScene}`), `null` if it was never emitted.
+//!
+//! A param that is exactly one placeholder takes the variable's JSON type
+//! (number, bool, ...); placeholders inside a longer string are formatted as
+//! text. Nested arrays / objects are resolved too.
+
+use super::conditions::SignalValues;
+use serde_json::Value;
+use tracing::trace;
+
+impl super::Router {
+    /// Resolve `${...}` placeholders in a step's params against the signal
+    /// store, the active page and the dispatched value.
+    pub(crate) async fn interpolate_step_params(
+        &self,
+        control_id: &str,
+        params: Vec<Value>,
+        value: Option<&Value>,
+    ) -> Vec<Value> {
+        if !has_placeholders(&params) {
+            return params;
+        }
+        let page = self.get_active_page().await.map(|p| p.name);
+        let signals = self.signal_values.read().await;
+        interpolate_params(
+            params,
+            &InterpolationVars {
+                value,
+                control_id,
+                page: page.as_deref(),
+                signals: &signals,
+            },
+        )
+    }
+}
+
+/// Variables available to `${...}` placeholders for one dispatch.
+struct InterpolationVars<'a> {
+    value: Option<&'a Value>,
+    control_id: &'a str,
+    page: Option<&'a str>,
+    signals: &'a SignalValues,
+}
+
+impl InterpolationVars<'_> {
+    fn lookup(&self, name: &str) -> Value {
+        match name {
+            "value" => self.value.cloned().unwrap_or(Value::Null),
+            "control_id" => Value::String(self.control_id.to_string()),
+            "page" => self
+                .page
+                .map(|p| Value::String(p.to_string()))
+                .unwrap_or(Value::Null),
+            signal => self.signals.get(signal).cloned().unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// `s` with its `${...}` placeholders resolved as text, for LCD labels:
+/// signals and `${page}` (no dispatched value or control).
+pub(super) fn interpolate_text(s: &str, page: Option<&str>, signals: &SignalValues) -> String {
+    let vars = InterpolationVars {
+        value: None,
+        control_id: "",
+        page,
+        signals,
+    };
+    match interpolate_str(s, &vars) {
+        Some(resolved) => to_text(&resolved),
+        None => s.to_string(),
+    }
+}
+
+/// Names of the complete `${...}` placeholders in `s`.
+pub(super) fn placeholder_names(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        let start = rest.find("${")?;
+        let len = rest[start + 2..].find('}')?;
+        let name = rest[start + 2..start + 2 + len].trim();
+        rest = &rest[start + 2 + len + 1..];
+        Some(name)
+    })
+}
+
+/// Whether any param (at any depth) contains a `${` placeholder.
+fn has_placeholders(params: &[Value]) -> bool {
+    params.iter().any(value_has_placeholder)
+}
+
+fn value_has_placeholder(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains("${"),
+        Value::Array(items) => items.iter().any(value_has_placeholder),
+        Value::Object(map) => map.values().any(value_has_placeholder),
+        _ => false,
+    }
+}
+
+/// Resolve every `${...}` placeholder in `params`.
+fn interpolate_params(params: Vec<Value>, vars: &InterpolationVars<'_>) -> Vec<Value> {
+    params
+        .into_iter()
+        .map(|param| interpolate_value(param, vars))
+        .collect()
+}
+
+fn interpolate_value(value: Value, vars: &InterpolationVars<'_>) -> Value {
+    match value {
+        Value::String(s) => interpolate_str(&s, vars).unwrap_or(Value::String(s)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| interpolate_value(item, vars))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, interpolate_value(v, vars)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// `None` when `s` has no complete placeholder (left untouched).
+fn interpolate_str(s: &str, vars: &InterpolationVars<'_>) -> Option<Value> {
+    // Whole-string placeholder: keep the variable's type.
+    if let Some(name) = s.strip_prefix("${").and_then(|r| r.strip_suffix('}')) {
+        if !name.contains(['{', '}']) {
+            let resolved = vars.lookup(name.trim());
+            trace!("Interpolated '{}' -> {}", s, resolved);
+            return Some(resolved);
+        }
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    let mut replaced = false;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + 2 + len];
+        out.push_str(&to_text(&vars.lookup(name.trim())));
+        rest = &rest[start + 2 + len + 1..];
+        replaced = true;
+    }
+    if !replaced {
+        return None;
+    }
+    out.push_str(rest);
+    trace!("Interpolated '{}' -> '{}'", s, out);
+    Some(Value::String(out))
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        // Driver values are f64 (`64.0`): print whole numbers without the
+        // fraction so they read naturally inside names.
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e15 => {
+                format!("{}", f as i64)
+            },
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn resolve(params: Vec<Value>) -> Vec<Value> {
+        let signals = SignalValues::from([
+            ("obs.selectedScene".to_string(), json!("Cam 2")),
+            ("obs.studioMode".to_string(), json!(true)),
+        ]);
+        let value = json!(64.0);
+        let vars = InterpolationVars {
+            value: Some(&value),
+            control_id: "fader1",
+            page: Some("Live"),
+            signals: &signals,
+        };
+        interpolate_params(params, &vars)
+    }
+
+    #[test]
+    fn whole_placeholder_keeps_type() {
+        assert_eq!(
+            resolve(vec![
+                json!("${value}"),
+                json!("${obs.studioMode}"),
+                json!("${obs.selectedScene}"),
+                json!("${never.emitted}"),
+            ]),
+            vec![json!(64.0), json!(true), json!("Cam 2"), Value::Null]
+        );
+    }
+
+    #[test]
+    fn embedded_placeholders_are_formatted() {
+        assert_eq!(
+            resolve(vec![
+                json!("${page}/${control_id}=${value}"),
+                json!("scene: ${ obs.selectedScene }${never.emitted}"),
+                json!({"target": ["${control_id}", 1]}),
+            ]),
+            vec![
+                json!("Live/fader1=64"),
+                json!("scene: Cam 2"),
+                json!({"target": ["fader1", 1]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_without_placeholders_is_untouched() {
+        let params = vec![json!("$camera.scene"), json!("${unclosed"), json!(3)];
+        assert!(has_placeholders(&params));
+        assert_eq!(resolve(params.clone()), params);
+        assert!(!has_placeholders(&[json!("plain"), json!([1, 2])]));
+    }
+
+    #[test]
+    fn text_and_placeholder_names() {
+        let signals = SignalValues::from([("obs.selectedScene".to_string(), json!("Cam 2"))]);
+        assert_eq!(
+            interpolate_text("${obs.selectedScene} @${page}", Some("Live"), &signals),
+            "Cam 2 @Live"
+        );
+        assert_eq!(interpolate_text("Mix", None, &signals), "Mix");
+        let names: Vec<&str> = placeholder_names("${ a.b }-${c}${open").collect();
+        assert_eq!(names, ["a.b", "c"]);
+    }
+}
@@ -0,0 +1,324 @@
+//! Page LCD rendering: labels and colors bound to signals
+//!
+//! Labels resolve `${...}` placeholders (driver signals, `${page}`), and a
+//! color may be a condition (`{ signal, equals, ..., then, else }`). Both
+//! are rendered on page changes, then re-rendered when a signal they read
+//! changes: only the strips reading it are re-sent.
+//!
+//! `merged` areas join adjacent strips into one wider text, cut into the
+//! strips' 7-character cells. With `scroll`, lines longer than their area
+//! move one step per interval (clock restarted on page changes); the main
+//! loop polls and re-sends the lines that moved.
+
+use super::conditions::SignalValues;
+use super::interpolate::{interpolate_text, placeholder_names};
+use crate::config::{LcdConfig, LcdLabel, LcdScrollConfig, LcdScrollMode, PageConfig};
+use crate::xtouch::{build_lcd_colors_sysex, build_lcd_strip_sysex, STRIP_CHARS};
+use std::ops::Range;
+use std::time::Instant;
+
+/// Number of LCD strips.
+pub const LCD_STRIPS: usize = 8;
+
+/// Blank run between the end of a marquee and its restart.
+const MARQUEE_GAP: usize = 3;
+
+/// Scroll clock of the active page's LCD.
+#[derive(Debug)]
+pub(crate) struct ScrollState {
+    page: String,
+    start: Instant,
+    /// Step of the last frame sent.
+    sent: u64,
+}
+
+impl Default for ScrollState {
+    fn default() -> Self {
+        Self {
+            page: String::new(),
+            start: Instant::now(),
+            sent: 0,
+        }
+    }
+}
+
+/// Rendered text (upper, lower) and color of every LCD strip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LcdFrame {
+    pub labels: [(String, String); LCD_STRIPS],
+    pub colors: [u8; LCD_STRIPS],
+}
+
+/// Label lines as configured, before interpolation.
+fn label_lines(label: &LcdLabel) -> (&str, &str) {
+    match label {
+        LcdLabel::Simple(text) => {
+            // Split on newline
+            let mut parts = text.splitn(2, '\n');
+            (parts.next().unwrap_or(""), parts.next().unwrap_or(""))
+        },
+        LcdLabel::Structured { upper, lower } => (
+            upper.as_deref().unwrap_or(""),
+            lower.as_deref().unwrap_or(""),
+        ),
+    }
+}
+
+fn lines_read_signal(upper: &str, lower: &str, signal: &str) -> bool {
+    placeholder_names(upper)
+        .chain(placeholder_names(lower))
+        .any(|name| name == signal)
+}
+
+fn label_reads_signal(label: &LcdLabel, signal: &str) -> bool {
+    let (upper, lower) = label_lines(label);
+    lines_read_signal(upper, lower, signal)
+}
+
+/// Merged areas as 0-based strip ranges, with their lines.
+fn merged_areas(lcd: &LcdConfig) -> impl Iterator<Item = (Range<usize>, &str, &str)> + '_ {
+    lcd.merged.iter().flatten().filter_map(|area| {
+        let strips = usize::from(area.from.checked_sub(1)?)..usize::from(area.to);
+        (!strips.is_empty() && strips.end <= LCD_STRIPS).then(|| {
+            (
+                strips,
+                area.upper.as_deref().unwrap_or(""),
+                area.lower.as_deref().unwrap_or(""),
+            )
+        })
+    })
+}
+
+/// The `width` characters of `text` shown at `step`; shorter text is kept.
+fn scroll_window(text: &str, width: usize, mode: LcdScrollMode, step: u64) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= width {
+        return text.to_string();
+    }
+    match mode {
+        LcdScrollMode::Marquee => {
+            let len = chars.len() + MARQUEE_GAP;
+            let offset = (step % len as u64) as usize;
+            chars
+                .iter()
+                .copied()
+                .chain(std::iter::repeat_n(' ', MARQUEE_GAP))
+                .cycle()
+                .skip(offset)
+                .take(width)
+                .collect()
+        },
+        LcdScrollMode::Segments => {
+            let count = chars.len().div_ceil(width);
+            let segment = (step % count as u64) as usize;
+            chars.iter().skip(segment * width).take(width).collect()
+        },
+    }
+}
+
+/// `line` in an area `width` characters wide: windowed when scrolling.
+fn fit_line(line: String, width: usize, scroll: Option<&LcdScrollConfig>, step: u64) -> String {
+    match scroll {
+        Some(scroll) => scroll_window(&line, width, scroll.mode, step),
+        None => line,
+    }
+}
+
+/// `line` cut into `count` strip cells.
+fn split_cells(line: &str, count: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    (0..count)
+        .map(|cell| {
+            chars
+                .iter()
+                .skip(cell * STRIP_CHARS)
+                .take(STRIP_CHARS)
+                .collect()
+        })
+        .collect()
+}
+
+impl super::Router {
+    /// Render the LCD strips of `page` against the current signal values.
+    pub async fn render_lcd(&self, page: &PageConfig) -> LcdFrame {
+        let step = self.scroll_step(page, Instant::now()).await;
+        self.render_lcd_step(page, step).await
+    }
+
+    async fn render_lcd_step(&self, page: &PageConfig, step: u64) -> LcdFrame {
+        let signals = self.signal_values.read().await;
+        let mut frame = LcdFrame::default();
+        let Some(lcd) = &page.lcd else {
+            return frame;
+        };
+        let scroll = lcd.scroll.as_ref();
+        for strip in 0..LCD_STRIPS {
+            let (upper, lower) = self.render_label(lcd, strip, &page.name, &signals);
+            frame.labels[strip] = (
+                fit_line(upper, STRIP_CHARS, scroll, step),
+                fit_line(lower, STRIP_CHARS, scroll, step),
+            );
+        }
+        for (strips, upper, lower) in merged_areas(lcd) {
+            let width = strips.len() * STRIP_CHARS;
+            let [upper, lower] = [upper, lower].map(|line| {
+                let line = interpolate_text(line, Some(&page.name), &signals);
+                split_cells(&fit_line(line, width, scroll, step), strips.len())
+            });
+            for ((strip, upper), lower) in strips.zip(upper).zip(lower) {
+                frame.labels[strip] = (upper, lower);
+            }
+        }
+        frame.colors = self.render_colors(lcd, &signals);
+        frame
+    }
+
+    /// Scroll step of `page` at `now`; the clock restarts when the page
+    /// changes.
+    async fn scroll_step(&self, page: &PageConfig, now: Instant) -> u64 {
+        let mut state = self.lcd_scroll.lock().await;
+        if state.page != page.name {
+            *state = ScrollState {
+                page: page.name.clone(),
+                start: now,
+                sent: 0,
+            };
+        }
+        let Some(scroll) = page.lcd.as_ref().and_then(|lcd| lcd.scroll.as_ref()) else {
+            return 0;
+        };
+        let elapsed = now.saturating_duration_since(state.start).as_millis();
+        (elapsed / u128::from(scroll.interval_ms.max(1))) as u64
+    }
+
+    /// LCD lines of the active page that scrolled since the last poll.
+    pub async fn poll_lcd_scroll(&self) -> Vec<Vec<u8>> {
+        self.poll_lcd_scroll_at(Instant::now()).await
+    }
+
+    pub(super) async fn poll_lcd_scroll_at(&self, now: Instant) -> Vec<Vec<u8>> {
+        // The screen saver owns the LCD until the next input
+        if self.is_idle().await {
+            return Vec::new();
+        }
+        let Some(page) = self.get_active_page().await else {
+            return Vec::new();
+        };
+        if page.lcd.as_ref().is_none_or(|lcd| lcd.scroll.is_none()) {
+            return Vec::new();
+        }
+        let step = self.scroll_step(&page, now).await;
+        let sent = std::mem::replace(&mut self.lcd_scroll.lock().await.sent, step);
+        if sent == step {
+            return Vec::new();
+        }
+
+        let before = self.render_lcd_step(&page, sent).await;
+        let after = self.render_lcd_step(&page, step).await;
+        let mut updates = Vec::new();
+        for (strip, (old, new)) in before.labels.iter().zip(&after.labels).enumerate() {
+            let (upper, lower) = build_lcd_strip_sysex(strip as u8, &new.0, &new.1);
+            if old.0 != new.0 {
+                updates.push(upper);
+            }
+            if old.1 != new.1 {
+                updates.push(lower);
+            }
+        }
+        updates
+    }
+
+    /// SysEx updates after `signal` changed: the text of the active page's
+    /// strips whose label (or merged area) reads it, and the colors when a
+    /// color reads it.
+    pub async fn lcd_updates_for_signal(&self, signal: &str) -> Vec<Vec<u8>> {
+        // The screen saver owns the LCD until the next input
+        if self.is_idle().await {
+            return Vec::new();
+        }
+        let Some(page) = self.get_active_page().await else {
+            return Vec::new();
+        };
+        let Some(lcd) = &page.lcd else {
+            return Vec::new();
+        };
+
+        let mut strips = [false; LCD_STRIPS];
+        for (strip, label) in lcd.labels.iter().flatten().enumerate().take(LCD_STRIPS) {
+            strips[strip] = label_reads_signal(label, signal);
+        }
+        for (area, upper, lower) in merged_areas(lcd) {
+            let reads = lines_read_signal(upper, lower, signal);
+            strips[area].fill(reads);
+        }
+        let colors = lcd
+            .colors
+            .iter()
+            .flatten()
+            .any(|color| color.reads_signal(signal));
+        if !colors && !strips.contains(&true) {
+            return Vec::new();
+        }
+
+        let frame = self.render_lcd(&page).await;
+        let mut updates = Vec::new();
+        for (strip, (upper, lower)) in frame.labels.iter().enumerate() {
+            if strips[strip] {
+                let (upper, lower) = build_lcd_strip_sysex(strip as u8, upper, lower);
+                updates.extend([upper, lower]);
+            }
+        }
+        if colors {
+            updates.push(build_lcd_colors_sysex(&frame.colors));
+        }
+        updates
+    }
+
+    fn render_label(
+        &self,
+        lcd: &LcdConfig,
+        strip: usize,
+        page_name: &str,
+        signals: &SignalValues,
+    ) -> (String, String) {
+        let Some(label) = lcd.labels.as_ref().and_then(|labels| labels.get(strip)) else {
+            return Default::default();
+        };
+        let (upper, lower) = label_lines(label);
+        (
+            interpolate_text(upper, Some(page_name), signals),
+            interpolate_text(lower, Some(page_name), signals),
+        )
+    }
+
+    fn render_colors(&self, lcd: &LcdConfig, signals: &SignalValues) -> [u8; LCD_STRIPS] {
+        let mut colors = [0; LCD_STRIPS];
+        for (slot, color) in colors.iter_mut().zip(lcd.colors.iter().flatten()) {
+            *slot = color.resolve(&|when| self.condition_holds(when, signals));
+        }
+        colors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_lines_scroll_through_the_area() {
+        let marquee = |step| scroll_window("Breaking", 7, LcdScrollMode::Marquee, step);
+        assert_eq!(marquee(0), "Breakin");
+        assert_eq!(marquee(1), "reaking");
+        assert_eq!(marquee(3), "aking  ");
+        assert_eq!(marquee(7), "g   Bre");
+        assert_eq!(marquee(11), marquee(0));
+
+        let segments = |step| scroll_window("Breaking", 7, LcdScrollMode::Segments, step);
+        assert_eq!(segments(0), "Breakin");
+        assert_eq!(segments(1), "g");
+        assert_eq!(segments(2), segments(0));
+
+        assert_eq!(scroll_window("Mic", 7, LcdScrollMode::Marquee, 5), "Mic");
+        assert_eq!(split_cells("Now playing", 3), ["Now pla", "ying", ""]);
+    }
+}
@@ -8,18 +8,28 @@
 //! - Page refresh with state replay
 
 mod anti_echo;
+mod bank;
 mod camera_target;
+mod conditions;
 mod driver;
+mod encoder;
+mod fanout;
 mod feedback;
 mod feedback_toggle;
+mod idle;
 mod indicators;
+mod interpolate;
+mod lcd;
 mod page;
+mod pickup;
 mod refresh;
 mod refresh_plan;
+mod touch;
 mod xtouch_input;
 
 pub use crate::event_bus::{LiveEvent, LiveEventTx};
 pub use camera_target::CameraTargetState;
+pub use indicators::LedState;
 
 #[cfg(test)]
 mod tests;
@@ -77,6 +87,35 @@ pub struct Router {
     /// actual transition, not on every repeated feedback message. See
     /// `feedback_toggle.rs`.
     pub(crate) toggle_states: Arc<RwLock<HashMap<String, bool>>>,
+    /// Last normalized input forwarded per `control_id -> app` step that has
+    /// a `value.deadband`. See `xtouch_input.rs`.
+    pub(crate) value_deadband: Arc<RwLock<HashMap<String, f64>>>,
+    /// Target positions of `link` controls, keyed by `control_id`. See
+    /// `fanout.rs`.
+    pub(crate) link_states: Arc<RwLock<HashMap<String, fanout::LinkState>>>,
+    /// Position (0.0-1.0) of `encoder` controls, keyed by
+    /// `control_id -> app`. See `encoder.rs`.
+    pub(crate) encoder_positions: Arc<RwLock<HashMap<String, f64>>>,
+    /// Soft takeover state of `pickup` controls, keyed by `control_id`.
+    /// Cleared on page refresh. See `pickup.rs`.
+    pub(crate) pickup_states: Arc<RwLock<HashMap<String, pickup::PickupState>>>,
+    /// Channel offset of each banked page, keyed by page name. See `bank.rs`.
+    pub(crate) bank_offsets: Arc<RwLock<HashMap<String, u8>>>,
+    /// Screen saver timer. See `idle.rs`.
+    pub(crate) idle: Arc<tokio::sync::Mutex<idle::IdleState>>,
+    /// LCD scroll clock of the active page. See `lcd.rs`.
+    pub(crate) lcd_scroll: Arc<tokio::sync::Mutex<lcd::ScrollState>>,
+    /// Signal store: latest value per driver signal, read by `when:`
+    /// conditions and `${...}` params. See `conditions.rs` / `interpolate.rs`.
+    pub(crate) signal_values: Arc<RwLock<conditions::SignalValues>>,
+    /// Notified after each signal store update (macro `wait_for`).
+    pub(crate) signal_changed: Arc<tokio::sync::Notify>,
+    /// Signal values seen on a button press, keyed by `control_id`, so the
+    /// release takes the same `when:` branch.
+    pub(crate) condition_latch: Arc<RwLock<HashMap<String, conditions::SignalValues>>>,
+    /// Sled database for small persistent runtime state (camera targets,
+    /// driver trees opened with [`Router::open_state_tree`]).
+    state_db: sled::Db,
 }
 
 impl Router {
@@ -109,7 +148,7 @@ impl Router {
             .with_context(|| {
                 format!("Failed to open camera sled database at: {}", camera_db_path)
             })?;
-        let camera_targets = Arc::new(CameraTargetState::new(camera_db));
+        let camera_targets = Arc::new(CameraTargetState::new(camera_db.clone()));
 
         Ok(Self {
             config: Arc::new(RwLock::new(config)),
@@ -127,9 +166,28 @@ impl Router {
             live_tx: Arc::new(tokio::sync::RwLock::new(None)),
             display_refresh_notify: Arc::new(tokio::sync::Notify::new()),
             toggle_states: Arc::new(RwLock::new(HashMap::new())),
+            value_deadband: Arc::new(RwLock::new(HashMap::new())),
+            link_states: Arc::new(RwLock::new(HashMap::new())),
+            encoder_positions: Arc::new(RwLock::new(HashMap::new())),
+            pickup_states: Arc::new(RwLock::new(HashMap::new())),
+            bank_offsets: Arc::new(RwLock::new(HashMap::new())),
+            idle: Arc::new(tokio::sync::Mutex::new(idle::IdleState::default())),
+            lcd_scroll: Arc::new(tokio::sync::Mutex::new(lcd::ScrollState::default())),
+            signal_values: Arc::new(RwLock::new(HashMap::new())),
+            signal_changed: Arc::new(tokio::sync::Notify::new()),
+            condition_latch: Arc::new(RwLock::new(HashMap::new())),
+            state_db: camera_db,
         })
     }
 
+    /// Open (or create) a named tree in the router's persistent state
+    /// database, for drivers that keep state across restarts.
+    pub fn open_state_tree(&self, name: &str) -> Result<sled::Tree> {
+        self.state_db
+            .open_tree(name)
+            .with_context(|| format!("Failed to open state tree '{}'", name))
+    }
+
     /// Inject the live event broadcaster after construction.
     ///
     /// The `Router` is constructed before the editor / API state, so we wire
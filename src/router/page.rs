@@ -3,6 +3,7 @@
 use crate::config::PageConfig;
 use crate::event_bus::{now_ms, LiveEvent};
 use anyhow::{anyhow, Result};
+use std::borrow::Cow;
 use std::collections::HashSet;
 use tracing::info;
 
@@ -28,11 +29,18 @@ impl super::Router {
         *self.active_page_index.read().await
     }
 
-    /// Get the active page configuration
+    /// Get the active page configuration, as seen through its current bank
     pub async fn get_active_page(&self) -> Option<PageConfig> {
-        let config = self.config.read().await;
-        let index = *self.active_page_index.read().await;
-        config.pages.get(index).cloned()
+        let page = {
+            let config = self.config.read().await;
+            let index = *self.active_page_index.read().await;
+            config.pages.get(index).cloned()?
+        };
+        let banked = match self.bank_view(&page).await {
+            Cow::Owned(banked) => Some(banked),
+            Cow::Borrowed(_) => None,
+        };
+        Some(banked.unwrap_or(page))
     }
 
     /// Get the active page name
@@ -165,20 +173,25 @@ impl super::Router {
         config: &crate::config::AppConfig,
     ) -> HashSet<String> {
         let mut apps = HashSet::new();
+        // The primary app and the feedback source; every target of a linked
+        // control, whose feedback keeps the link offsets up to date.
+        let mut insert_apps = |mapping: &crate::config::ControlMapping| {
+            apps.insert(mapping.app.clone());
+            apps.insert(mapping.feedback_app().to_string());
+            if mapping.link {
+                apps.extend(mapping.targets().map(|(app, _, _)| app.to_string()));
+            }
+        };
 
         // 1. Extract apps from page-specific controls
         if let Some(controls) = &page.controls {
-            for mapping in controls.values() {
-                apps.insert(mapping.app.clone());
-            }
+            controls.values().for_each(&mut insert_apps);
         }
 
         // 2. Extract apps from global controls (always available on all pages)
         if let Some(global) = &config.pages_global {
             if let Some(controls) = &global.controls {
-                for mapping in controls.values() {
-                    apps.insert(mapping.app.clone());
-                }
+                controls.values().for_each(&mut insert_apps);
             }
         }
 
@@ -0,0 +1,176 @@
+//! Soft takeover (pickup) for faders whose motor can't be trusted
+//!
+//! A `pickup: true` control ignores hardware moves until the fader reaches
+//! the app's last known value: the `StateActor` entry of its feedback
+//! target, with the target's `value:` transform reversed. It is picked up
+//! within [`PICKUP_TOLERANCE`] of that value, or when a move crosses it.
+//! Until then the strip's lower LCD line shows which way to move.
+//!
+//! Pickup is re-armed on page refresh, and whenever the app's value moved
+//! away from the fader's last position (the app changed on its own and the
+//! motor didn't follow). Without a known value there is nothing to jump
+//! from: moves go through. The value is read from MIDI feedback, so config
+//! validation rejects `pickup` on a target without `midi:`.
+
+use super::fanout::position;
+use crate::config::{ControlMapping, MidiType};
+use crate::midi::MidiMessage;
+use crate::state::{build_entry_from_raw, AppKey, MidiStatus};
+use crate::xtouch::build_lcd_strip_sysex;
+
+/// How close (normalized) the fader must get to the app's value.
+const PICKUP_TOLERANCE: f64 = 0.02;
+
+/// Lower LCD line while the fader must go up / down to pick up.
+const ARROW_UP: &str = " ^^^^^ ";
+const ARROW_DOWN: &str = " vvvvv ";
+
+/// Pickup state of a control.
+#[derive(Debug, Default)]
+pub(crate) struct PickupState {
+    /// Moves go through.
+    engaged: bool,
+    /// Last hardware position.
+    last: Option<f64>,
+    /// Arrow shown on the LCD (`Some(true)`: up).
+    arrow: Option<bool>,
+}
+
+impl PickupState {
+    /// Whether a move to `hw` goes through, given the app's value `app`.
+    fn accept(&mut self, hw: f64, app: Option<f64>) -> bool {
+        let last = self.last.replace(hw);
+        let Some(app) = app else {
+            self.engaged = true;
+            return true;
+        };
+        if self.engaged && last.is_some_and(|last| (last - app).abs() > PICKUP_TOLERANCE) {
+            self.engaged = false;
+        }
+        if !self.engaged {
+            let crossed = last.is_some_and(|last| (last < app) != (hw < app));
+            self.engaged = crossed || (hw - app).abs() <= PICKUP_TOLERANCE;
+        }
+        self.engaged
+    }
+}
+
+/// LCD strip (0-7) of a fader control id.
+fn fader_strip(control_id: &str) -> Option<u8> {
+    control_id
+        .strip_prefix("fader")?
+        .parse::<u8>()
+        .ok()
+        .filter(|n| (1..=8).contains(n))
+        .map(|n| n - 1)
+}
+
+impl super::Router {
+    /// Whether a move of pickup control `control_id` goes through (always
+    /// for controls without `pickup`). Queues the LCD arrow updates.
+    pub(super) async fn pickup_move(
+        &self,
+        control_id: &str,
+        mapping: &ControlMapping,
+        raw: &[u8],
+    ) -> bool {
+        if !mapping.pickup {
+            return true;
+        }
+        let Some(hw) = MidiMessage::parse(raw).as_ref().and_then(position) else {
+            return true;
+        };
+        let app = self.pickup_app_value(mapping, raw).await;
+
+        let (accepted, arrow_changed, arrow) = {
+            let mut states = self.pickup_states.write().await;
+            let state = states.entry(control_id.to_string()).or_default();
+            let accepted = state.accept(hw, app);
+            let arrow = match app {
+                Some(app) if !accepted => Some(app > hw),
+                _ => None,
+            };
+            let changed = state.arrow != arrow;
+            state.arrow = arrow;
+            (accepted, changed, arrow)
+        };
+
+        if arrow_changed {
+            if let Some(strip) = fader_strip(control_id) {
+                self.queue_pickup_arrow(strip, arrow).await;
+            }
+        }
+        accepted
+    }
+
+    /// The app's last known value for `mapping`'s feedback target, on the
+    /// surface scale. `raw` addresses passthrough targets.
+    async fn pickup_app_value(&self, mapping: &ControlMapping, raw: &[u8]) -> Option<f64> {
+        let (app, midi, transform) = mapping.feedback_target();
+        let app_key = AppKey::from_str(app)?;
+        let spec = midi?;
+        let (status, channel, data1) = match spec.midi_type {
+            MidiType::Cc => (MidiStatus::CC, spec.channel, spec.cc),
+            MidiType::Note => (MidiStatus::Note, spec.channel, spec.note),
+            MidiType::Pb => (MidiStatus::PB, spec.channel, Some(0)),
+            // The app gets (and echoes) the surface's own message
+            MidiType::Passthrough => {
+                let addr = build_entry_from_raw(raw, app)?.addr;
+                (addr.status, addr.channel, addr.data1)
+            },
+        };
+        let entry = self
+            .state_actor
+            .get_known_latest(app_key, status, channel, data1)
+            .await?;
+        let value = entry.value.as_number()?;
+        let scale = if status == MidiStatus::PB {
+            16383.0
+        } else {
+            127.0
+        };
+        let value = f64::from(value) / scale;
+        Some(transform.map_or(value, |t| t.reverse(value)))
+    }
+
+    /// Show the pickup arrow on `strip`'s lower line, or restore its label.
+    async fn queue_pickup_arrow(&self, strip: u8, arrow: Option<bool>) {
+        let Some(page) = self.get_active_page().await else {
+            return;
+        };
+        let frame = self.render_lcd(&page).await;
+        let (upper, lower) = &frame.labels[usize::from(strip)];
+        let lower = match arrow {
+            Some(true) => ARROW_UP,
+            Some(false) => ARROW_DOWN,
+            None => lower,
+        };
+        let (upper, lower) = build_lcd_strip_sysex(strip, upper, lower);
+        self.pending_midi_messages
+            .lock()
+            .await
+            .extend([upper, lower]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pickup_engages_near_or_across_app_value() {
+        let mut state = PickupState::default();
+        // Far below the app: ignored, then crossing it picks up.
+        assert!(!state.accept(0.1, Some(0.6)));
+        assert!(!state.accept(0.4, Some(0.6)));
+        assert!(state.accept(0.7, Some(0.6)));
+        // Engaged: the app follows the fader.
+        assert!(state.accept(0.5, Some(0.7)));
+        // The app moved on its own: pickup again.
+        assert!(!state.accept(0.45, Some(0.9)));
+        assert!(state.accept(0.89, Some(0.9)));
+        // Nothing known: moves go through.
+        let mut state = PickupState::default();
+        assert!(state.accept(0.3, None));
+    }
+}
@@ -2,7 +2,7 @@
 
 use super::refresh_plan::MidiReverseMaps;
 use crate::config::{ControlMapping, PageConfig};
-use crate::control_mapping::{ControlMappingDB, MidiSpec};
+use crate::control_mapping::{load_default_mappings, ControlMappingDB, MidiSpec};
 use crate::state::{MidiAddr, MidiStateEntry, MidiStatus, MidiValue, Origin};
 use tracing::{debug, info, trace};
 
@@ -31,6 +31,9 @@ impl super::Router {
         // suppress this refresh's own re-emitted values).
         self.state_actor.clear_shadows().await;
 
+        // Pickup controls must be picked up again on the new page
+        self.pickup_states.write().await.clear();
+
         // Build and execute refresh plan
         let entries = self.plan_page_refresh(&page).await;
 
@@ -76,12 +79,30 @@ impl super::Router {
                     // virtual surface tracks page-driven motor moves.
                     if let (Some(ch), MidiValue::Number(v14)) = (entry.addr.channel, &entry.value) {
                         self.emit_fader_live(ch, *v14).await;
+                        // A touched fader keeps still: applied on release
+                        if self.fader_setpoint.is_touched(ch) {
+                            self.fader_setpoint.schedule(ch, *v14, None);
+                            continue;
+                        }
                     }
                 }
                 midi_messages.push(bytes);
             }
         }
 
+        // The plan addresses the surface in MCU terms
+        if !self.config.read().await.is_mcu_mode() {
+            if let Ok(db) = load_default_mappings() {
+                midi_messages = midi_messages
+                    .iter()
+                    .filter_map(|message| db.mcu_to_ctrl(message))
+                    .collect();
+            }
+        }
+
+        // Encoder rings last, over any replayed CC
+        midi_messages.extend(self.encoder_rings(&page).await);
+
         info!(
             "Page refresh completed: {} (sending {} MIDI messages)",
             page.name,
@@ -117,7 +138,7 @@ impl super::Router {
         };
 
         match Self::get_control_config(page, global_config, control_id) {
-            Some(config) => config.app == app_name,
+            Some(config) => config.feedback_app() == app_name,
             None => false,
         }
     }
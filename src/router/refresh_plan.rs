@@ -169,6 +169,8 @@ impl super::Router {
             {
                 // BUG-010 FIX: Only use PB state if this app owns this fader on the current page.
                 if Self::is_app_mapped_to_fader(page, config, app.as_str(), ch, reverse_maps) {
+                    let latest_pb =
+                        Self::reverse_fader_transform(page, config, ch, reverse_maps, latest_pb);
                     insert_prioritized(pb_plan, ch, latest_pb, 3);
                     continue;
                 }
@@ -188,6 +190,27 @@ impl super::Router {
         }
     }
 
+    /// Apply the reverse `value:` transform of the control on fader `ch` to a
+    /// known app PB, so the motor lands where the app actually is.
+    fn reverse_fader_transform(
+        page: &PageConfig,
+        config: &crate::config::AppConfig,
+        ch: u8,
+        reverse_maps: &MidiReverseMaps,
+        mut entry: MidiStateEntry,
+    ) -> MidiStateEntry {
+        let transform = reverse_maps
+            .pb_channel_to_control_id
+            .get(&ch.saturating_sub(1))
+            .and_then(|control_id| Self::get_control_config(page, config, control_id))
+            .and_then(|control| control.feedback_target().2.cloned());
+        if let (Some(transform), Some(value14)) = (transform, entry.value.as_number()) {
+            let position = transform.reverse(f64::from(value14) / 16383.0);
+            entry.value = MidiValue::Number(crate::midi::convert::denormalize_to_14bit(position));
+        }
+        entry
+    }
+
     /// Build Note plan entries for an app (CC→Note transform, direct lookup, or reset)
     async fn plan_note_entries(
         &self,
@@ -283,13 +306,14 @@ impl super::Router {
         entries
     }
 
-    /// Query CC value from StateActor for a control mapping
+    /// Query CC value from StateActor for a control mapping (its feedback
+    /// source target)
     async fn get_cc_value_for_control(
         &self,
         app: &AppKey,
         control_config: &crate::config::ControlMapping,
     ) -> Option<(MidiStateEntry, u8)> {
-        let midi_spec = control_config.midi.as_ref()?;
+        let midi_spec = control_config.feedback_target().1?;
 
         if !matches!(midi_spec.midi_type, crate::config::MidiType::Cc) {
             return None;
@@ -327,12 +351,17 @@ impl super::Router {
         );
 
         let control_config = Self::get_control_config(page, global_config, control_id)?;
-        if control_config.app != app.as_str() {
+        if control_config.feedback_app() != app.as_str() {
             return None;
         }
 
         let (cc_entry, cc_value) = self.get_cc_value_for_control(app, &control_config).await?;
-        let pb_value = crate::midi::convert::to_14bit(cc_value);
+        let pb_value = match control_config.feedback_target().2 {
+            Some(transform) => crate::midi::convert::denormalize_to_14bit(
+                transform.reverse(f64::from(cc_value) / 127.0),
+            ),
+            None => crate::midi::convert::to_14bit(cc_value),
+        };
 
         trace!(
             "  Transform CC {} -> PB {} (0x{:04X})",
@@ -372,7 +401,7 @@ impl super::Router {
     ) -> Option<(String, crate::config::ControlMapping)> {
         let control_id = note_map.get(&note)?.clone();
         let control_config = Self::get_control_config(page, global_config, &control_id)?;
-        if control_config.app != app.as_str() {
+        if control_config.feedback_app() != app.as_str() {
             return None;
         }
         Some((control_id, control_config))
@@ -391,7 +420,11 @@ impl super::Router {
             Self::resolve_note_control(page, global_config, app, note, note_map)?;
 
         let (cc_entry, cc_value) = self.get_cc_value_for_control(app, &control_config).await?;
-        let velocity: u8 = if cc_value > 0 { 127 } else { 0 };
+        let level = match control_config.feedback_target().2 {
+            Some(transform) => transform.reverse(f64::from(cc_value) / 127.0),
+            None => f64::from(cc_value),
+        };
+        let velocity: u8 = if level > 0.0 { 127 } else { 0 };
 
         trace!(
             "CC->Note transform: {} CC {} -> Note {} velocity {}",
@@ -30,6 +30,14 @@ fn make_test_config(pages: Vec<PageConfig>) -> AppConfig {
         gamepad: None,
         pages_global: None,
         winaudio: None,
+        pulseaudio: None,
+        vmix: None,
+        mpris: None,
+        mmc: None,
+        process: None,
+        vars: None,
+        macros: None,
+        page_templates: None,
         pages,
         tray: None,
     }
@@ -159,6 +167,15 @@ async fn test_midi_note_off_does_not_double_fire_driver_action() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     page.controls = Some(controls);
@@ -302,6 +319,15 @@ async fn test_driver_execution_with_context() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     page.controls = Some(controls);
@@ -341,6 +367,15 @@ async fn test_driver_execution_missing_driver() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     page.controls = Some(controls);
@@ -401,6 +436,15 @@ async fn test_multiple_drivers_execution() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
 
@@ -415,6 +459,15 @@ async fn test_multiple_drivers_execution() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
 
@@ -638,6 +691,15 @@ async fn test_update_config_unregisters_drivers_removed_from_new_config() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     control_a.insert(
@@ -651,6 +713,15 @@ async fn test_update_config_unregisters_drivers_removed_from_new_config() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     let mut page_a = make_test_page("AB");
@@ -684,6 +755,15 @@ async fn test_update_config_unregisters_drivers_removed_from_new_config() {
             indicator: None,
             also: None,
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     let mut page_b = make_test_page("B");
@@ -757,6 +837,9 @@ async fn test_multi_action_also_fans_out_press_and_release_semantics() {
                     action: Some("selectCamera".to_string()),
                     params: Some(vec![json!("Main")]),
                     midi: None,
+                    value: None,
+                    when: None,
+                    else_steps: Vec::new(),
                 },
                 // MIDI direct step (QLC-like CC) → both edges.
                 ActionStep {
@@ -769,9 +852,21 @@ async fn test_multi_action_also_fans_out_press_and_release_semantics() {
                         cc: Some(20),
                         note: None,
                     }),
+                    value: None,
+                    when: None,
+                    else_steps: Vec::new(),
                 },
             ]),
             toggle: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     page.controls = Some(controls);
@@ -833,6 +928,89 @@ async fn test_multi_action_also_fans_out_press_and_release_semantics() {
     );
 }
 
+// ===== Conditional mappings (`when` / `else`) =====
+
+/// A control-level `when` picks the primary effect while the signal holds and
+/// the `else` steps otherwise. A release follows its press's branch even if
+/// the signal flipped in between (the `else` MIDI step gets both edges).
+#[tokio::test]
+async fn test_when_selects_branch_and_release_follows_press() {
+    use crate::config::{ActionStep, IndicatorConfig, MidiSpec, MidiType};
+
+    let mut page = make_test_page("Page 1");
+    let mut controls = HashMap::new();
+    controls.insert(
+        "mute1".to_string(),
+        ControlMapping {
+            app: "studio".to_string(),
+            action: Some("TriggerStudioModeTransition".to_string()),
+            params: None,
+            midi: None,
+            overlay: None,
+            indicator: None,
+            also: None,
+            toggle: None,
+            value: None,
+            when: Some(IndicatorConfig {
+                signal: "obs.studioMode".to_string(),
+                equals: None,
+                truthy: Some(true),
+                ..IndicatorConfig::default()
+            }),
+            else_steps: vec![ActionStep {
+                app: "midiapp".to_string(),
+                action: None,
+                params: None,
+                midi: Some(MidiSpec {
+                    midi_type: MidiType::Cc,
+                    channel: Some(1),
+                    cc: Some(20),
+                    note: None,
+                }),
+                value: None,
+                when: None,
+                else_steps: Vec::new(),
+            }],
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
+        },
+    );
+    page.controls = Some(controls);
+
+    let router = make_test_router(make_test_config(vec![page]));
+    let studio = Arc::new(ConsoleDriver::new("studio"));
+    let midiapp = Arc::new(ConsoleDriver::new("midiapp"));
+    router
+        .register_driver("studio".to_string(), studio.clone())
+        .await
+        .unwrap();
+    router
+        .register_driver("midiapp".to_string(), midiapp.clone())
+        .await
+        .unwrap();
+
+    // Signal never emitted → falsy → `else`. The press flips studio mode
+    // before the release arrives: the release must stay on `else`.
+    router.on_midi_from_xtouch(&[0x90, 16, 127]).await;
+    router.record_signal("obs.studioMode", &json!(true)).await;
+    router.on_midi_from_xtouch(&[0x80, 16, 0]).await;
+    assert_eq!(studio.execution_count().await, 0, "primary skipped");
+    assert_eq!(
+        midiapp.execution_count().await,
+        2,
+        "else step gets press and release"
+    );
+
+    // Studio mode on → primary effect, `else` untouched.
+    router.on_midi_from_xtouch(&[0x90, 16, 127]).await;
+    assert_eq!(studio.execution_count().await, 1, "primary fires when true");
+    assert_eq!(midiapp.execution_count().await, 2, "else not dispatched");
+}
+
 // ===== Feedback-driven toggles (`toggle`) =====
 
 use crate::config::{ActionStep, GlobalPageDefaults, MidiSpec, MidiType, ToggleConfig};
@@ -849,12 +1027,18 @@ fn record_toggle(watch: Option<MidiSpec>) -> ToggleConfig {
             action: Some("selectCamera".to_string()),
             params: Some(vec![json!("Main"), json!("program")]),
             midi: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
         }],
         off: vec![ActionStep {
             app: "obsoff".to_string(),
             action: Some("changeScene".to_string()),
             params: Some(vec![json!("End")]),
             midi: None,
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
         }],
     }
 }
@@ -873,6 +1057,15 @@ fn make_toggle_config(toggle: ToggleConfig) -> AppConfig {
             indicator: None,
             also: None,
             toggle: Some(toggle),
+            value: None,
+            when: None,
+            else_steps: Vec::new(),
+            feedback_from: None,
+            link: false,
+            encoder: None,
+            on_touch: Vec::new(),
+            on_release: Vec::new(),
+            pickup: false,
         },
     );
     let mut config = make_test_config(vec![make_test_page("P1")]);
@@ -1032,3 +1225,660 @@ async fn test_feedback_toggle_ignores_other_source_app() {
     );
     assert_eq!(off_drv.execution_count().await, 0);
 }
+
+// ===== Macros (`macros`, `app: "macro"`) =====
+
+/// Poll until `driver` has run `expected` actions (macros run as
+/// background tasks).
+async fn wait_for_executions(driver: &ConsoleDriver, expected: u64) {
+    for _ in 0..400 {
+        if driver.execution_count().await == expected {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    panic!(
+        "expected {} executions, got {}",
+        expected,
+        driver.execution_count().await
+    );
+}
+
+async fn execute_macro(router: &Router, driver: &crate::drivers::MacroDriver, action: &str) {
+    let ctx = router.create_execution_context().await;
+    driver
+        .execute(action, vec![json!("intro")], ctx)
+        .await
+        .unwrap();
+}
+
+/// A macro blocks on `wait_for` until the signal arrives; running it again
+/// meanwhile cancels the waiting run and starts over.
+#[tokio::test]
+async fn test_macro_waits_for_signal_and_retrigger_restarts() {
+    use crate::drivers::MacroDriver;
+
+    let mut config = make_test_config(vec![make_test_page("Page 1")]);
+    config.macros = Some(
+        serde_yaml::from_str(
+            r#"
+intro:
+  steps:
+    - { app: studio, action: first }
+    - wait_for: { signal: obs.streaming, truthy: true }
+      app: studio
+      action: second
+"#,
+        )
+        .unwrap(),
+    );
+    let router = Arc::new(make_test_router(config));
+    let studio = Arc::new(ConsoleDriver::new("studio"));
+    router
+        .register_driver("studio".to_string(), studio.clone())
+        .await
+        .unwrap();
+
+    let driver = Arc::new(MacroDriver::new(Arc::downgrade(&router)));
+    let running = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = running.clone();
+    driver.subscribe_indicators(Arc::new(move |signal, value| {
+        if signal == "macro.intro.running" {
+            sink.lock().unwrap().push(value);
+        }
+    }));
+    router
+        .register_driver("macro".to_string(), driver.clone())
+        .await
+        .unwrap();
+
+    execute_macro(&router, &driver, "run").await;
+    wait_for_executions(&studio, 1).await;
+    tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+    assert_eq!(
+        studio.execution_count().await,
+        1,
+        "second step waits for the signal"
+    );
+
+    // Retrigger while waiting: first step again, still waiting.
+    execute_macro(&router, &driver, "run").await;
+    wait_for_executions(&studio, 2).await;
+
+    router.record_signal("obs.streaming", &json!(true)).await;
+    wait_for_executions(&studio, 3).await;
+    for _ in 0..400 {
+        if running.lock().unwrap().len() == 3 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    assert_eq!(
+        *running.lock().unwrap(),
+        vec![json!(false), json!(true), json!(false)]
+    );
+}
+
+// ===== Feedback fan-out (`feedback_from`, `link`) =====
+
+/// A fader driving two apps moves with its `feedback_from` app only, and a
+/// linked move shifts each app by the fader's delta, keeping their offset.
+#[tokio::test]
+async fn test_fanout_feedback_source_and_linked_moves() {
+    use crate::midi::MidiMessage;
+
+    let fader: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: cc, channel: 1, cc: 1 }
+also:
+  - { app: vm, midi: { type: cc, channel: 1, cc: 2 } }
+feedback_from: vm
+link: true
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("fader1".to_string(), fader.clone())]));
+    let router = make_test_router(make_test_config(vec![page]));
+
+    // QLC at half, not the source: the motor stays put.
+    assert!(router
+        .process_feedback("qlc", &[0xB0, 1, 64])
+        .await
+        .is_none());
+    // Voicemeeter at the top drives the fader.
+    let forwarded = router
+        .process_feedback("vm", &[0xB0, 2, 127])
+        .await
+        .unwrap();
+    assert!(matches!(
+        MidiMessage::parse(&forwarded),
+        Some(MidiMessage::PitchBend { channel: 0, .. })
+    ));
+
+    // Fader pulled down by 0.2: QLC 0.5 -> 0.3, Voicemeeter 1.0 -> 0.8.
+    let steps = vec![fader.primary_step(), fader.also.clone().unwrap()[0].clone()];
+    let down = MidiMessage::PitchBend {
+        channel: 0,
+        value: 13107,
+    };
+    let moves = router
+        .linked_moves("fader1", &fader, &steps, &down.to_bytes())
+        .await
+        .expect("linked control");
+    let values: Vec<f64> = moves
+        .iter()
+        .map(|raw| match MidiMessage::parse(raw) {
+            Some(MidiMessage::PitchBend { value, .. }) => f64::from(value) / 16383.0,
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert!((values[0] - 0.3).abs() < 0.02, "{:?}", values);
+    assert!((values[1] - 0.8).abs() < 0.02, "{:?}", values);
+}
+
+// ===== Indicators =====
+
+/// Compound indicators read other signals from the store, and a `blink`
+/// condition blinks the LED until the solid one holds.
+#[tokio::test]
+async fn test_indicator_expressions_and_blink() {
+    use super::LedState;
+
+    let controls: HashMap<String, ControlMapping> = serde_yaml::from_str(
+        r#"
+play:
+  app: obs
+  action: TriggerStudioModeTransition
+  indicator:
+    signal: obs.streaming
+    truthy: true
+    blink: { signal: obs.transition, in: [Fade, Cut] }
+talk:
+  app: obs
+  action: toggleMute
+  indicator:
+    all: [{ signal: mic.level, between: [-20, 0] }]
+    not: { signal: mic.muted, truthy: true }
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Live");
+    page.controls = Some(controls);
+    let router = make_test_router(make_test_config(vec![page]));
+
+    let states = router
+        .evaluate_indicators("obs.transition", &json!("Fade"))
+        .await;
+    assert_eq!(
+        states,
+        HashMap::from([("play".to_string(), LedState::Blink)])
+    );
+    router.record_signal("obs.transition", &json!("Fade")).await;
+    let states = router
+        .evaluate_indicators("obs.streaming", &json!(true))
+        .await;
+    assert_eq!(states["play"], LedState::On);
+
+    let states = router.evaluate_indicators("mic.level", &json!("-12")).await;
+    assert_eq!(states, HashMap::from([("talk".to_string(), LedState::On)]));
+    router.record_signal("mic.level", &json!(-12)).await;
+    router.record_signal("mic.muted", &json!(true)).await;
+    assert_eq!(
+        router.evaluate_indicators("mic.level", &json!(-12)).await["talk"],
+        LedState::Off
+    );
+    assert_eq!(
+        router.evaluate_indicators("mic.muted", &json!(false)).await["talk"],
+        LedState::On
+    );
+    assert_eq!(LedState::Blink.velocity(), 1);
+}
+
+// ===== LCD bindings =====
+
+/// Labels and colors bound to a signal render from the store, and a change
+/// of that signal re-sends only the strips reading it.
+#[tokio::test]
+async fn test_lcd_labels_and_colors_follow_signals() {
+    let mut page = make_test_page("Live");
+    page.lcd = Some(
+        serde_yaml::from_str(
+            r#"
+labels: ["${obs.scene}", { upper: "Mix", lower: "${page}" }]
+colors:
+  - { signal: obs.scene, equals: "CAM Cour", then: red, else: green }
+  - 3
+"#,
+        )
+        .unwrap(),
+    );
+    let router = make_test_router(make_test_config(vec![page.clone()]));
+
+    let frame = router.render_lcd(&page).await;
+    assert_eq!(frame.labels[0], (String::new(), String::new()));
+    assert_eq!(frame.labels[1], ("Mix".to_string(), "Live".to_string()));
+    assert_eq!(frame.colors[..3], [2, 3, 0]);
+
+    router.record_signal("obs.scene", &json!("CAM Cour")).await;
+    let frame = router.render_lcd(&page).await;
+    assert_eq!(frame.labels[0].0, "CAM Cour");
+    assert_eq!(frame.colors[0], 1);
+
+    // Strip 1 text (upper + lower) and the colors message.
+    let updates = router.lcd_updates_for_signal("obs.scene").await;
+    assert_eq!(updates.len(), 3);
+    assert_eq!(
+        updates[2],
+        crate::xtouch::build_lcd_colors_sysex(&[1, 3, 0, 0, 0, 0, 0, 0])
+    );
+    assert!(router
+        .lcd_updates_for_signal("obs.studioMode")
+        .await
+        .is_empty());
+}
+
+/// A merged area spreads its text over its strips; scrolling lines move on
+/// each step and only the lines that moved are re-sent.
+#[tokio::test]
+async fn test_lcd_merged_text_scrolls() {
+    let mut page = make_test_page("Now");
+    page.lcd = Some(
+        serde_yaml::from_str(
+            r#"
+labels: ["Mic", "Hidden", "Hidden", "Headphones"]
+merged: [{ from: 2, to: 3, upper: "${track}", lower: "Playing" }]
+scroll: { mode: segments, interval_ms: 100 }
+"#,
+        )
+        .unwrap(),
+    );
+    let router = make_test_router(make_test_config(vec![page.clone()]));
+    router
+        .record_signal("track", &json!("Lorem ipsum dolor sit"))
+        .await;
+
+    let start = std::time::Instant::now();
+    let frame = router.render_lcd(&page).await;
+    assert_eq!(frame.labels[0].0, "Mic");
+    assert_eq!(
+        frame.labels[1],
+        ("Lorem i".to_string(), "Playing".to_string())
+    );
+    assert_eq!(frame.labels[2], ("psum do".to_string(), String::new()));
+    assert_eq!(frame.labels[3].0, "Headpho");
+
+    // Nothing moved yet
+    assert!(router.poll_lcd_scroll_at(start).await.is_empty());
+    let updates = router
+        .poll_lcd_scroll_at(start + std::time::Duration::from_millis(150))
+        .await;
+    let (strip2, _) = crate::xtouch::build_lcd_strip_sysex(1, "lor sit", "");
+    let (strip3, _) = crate::xtouch::build_lcd_strip_sysex(2, "", "");
+    let (strip4, _) = crate::xtouch::build_lcd_strip_sysex(3, "nes", "");
+    assert_eq!(updates, vec![strip2, strip3, strip4]);
+
+    // The merged area reads `track`: both of its strips are re-sent
+    assert_eq!(router.lcd_updates_for_signal("track").await.len(), 4);
+}
+
+// ===== Encoders =====
+
+/// Encoder ticks move a stored position sent absolutely, feedback resets
+/// it, and the ring follows both.
+#[tokio::test]
+async fn test_absolute_encoder_ticks_feedback_and_ring() {
+    let vpot: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: cc, channel: 1, cc: 5 }
+encoder: { ring: boost_cut, step: 0.1, initial: 0.5 }
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("vpot1_rotate".to_string(), vpot)]));
+    let router = make_test_router(make_test_config(vec![page]));
+
+    // Two clockwise ticks: 0.5 -> 0.7, ring in boost/cut at position 8.
+    router.on_midi_from_xtouch(&[0xB0, 16, 2]).await;
+    let position = router.encoder_positions.read().await["vpot1_rotate->qlc"];
+    assert!((position - 0.7).abs() < 1e-9, "{}", position);
+    assert_eq!(router.take_pending_midi().await, vec![vec![0xB0, 48, 0x18]]);
+
+    // One counter-clockwise tick.
+    router.on_midi_from_xtouch(&[0xB0, 16, 65]).await;
+    assert_eq!(router.take_pending_midi().await, vec![vec![0xB0, 48, 0x17]]);
+
+    // QLC reports its minimum: the ring follows.
+    let forwarded = router.process_feedback("qlc", &[0xB0, 5, 0]).await;
+    assert_eq!(forwarded, Some(vec![0xB0, 48, 0x11]));
+    assert_eq!(
+        router
+            .encoder_positions
+            .read()
+            .await
+            .get("vpot1_rotate->qlc"),
+        Some(&0.0)
+    );
+}
+
+// ===== Fader touch =====
+
+/// Touching a fader locks its motor and runs `on_touch`; releasing runs
+/// `on_release` and unlocks it.
+#[tokio::test]
+async fn test_fader_touch_lock_and_actions() {
+    let fader: ControlMapping = serde_yaml::from_str(
+        r#"
+app: test_console
+action: setVolume
+on_touch:
+  - { app: test_console, action: selectCamera }
+  - { app: test_console, action: showOverlay }
+on_release:
+  - { app: test_console, action: hideOverlay }
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("fader2".to_string(), fader)]));
+    let router = make_test_router(make_test_config(vec![page]));
+    let driver = Arc::new(ConsoleDriver::new("test_console"));
+    router
+        .register_driver("test_console".to_string(), driver.clone())
+        .await
+        .unwrap();
+
+    // fader2_touch is note 105 in MCU mode.
+    router.on_midi_from_xtouch(&[0x90, 105, 127]).await;
+    assert!(router.fader_setpoint.is_touched(2));
+    assert_eq!(driver.execution_count().await, 2);
+
+    router.on_midi_from_xtouch(&[0x90, 105, 0]).await;
+    assert!(!router.fader_setpoint.is_touched(2));
+    assert_eq!(driver.execution_count().await, 3);
+}
+
+// ===== Pickup (soft takeover) =====
+
+/// A `pickup` fader is ignored until it reaches the app's known value, with
+/// an LCD arrow pointing the way meanwhile.
+#[tokio::test]
+async fn test_pickup_waits_for_app_value() {
+    use crate::midi::MidiMessage;
+    use crate::state::{build_entry_from_raw, AppKey};
+
+    let fader: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: cc, channel: 1, cc: 1 }
+pickup: true
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("fader1".to_string(), fader.clone())]));
+    let router = make_test_router(make_test_config(vec![page]));
+    let entry = build_entry_from_raw(&[0xB0, 1, 96], "qlc").unwrap();
+    router
+        .state_actor
+        .hydrate_from_snapshot_and_wait(AppKey::Qlc, vec![entry])
+        .await;
+
+    let pb = |value: u16| MidiMessage::PitchBend { channel: 0, value }.to_bytes();
+    // Far below QLC (~0.75): ignored, "go up" arrow on strip 1.
+    assert!(!router.pickup_move("fader1", &fader, &pb(1638)).await);
+    let pending = std::mem::take(&mut *router.pending_midi_messages.lock().await);
+    let lower = &pending.last().expect("arrow queued")[7..14];
+    assert_eq!(lower, b" ^^^^^ ");
+    // Same direction: no new LCD write.
+    assert!(!router.pickup_move("fader1", &fader, &pb(8000)).await);
+    assert!(router.pending_midi_messages.lock().await.is_empty());
+    // Crossing the app value picks it up and restores the label.
+    assert!(router.pickup_move("fader1", &fader, &pb(14000)).await);
+    assert_eq!(router.pending_midi_messages.lock().await.len(), 2);
+
+    // Re-armed (as on a page refresh): picked up again from scratch.
+    router.pickup_states.write().await.clear();
+    assert!(!router.pickup_move("fader1", &fader, &pb(0)).await);
+}
+
+/// A pitch-bend feedback target is looked up on its own (1-based) channel.
+#[tokio::test]
+async fn test_pickup_waits_for_app_pitch_bend() {
+    use crate::midi::MidiMessage;
+    use crate::state::{build_entry_from_raw, AppKey};
+
+    let fader: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: pb, channel: 1 }
+pickup: true
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("fader1".to_string(), fader.clone())]));
+    let router = make_test_router(make_test_config(vec![page]));
+    // QLC at ~0.75 on channel 1, its neighbour at the bottom on channel 2.
+    let entries = vec![
+        build_entry_from_raw(&[0xE0, 0x7F, 0x5F], "qlc").unwrap(),
+        build_entry_from_raw(&[0xE1, 0x00, 0x00], "qlc").unwrap(),
+    ];
+    router
+        .state_actor
+        .hydrate_from_snapshot_and_wait(AppKey::Qlc, entries)
+        .await;
+
+    let pb = |value: u16| MidiMessage::PitchBend { channel: 0, value }.to_bytes();
+    assert!(!router.pickup_move("fader1", &fader, &pb(1638)).await);
+    assert!(router.pickup_move("fader1", &fader, &pb(14000)).await);
+}
+
+// ===== Fader banking =====
+
+/// BANK / CHANNEL shift a banked page's strips: addresses, LCD labels and
+/// feedback follow the offset, and BANK falls through at the last bank.
+#[tokio::test]
+async fn test_bank_shifts_strip_addresses() {
+    use crate::config::{BankConfig, LcdConfig, LcdLabel};
+    use crate::midi::MidiMessage;
+
+    let fader: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: cc, channel: 1, cc: 1 }
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Mix");
+    page.controls = Some(HashMap::from([("fader1".to_string(), fader)]));
+    page.lcd = Some(LcdConfig {
+        labels: Some(
+            (1..=16)
+                .map(|n| LcdLabel::Simple(format!("Ch {}", n)))
+                .collect(),
+        ),
+        colors: None,
+        scroll: None,
+        merged: None,
+    });
+    page.bank = Some(BankConfig { channels: 16 });
+    let router = make_test_router(make_test_config(vec![page]));
+    let fader1_cc = |page: PageConfig| page.controls.unwrap()["fader1"].midi.clone().unwrap().cc;
+
+    // BANK ► (note 47): channels 9-16.
+    assert!(router.bank_input(&[0x90, 47, 127], true).await);
+    // Releasing BANK is left to page navigation too (it ignores releases).
+    assert!(!router.bank_input(&[0x90, 47, 0], true).await);
+    let page = router.get_active_page().await.unwrap();
+    assert_eq!(fader1_cc(page.clone()), Some(9));
+    assert_eq!(router.render_lcd(&page).await.labels[0].0, "Ch 9");
+    // Last bank: BANK ► is left to page navigation.
+    assert!(!router.bank_input(&[0x90, 47, 127], true).await);
+
+    // CHANNEL ◄ (note 48): channels 8-15; feedback on CC 8 drives fader 1.
+    assert!(router.bank_input(&[0x90, 48, 127], true).await);
+    assert_eq!(fader1_cc(router.get_active_page().await.unwrap()), Some(8));
+    let forwarded = router
+        .process_feedback("qlc", &[0xB0, 8, 127])
+        .await
+        .unwrap();
+    assert!(matches!(
+        MidiMessage::parse(&forwarded),
+        Some(MidiMessage::PitchBend { channel: 0, .. })
+    ));
+
+    // Pitch-bend faders shift their (1-based) channel, up to channel 16.
+    let fader8: ControlMapping = serde_yaml::from_str(
+        r#"
+app: qlc
+midi: { type: pb, channel: 8 }
+"#,
+    )
+    .unwrap();
+    let mut page = make_test_page("Faders");
+    page.controls = Some(HashMap::from([("fader8".to_string(), fader8)]));
+    page.bank = Some(BankConfig { channels: 16 });
+    let router = make_test_router(make_test_config(vec![page]));
+    assert!(router.bank_input(&[0x90, 47, 127], true).await);
+    let page = router.get_active_page().await.unwrap();
+    let fader8 = &page.controls.unwrap()["fader8"];
+    assert_eq!(fader8.midi.as_ref().unwrap().channel, Some(16));
+}
+
+// ===== Screen saver =====
+
+/// Without input the surface goes idle (dimmed LCD, idle text, signal);
+/// the next input restores the page display.
+#[tokio::test]
+async fn test_idle_screen_saver() {
+    use std::time::{Duration, Instant};
+
+    let mut config = make_test_config(vec![make_test_page("Mix")]);
+    config.xtouch =
+        Some(serde_yaml::from_str("idle: { timeout_s: 60, text: \"ON AIR\" }").unwrap());
+    let router = make_test_router(config);
+    let now = Instant::now();
+
+    assert!(router.poll_idle_at(now).await.is_empty());
+    let frame = router.poll_idle_at(now + Duration::from_secs(61)).await;
+    assert!(router.is_idle().await);
+    assert_eq!(
+        router.signal_values.read().await.get("surface.idle"),
+        Some(&json!(true))
+    );
+    // Backlights off, then "ON AIR" across strips 4 and 5.
+    assert_eq!(frame[0], crate::xtouch::build_lcd_colors_sysex(&[0; 8]));
+    assert_eq!(&frame[1 + 2 * 3][7..14], b"    ON ");
+    assert!(router
+        .lcd_updates_for_signal("surface.idle")
+        .await
+        .is_empty());
+
+    router.mark_input().await;
+    assert!(!router.is_idle().await);
+    assert_eq!(
+        router.signal_values.read().await.get("surface.idle"),
+        Some(&json!(false))
+    );
+    assert!(router.check_and_clear_display_update().await);
+}
+// ===== CTRL mode parity =====
+
+/// Router tests below run the same scenario in MCU and CTRL mode.
+fn with_mode(mut config: AppConfig, mode: &str) -> AppConfig {
+    config.xtouch = Some(serde_yaml::from_str(&format!("mode: {}", mode)).unwrap());
+    config
+}
+
+/// Page with QLC on fader 1 (CC 1) and mute 1 (note 5).
+fn make_qlc_strip_page() -> PageConfig {
+    let mut page = make_test_page("Mix");
+    page.controls = Some(
+        serde_yaml::from_str(
+            r#"
+fader1: { app: qlc, midi: { type: cc, channel: 1, cc: 1 } }
+mute1: { app: qlc, midi: { type: note, channel: 1, note: 5 } }
+"#,
+        )
+        .unwrap(),
+    );
+    page
+}
+
+/// BANK ► and F3 page in both modes (notes 47 / 56 in MCU, 93 / 60 in CTRL).
+#[tokio::test]
+async fn test_paging_in_both_modes() {
+    for (mode, next, f3) in [("mcu", 47, 56), ("ctrl", 93, 60)] {
+        let pages = vec![
+            make_test_page("Page 1"),
+            make_test_page("Page 2"),
+            make_test_page("Page 3"),
+        ];
+        let router = make_test_router(with_mode(make_test_config(pages), mode));
+
+        router.on_midi_from_xtouch(&[0x90, next, 127]).await;
+        assert_eq!(router.get_active_page_name().await, "Page 2", "{}", mode);
+        router.on_midi_from_xtouch(&[0x90, f3, 127]).await;
+        assert_eq!(router.get_active_page_name().await, "Page 3", "{}", mode);
+    }
+}
+
+/// App feedback reaches the fader and the mute LED in each mode's encoding;
+/// a forwarded fader PB becomes the fader CC in CTRL mode.
+#[tokio::test]
+async fn test_feedback_in_both_modes() {
+    for (mode, fader, led, passthrough) in [
+        (
+            "mcu",
+            vec![0xE0, 0x7F, 0x7F],
+            vec![0x90, 16, 127],
+            vec![0xE1, 0x00, 0x40],
+        ),
+        (
+            "ctrl",
+            vec![0xB0, 70, 127],
+            vec![0x90, 24, 127],
+            vec![0xB0, 71, 64],
+        ),
+    ] {
+        let config = with_mode(make_test_config(vec![make_qlc_strip_page()]), mode);
+        let router = make_test_router(config);
+
+        let forwarded = router.process_feedback("qlc", &[0xB0, 1, 127]).await;
+        assert_eq!(forwarded, Some(fader), "{}", mode);
+        let forwarded = router.process_feedback("qlc", &[0x90, 5, 127]).await;
+        assert_eq!(forwarded, Some(led), "{}", mode);
+        let forwarded = router.process_feedback("qlc", &[0xE1, 0x00, 0x40]).await;
+        assert_eq!(forwarded, Some(passthrough), "{}", mode);
+    }
+}
+
+/// A page refresh replays the app's fader value in each mode's encoding.
+#[tokio::test]
+async fn test_page_refresh_in_both_modes() {
+    use crate::state::{build_entry_from_raw, AppKey};
+
+    for (mode, fader) in [
+        ("mcu", vec![0xE0, 0x7F, 0x7F]),
+        ("ctrl", vec![0xB0, 70, 127]),
+    ] {
+        let config = with_mode(make_test_config(vec![make_qlc_strip_page()]), mode);
+        let router = make_test_router(config);
+        let entry = build_entry_from_raw(&[0xB0, 1, 127], "qlc").unwrap();
+        router
+            .state_actor
+            .hydrate_from_snapshot_and_wait(AppKey::Qlc, vec![entry])
+            .await;
+
+        router.refresh_page().await;
+        let pending = router.take_pending_midi().await;
+        assert!(pending.contains(&fader), "{}: {:02X?}", mode, pending);
+        if mode == "ctrl" {
+            assert!(pending.iter().all(|msg| msg[0] & 0xF0 != 0xE0));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Fader touch: motor lock and touch actions
+//!
+//! A `faderN_touch` note locks the fader's motor while the hand is on it
+//! (see [`FaderSetpoint::set_touched`]), then runs the fader's `on_touch` /
+//! `on_release` steps as synthetic presses.
+//!
+//! [`FaderSetpoint::set_touched`]: crate::xtouch::fader_setpoint::FaderSetpoint::set_touched
+
+/// Setpoint channel (1-9) of a fader control id (`fader1`..`fader8`,
+/// `fader_master`).
+fn fader_channel(fader_id: &str) -> Option<u8> {
+    if fader_id == "fader_master" {
+        return Some(9);
+    }
+    fader_id
+        .strip_prefix("fader")?
+        .parse()
+        .ok()
+        .filter(|n| (1..=8).contains(n))
+}
+
+impl super::Router {
+    /// Handle a touch (`touched`) or release of `touch_id`; anything but a
+    /// `faderN_touch` control is ignored.
+    pub(super) async fn on_fader_touch(&self, touch_id: &str, touched: bool) {
+        let Some(fader_id) = touch_id.strip_suffix("_touch") else {
+            return;
+        };
+        let Some(channel) = fader_channel(fader_id) else {
+            return;
+        };
+        self.fader_setpoint.set_touched(channel, touched);
+
+        let Some(page) = self.get_active_page().await else {
+            return;
+        };
+        let mapping = {
+            let config = self.config.read().await;
+            Self::get_control_config(&page, &config, fader_id)
+        };
+        let Some(mapping) = mapping else {
+            return;
+        };
+        let steps = if touched {
+            &mapping.on_touch
+        } else {
+            &mapping.on_release
+        };
+        for step in steps {
+            self.dispatch_synthetic_step(fader_id, step).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fader_channels() {
+        assert_eq!(fader_channel("fader1"), Some(1));
+        assert_eq!(fader_channel("fader8"), Some(8));
+        assert_eq!(fader_channel("fader_master"), Some(9));
+        assert_eq!(fader_channel("fader9"), None);
+        assert_eq!(fader_channel("vpot1"), None);
+    }
+}
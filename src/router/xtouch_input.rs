@@ -38,9 +38,12 @@ fn classify_xtouch_midi(raw: &[u8], control_id: &str) -> Option<(HwEventKind, f3
         0x8 if raw.len() >= 3 => Some((HwEventKind::Release, 0.0)),
         0xB if raw.len() >= 3 => {
             let value = raw[2];
-            // Encoder rotation has its own canonical id ("vpot{N}_rotate").
-            // Other CC controls behave like buttons (press / release).
-            if control_id.contains("rotate") || control_id.contains("encoder") {
+            // Encoder rotation has its own canonical id ("vpot{N}_rotate"),
+            // CTRL-mode faders send a CC. Other CC controls behave like
+            // buttons (press / release).
+            if control_id.starts_with("fader") && !control_id.ends_with("_touch") {
+                Some((HwEventKind::Fader, value as f32 / 127.0))
+            } else if control_id.contains("rotate") || control_id.contains("encoder") {
                 Some((HwEventKind::Rotate, value as f32 / 127.0))
             } else if value == 0 {
                 Some((HwEventKind::Release, 0.0))
@@ -52,6 +55,17 @@ fn classify_xtouch_midi(raw: &[u8], control_id: &str) -> Option<(HwEventKind, f3
     }
 }
 
+/// MCU note number of the control sending CTRL-mode note `note`.
+fn ctrl_note_to_mcu(note: u8) -> Option<u8> {
+    use crate::control_mapping::{load_default_mappings, MidiSpec};
+
+    let db = load_default_mappings().ok()?;
+    match db.translate_spec(&MidiSpec::Note { note }, true)? {
+        MidiSpec::Note { note } => Some(note),
+        _ => None,
+    }
+}
+
 impl super::Router {
     /// Process MIDI input from X-Touch hardware
     ///
@@ -69,6 +83,7 @@ impl super::Router {
         if let Some(ref tracker) = self.activity_tracker {
             tracker.record("xtouch", crate::tray::ActivityDirection::Inbound);
         }
+        self.mark_input().await;
 
         let status = raw[0];
         let type_nibble = (status & 0xF0) >> 4;
@@ -89,13 +104,24 @@ impl super::Router {
             )
         };
 
-        // First, check for page navigation (Note On messages only)
+        // BANK / CHANNEL shift the strips of a banked page, before paging
+        // (which uses the BANK buttons by default)
+        if type_nibble == 0x9 && raw.len() >= 3 && self.bank_input(raw, is_mcu_mode).await {
+            return;
+        }
+
+        // First, check for page navigation (Note On messages only). Paging
+        // notes are MCU note numbers: CTRL mode goes through the control table.
         if type_nibble == 0x9 && raw.len() >= 3 {
-            let note = raw[1];
+            let note = if is_mcu_mode {
+                Some(raw[1])
+            } else {
+                ctrl_note_to_mcu(raw[1])
+            };
             let velocity = raw[2];
 
             // Ignore Note Off (velocity 0)
-            if velocity != 0 && channel == paging_channel {
+            if let Some(note) = note.filter(|_| velocity != 0 && channel == paging_channel) {
                 if note == prev_note {
                     debug!("X-Touch: Previous page (note {})", note);
                     self.prev_page().await;
@@ -170,6 +196,16 @@ impl super::Router {
         // Mark user action for Last-Write-Wins
         self.mark_user_action(raw);
 
+        // Toucher de fader : moteur figé tant que la main est dessus, étapes
+        // `on_touch` / `on_release` du fader.
+        if control_id.ends_with("_touch") {
+            match classify_xtouch_midi(raw, control_id) {
+                Some((HwEventKind::Press, _)) => self.on_fader_touch(control_id, true).await,
+                Some((HwEventKind::Release, _)) => self.on_fader_touch(control_id, false).await,
+                _ => {},
+            }
+        }
+
         // CRITICAL: Update fader setpoint for user actions (PitchBend from X-Touch)
         // This ensures the motor tracks the user's physical position
         if type_nibble == 0xE && raw.len() >= 3 {
@@ -180,6 +216,18 @@ impl super::Router {
             debug!("← User moved fader: ch={} value14={}", channel, value14);
             self.fader_setpoint.schedule(channel, value14, None);
         }
+        // CTRL mode: faders send their (7-bit) CC instead
+        if let (MidiSpec::ControlChange { .. }, Some(&value), false) =
+            (&midi_spec, raw.get(2), is_mcu_mode)
+        {
+            if let Some(MidiSpec::PitchBend { channel: fader }) =
+                mapping_db.get_midi_spec(control_id, true)
+            {
+                let value14 = crate::midi::convert::to_14bit(value & 0x7F);
+                debug!("← User moved fader: ch={} value14={}", fader + 1, value14);
+                self.fader_setpoint.schedule(fader + 1, value14, None);
+            }
+        }
 
         // Get active page and find control configuration
         let page = match self.get_active_page().await {
@@ -218,16 +266,38 @@ impl super::Router {
         drop(config);
 
         // Effet primaire (comportement historique du contrôle) : MIDI direct si
-        // `midi:` est présent, sinon action driver.
-        let primary = control_config.primary_step();
-        self.dispatch_step(raw, control_id, &primary).await;
-
-        // Effets supplémentaires (boutons multi-action) : chaque étape est
-        // déclenchée en plus du primaire.
-        if let Some(steps) = &control_config.also {
-            for step in steps {
-                self.dispatch_step(raw, control_id, step).await;
-            }
+        // `midi:` est présent, sinon action driver. Effets supplémentaires
+        // (boutons multi-action) : chaque étape `also` est déclenchée en plus du
+        // primaire. Les conditions `when:` choisissent entre ces étapes et `else`.
+        let kind = classify_xtouch_midi(raw, control_id).map(|(kind, _)| kind);
+        let signals = self
+            .condition_signals(control_id, &control_config, kind)
+            .await;
+        let steps = self.select_control_steps(control_id, &control_config, &signals);
+        // Pickup : mouvements ignorés tant que le fader n'a pas rejoint la
+        // valeur connue de l'app.
+        if !self.pickup_move(control_id, &control_config, raw).await {
+            trace!("Pickup: '{}' not picked up yet, move ignored", control_id);
+            return;
+        }
+        // Encodeur absolu : les crans deviennent la nouvelle position (CC 0-127).
+        let encoder_raw = self.encoder_move(control_id, &control_config, raw).await;
+        let raw = encoder_raw.as_deref().unwrap_or(raw);
+        // Contrôle lié : chaque cible reçoit sa propre position décalée.
+        match self
+            .linked_moves(control_id, &control_config, &steps, raw)
+            .await
+        {
+            Some(moves) => {
+                for (step, raw) in steps.iter().zip(&moves) {
+                    self.dispatch_selected_step(raw, control_id, step).await;
+                }
+            },
+            None => {
+                for step in &steps {
+                    self.dispatch_selected_step(raw, control_id, step).await;
+                }
+            },
         }
     }
 
@@ -236,12 +306,40 @@ impl super::Router {
     /// `midi:` présent → MIDI direct (passthrough/transform) ; sinon action driver.
     ///
     /// `pub(crate)` : réutilisé par `feedback_toggle.rs` pour déclencher les
-    /// étapes d'un toggle (avec un message MIDI synthétique).
+    /// étapes d'un toggle (avec un message MIDI synthétique). Le `when:` de
+    /// l'étape est évalué sur les valeurs de signaux courantes.
     pub(crate) async fn dispatch_step(
         &self,
         raw: &[u8],
         control_id: &str,
         step: &crate::config::ActionStep,
+    ) {
+        let steps = {
+            let signals = self.signal_values.read().await;
+            self.select_steps(step, &signals)
+        };
+        for step in steps {
+            self.dispatch_selected_step(raw, control_id, &step).await;
+        }
+    }
+
+    /// Déclenche une étape hors événement surface (macros) : comme un appui,
+    /// avec le `when:` de l'étape évalué sur les valeurs de signaux courantes.
+    pub async fn dispatch_synthetic_step(
+        &self,
+        control_id: &str,
+        step: &crate::config::ActionStep,
+    ) {
+        self.dispatch_step(&super::feedback_toggle::SYNTHETIC_PRESS, control_id, step)
+            .await;
+    }
+
+    /// Dispatch d'une étape dont la condition a déjà été résolue.
+    async fn dispatch_selected_step(
+        &self,
+        raw: &[u8],
+        control_id: &str,
+        step: &crate::config::ActionStep,
     ) {
         if let Some(target_spec) = &step.midi {
             self.handle_midi_direct_mode(raw, control_id, step, target_spec)
@@ -259,10 +357,17 @@ impl super::Router {
         step: &crate::config::ActionStep,
         target_spec: &crate::config::MidiSpec,
     ) {
-        // 1. Parse input MIDI to get normalized value (0.0 - 1.0)
+        // 1. Parse input MIDI to get normalized value (0.0 - 1.0), then apply
+        // the step's `value:` transform (skipped when inside the deadband)
         let normalized_value = crate::midi::MidiMessage::parse(raw)
             .and_then(|msg| msg.normalized_value())
             .unwrap_or(0.0);
+        let Some(normalized_value) = self
+            .transform_step_value(control_id, step, normalized_value)
+            .await
+        else {
+            return;
+        };
 
         // 2. Construct target message based on config
         use crate::midi::convert::{
@@ -312,8 +417,13 @@ impl super::Router {
                     })
             },
             crate::config::MidiType::Passthrough => {
-                // Raw passthrough (no transformation)
-                crate::midi::MidiMessage::parse(raw)
+                // Raw passthrough: same message, only the value is reshaped
+                // when the step has a `value:` transform
+                let msg = crate::midi::MidiMessage::parse(raw);
+                match (msg, &step.value) {
+                    (Some(msg), Some(_)) => msg.with_normalized_value(normalized_value),
+                    (msg, _) => msg,
+                }
             },
         };
 
@@ -380,6 +490,36 @@ impl super::Router {
         }
     }
 
+    /// Apply a step's `value:` transform to a normalized hardware value.
+    ///
+    /// Returns `None` when the move stays within the step's deadband (the
+    /// step is skipped); values pass through unchanged without a transform.
+    async fn transform_step_value(
+        &self,
+        control_id: &str,
+        step: &crate::config::ActionStep,
+        normalized: f64,
+    ) -> Option<f64> {
+        let Some(transform) = &step.value else {
+            return Some(normalized);
+        };
+        if transform.deadband > 0.0 {
+            let key = format!("{}->{}", control_id, step.app);
+            let mut last = self.value_deadband.write().await;
+            if !transform.passes_deadband(last.get(&key).copied(), normalized) {
+                trace!(
+                    "Deadband: dropping {} = {:.4} for '{}'",
+                    control_id,
+                    normalized,
+                    step.app
+                );
+                return None;
+            }
+            last.insert(key, normalized);
+        }
+        Some(transform.apply(normalized))
+    }
+
     /// Handle driver action mode (execute action on driver)
     async fn handle_driver_action_mode(
         &self,
@@ -455,6 +595,16 @@ impl super::Router {
             };
 
             if type_nibble == 0x9 || type_nibble == 0xB || type_nibble == 0xE {
+                // `value:` transform, applied in the message's own scale so
+                // drivers keep receiving 0-127 / 0-16383
+                let scale = if type_nibble == 0xE { 16383.0 } else { 127.0 };
+                let Some(normalized) = self
+                    .transform_step_value(control_id, step, value / scale)
+                    .await
+                else {
+                    return;
+                };
+                let value = (normalized * scale).round();
                 ctx.value = Some(Value::Number(serde_json::Number::from_f64(value).unwrap()));
             }
         } else {
@@ -468,6 +618,11 @@ impl super::Router {
             });
         }
 
+        // Resolve `${...}` placeholders now that the value is known
+        let params = self
+            .interpolate_step_params(control_id, params, ctx.value.as_ref())
+            .await;
+
         // Execute driver action (timeout-bounded so a wedged driver can't
         // freeze the single main event loop).
         debug!(
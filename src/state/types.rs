@@ -125,7 +125,14 @@ pub enum AppKey {
     Qlc,
     Obs,
     WinAudio,
+    PulseAudio,
     WinMedia,
+    Mpris,
+    Mmc,
+    Process,
+    Vars,
+    Macro,
+    Vmix,
     #[serde(rename = "midi-bridge")]
     MidiBridge,
 }
@@ -138,7 +145,14 @@ impl AppKey {
             AppKey::Qlc,
             AppKey::Obs,
             AppKey::WinAudio,
+            AppKey::PulseAudio,
             AppKey::WinMedia,
+            AppKey::Mpris,
+            AppKey::Mmc,
+            AppKey::Process,
+            AppKey::Vars,
+            AppKey::Macro,
+            AppKey::Vmix,
             AppKey::MidiBridge,
         ]
     }
@@ -150,7 +164,14 @@ impl AppKey {
             "qlc" => Some(AppKey::Qlc),
             "obs" => Some(AppKey::Obs),
             "winaudio" => Some(AppKey::WinAudio),
+            "pulseaudio" => Some(AppKey::PulseAudio),
             "winmedia" => Some(AppKey::WinMedia),
+            "mpris" => Some(AppKey::Mpris),
+            "mmc" => Some(AppKey::Mmc),
+            "process" => Some(AppKey::Process),
+            "vars" => Some(AppKey::Vars),
+            "macro" => Some(AppKey::Macro),
+            "vmix" => Some(AppKey::Vmix),
             "midi-bridge" => Some(AppKey::MidiBridge),
             _ => None,
         }
@@ -163,7 +184,14 @@ impl AppKey {
             AppKey::Qlc => "qlc",
             AppKey::Obs => "obs",
             AppKey::WinAudio => "winaudio",
+            AppKey::PulseAudio => "pulseaudio",
             AppKey::WinMedia => "winmedia",
+            AppKey::Mpris => "mpris",
+            AppKey::Mmc => "mmc",
+            AppKey::Process => "process",
+            AppKey::Vars => "vars",
+            AppKey::Macro => "macro",
+            AppKey::Vmix => "vmix",
             AppKey::MidiBridge => "midi-bridge",
         }
     }
@@ -3,20 +3,26 @@
 //! Handles MIDI communication with the X-Touch control surface.
 
 pub mod fader_setpoint;
+pub mod output_cache;
 pub mod pitch_bend_squelch;
 
+use output_cache::{OutputCache, LCD_COLORS_HEADER, LCD_TEXT_HEADER};
 use pitch_bend_squelch::PitchBendSquelch;
 
 use anyhow::{bail, Context, Result};
 use midir::{MidiInput, MidiInputConnection, MidiOutput, MidiOutputConnection};
+use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::mpsc;
 use tracing::{debug, trace};
 
-use crate::config::{AppConfig, XTouchMode};
+use crate::config::{AppConfig, DeviceProfile, RingMode, XTouchMode};
 use crate::midi::{format_hex, MidiMessage};
 
+/// Characters per strip and LCD line.
+pub const STRIP_CHARS: usize = 7;
+
 /// MIDI event from X-Touch
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // `timestamp`/`message` are part of the public event payload (consumers may inspect them)
@@ -43,6 +49,9 @@ pub struct XTouchDriver {
     /// X-Touch mode (MCU or Ctrl)
     mode: XTouchMode,
 
+    /// Surface model, for what it can display
+    device: DeviceProfile,
+
     /// Input port name pattern
     input_port_name: String,
 
@@ -51,6 +60,9 @@ pub struct XTouchDriver {
 
     /// Pitch bend squelch for preventing feedback loops
     pb_squelch: PitchBendSquelch,
+
+    /// What the surface shows, to skip writes that change nothing
+    output_cache: Arc<Mutex<OutputCache>>,
 }
 
 impl XTouchDriver {
@@ -70,9 +82,11 @@ impl XTouchDriver {
             event_tx,
             event_rx: Some(event_rx),
             mode,
+            device: config.device(),
             input_port_name: config.midi.input_port.clone(),
             output_port_name: config.midi.output_port.clone(),
             pb_squelch: PitchBendSquelch::new(),
+            output_cache: Arc::new(Mutex::new(OutputCache::new())),
         })
     }
 
@@ -237,6 +251,8 @@ impl XTouchDriver {
             .context("Failed to connect to output port")?;
 
         self.output_conn = Some(Arc::new(Mutex::new(output_conn)));
+        // Whatever the surface shows now is unknown
+        self.output_cache.lock().unwrap().invalidate();
 
         debug!("X-Touch connected successfully in {:?} mode", self.mode);
 
@@ -263,19 +279,60 @@ impl XTouchDriver {
 
     /// Send a MIDI message to X-Touch
     pub async fn send(&self, message: &MidiMessage) -> Result<()> {
+        self.send_changed(message).await.map(|_| ())
+    }
+
+    /// Send a MIDI message to X-Touch unless the surface already shows it;
+    /// whether anything was sent.
+    async fn send_changed(&self, message: &MidiMessage) -> Result<bool> {
         let output = self
             .output_conn
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Not connected to output port"))?;
 
         let data = message.encode();
+        let messages = self.filter_output(&data);
+        if messages.is_empty() {
+            return Ok(false);
+        }
 
         let mut conn = output.lock().unwrap();
-        conn.send(&data).context("Failed to send MIDI message")?;
+        for data in &messages {
+            conn.send(data).context("Failed to send MIDI message")?;
+            trace!("Sent: {} | {}", format_hex(data), message);
+        }
 
-        trace!("Sent: {} | {}", format_hex(&data), message);
+        Ok(true)
+    }
 
-        Ok(())
+    /// What to send for `data`: the part the surface doesn't show yet (see
+    /// [`OutputCache`]). In CTRL mode, LCD text and colors go out as the
+    /// per-strip SysEx of the strips they change.
+    fn filter_output<'a>(&self, data: &'a [u8]) -> Vec<Cow<'a, [u8]>> {
+        if !device_can_show(self.device, data) {
+            trace!(
+                "Skipped, no display on {:?}: {}",
+                self.device,
+                format_hex(data)
+            );
+            return Vec::new();
+        }
+        let mut cache = self.output_cache.lock().unwrap();
+        let Some(filtered) = cache.filter(data) else {
+            trace!("Skipped unchanged: {}", format_hex(data));
+            return Vec::new();
+        };
+        if self.mode == XTouchMode::Ctrl {
+            if let Some(strips) = OutputCache::lcd_strips(&filtered) {
+                return strips
+                    .map(|strip| {
+                        let (color, text) = cache.lcd_strip(strip);
+                        Cow::Owned(build_ctrl_lcd_sysex(strip, color, &text))
+                    })
+                    .collect();
+            }
+        }
+        vec![filtered]
     }
 
     /// Send raw MIDI data directly to X-Touch (synchronous, for callbacks)
@@ -290,9 +347,10 @@ impl XTouchDriver {
             .ok_or_else(|| anyhow::anyhow!("Not connected to output port"))?;
 
         let mut conn = output.lock().unwrap();
-        conn.send(data).context("Failed to send raw MIDI data")?;
-
-        trace!("Sent raw feedback: {}", format_hex(data));
+        for data in self.filter_output(data) {
+            conn.send(&data).context("Failed to send raw MIDI data")?;
+            trace!("Sent raw feedback: {}", format_hex(&data));
+        }
 
         Ok(())
     }
@@ -305,9 +363,10 @@ impl XTouchDriver {
             .ok_or_else(|| anyhow::anyhow!("Not connected to output port"))?;
 
         let mut conn = output.lock().unwrap();
-        conn.send(data).context("Failed to send raw MIDI data")?;
-
-        trace!("Sent raw: {}", format_hex(data));
+        for data in self.filter_output(data) {
+            conn.send(&data).context("Failed to send raw MIDI data")?;
+            trace!("Sent raw: {}", format_hex(&data));
+        }
 
         Ok(())
     }
@@ -474,24 +533,9 @@ impl XTouchDriver {
         // Center text to 12 characters
         let centered = Self::center_to_length(text, 12);
 
-        // Convert each character to 7-segment encoding
-        let segs: Vec<u8> = centered
-            .chars()
-            .take(12)
-            .map(Self::seven_seg_for_char)
-            .collect();
-
-        // Dots (disabled by default)
-        let dots1 = 0x00;
-        let dots2 = 0x00;
-
-        // Send to both device IDs (0x14 and 0x15)
+        // Send to both device IDs (0x14 and 0x15), dots disabled
         for device_id in [0x14, 0x15] {
-            let mut data = vec![0x00, 0x20, 0x32, device_id, 0x37];
-            data.extend_from_slice(&segs);
-            data.push(dots1);
-            data.push(dots2);
-
+            let data = seven_segment_data(&centered, 0, device_id);
             self.send(&MidiMessage::SysEx { data }).await?;
         }
 
@@ -564,46 +608,26 @@ impl XTouchDriver {
     /// Apply LCD configuration for active page
     ///
     /// Matches TypeScript applyLcdForActivePage() from ui/lcd.ts
+    ///
+    /// `labels` and `colors` are the rendered strips (see
+    /// `Router::render_lcd`); strips past their end are cleared / black.
     pub async fn apply_lcd_for_page(
         &self,
-        labels: Option<&Vec<crate::config::LcdLabel>>,
-        colors: Option<&Vec<u8>>,
+        labels: &[(String, String)],
+        colors: &[u8],
         page_name: &str,
     ) -> Result<()> {
-        // Clear all strips first to avoid leaks from previous pages
+        // Write every strip, so nothing leaks from the previous page
         for i in 0..8 {
-            self.send_lcd_strip_text(i, "", "").await?;
+            let (upper, lower) = labels
+                .get(i)
+                .map(|(upper, lower)| (upper.as_str(), lower.as_str()))
+                .unwrap_or(("", ""));
+            self.send_lcd_strip_text(i as u8, upper, lower).await?;
         }
 
-        // Apply labels if provided
-        if let Some(labels) = labels {
-            for (i, label) in labels.iter().enumerate().take(8) {
-                let (upper, lower) = match label {
-                    crate::config::LcdLabel::Simple(text) => {
-                        // Split on newline
-                        let parts: Vec<&str> = text.splitn(2, '\n').collect();
-                        (
-                            parts.first().copied().unwrap_or(""),
-                            parts.get(1).copied().unwrap_or(""),
-                        )
-                    },
-                    crate::config::LcdLabel::Structured { upper, lower } => (
-                        upper.as_deref().unwrap_or(""),
-                        lower.as_deref().unwrap_or(""),
-                    ),
-                };
-
-                self.send_lcd_strip_text(i as u8, upper, lower).await?;
-            }
-        }
-
-        // Apply colors if provided, otherwise set to black
-        if let Some(colors) = colors {
-            self.set_lcd_colors(colors).await?;
-        } else {
-            let black_colors = [0u8; 8];
-            self.set_lcd_colors(&black_colors).await?;
-        }
+        // Missing colors are black
+        self.set_lcd_colors(colors).await?;
 
         // Display page name on 7-segment display
         self.set_seven_segment_text(page_name).await?;
@@ -614,7 +638,7 @@ impl XTouchDriver {
     /// Reset all hardware to clean state
     ///
     /// Matches TypeScript resetAll() from api-midi.ts
-    /// - Turns off all button LEDs (notes 0-101)
+    /// - Turns off all button LEDs (notes 0-101), skipping those known off
     /// - Resets all faders to 0
     /// - Optionally clears LCD displays
     pub async fn reset_all(&self, clear_lcds: bool) -> Result<()> {
@@ -628,9 +652,10 @@ impl XTouchDriver {
                 note,
                 velocity: 0, // Velocity 0 turns off LED
             };
-            self.send(&message).await?;
             // Small delay between messages to avoid overwhelming MIDI buffer
-            tokio::time::sleep(tokio::time::Duration::from_millis(2)).await;
+            if self.send_changed(&message).await? {
+                tokio::time::sleep(tokio::time::Duration::from_millis(2)).await;
+            }
         }
 
         // Reset all faders to 0 (faders 0-8: strips 1-8 + master)
@@ -671,6 +696,18 @@ pub(crate) fn ascii7(text: &str, length: usize) -> Vec<u8> {
     bytes
 }
 
+/// Whether `device` has the display `data` writes to: LCD text needs an
+/// LCD, strip colors need a color LCD. Anything else goes through.
+fn device_can_show(device: DeviceProfile, data: &[u8]) -> bool {
+    if data.starts_with(&LCD_TEXT_HEADER) {
+        device.has_lcd()
+    } else if data.starts_with(&LCD_COLORS_HEADER) {
+        device.has_lcd_colors()
+    } else {
+        true
+    }
+}
+
 /// Build the SysEx message that sets all 8 LCD strip background colors.
 ///
 /// Each entry must be 0..=7 (extra values are clamped); 0 = off (black),
@@ -717,6 +754,78 @@ pub fn build_lcd_strip_sysex(strip_index: u8, upper: &str, lower: &str) -> (Vec<
     (upper_msg, lower_msg)
 }
 
+/// Build the CTRL-mode SysEx that sets one LCD strip: background `color`
+/// (0-7) and `text`, upper line then lower line (7 characters each).
+///
+/// `F0 00 20 32 14 4C <strip> <color> <14 bytes ASCII> F7`
+pub fn build_ctrl_lcd_sysex(strip_index: u8, color: u8, text: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(23);
+    msg.extend_from_slice(&[0xF0, 0x00, 0x20, 0x32, 0x14, 0x4C]);
+    msg.push(strip_index.min(7));
+    msg.push(color.min(7));
+    msg.extend((0..14).map(|i| text.get(i).copied().unwrap_or(b' ') & 0x7F));
+    msg.push(0xF7);
+    msg
+}
+
+/// Build the CC that shows `value` (0.0-1.0) on a V-Pot LED ring in MCU
+/// mode: `B0 <48 + encoder> <value>`.
+///
+/// The value byte holds the LED position (bits 0-3, 0 = all off, 1-11),
+/// the ring mode (bits 4-5) and the center LED (bit 6). Spread only has 6
+/// distinct widths.
+pub fn build_ring_cc(encoder: u8, mode: RingMode, value: f64, center_led: bool) -> Vec<u8> {
+    debug_assert!(encoder <= 7);
+    let value = value.clamp(0.0, 1.0);
+    let (mode_bits, position) = match mode {
+        RingMode::Dot => (0, 1.0 + value * 10.0),
+        RingMode::BoostCut => (1, 1.0 + value * 10.0),
+        RingMode::Wrap => (2, value * 11.0),
+        RingMode::Spread => (3, 1.0 + value * 5.0),
+    };
+    let center = if center_led { 0x40 } else { 0x00 };
+    vec![
+        0xB0,
+        48 + encoder,
+        center | (mode_bits << 4) | position.round() as u8,
+    ]
+}
+
+/// SysEx body (without `F0`/`F7`) for the 12-digit 7-segment display:
+/// one segment byte per character, then the two dot bytes. Bit `i` of
+/// `dots` lights the dot after character `i`.
+fn seven_segment_data(text: &str, dots: u16, device_id: u8) -> Vec<u8> {
+    let mut data = vec![0x00, 0x20, 0x32, device_id, 0x37];
+    let mut segs: Vec<u8> = text
+        .chars()
+        .take(12)
+        .map(XTouchDriver::seven_seg_for_char)
+        .collect();
+    segs.resize(12, 0x00);
+    data.extend_from_slice(&segs);
+    data.push((dots & 0x7F) as u8);
+    data.push(((dots >> 7) & 0x1F) as u8);
+    data
+}
+
+/// Build the SysEx messages that write `text` (up to 12 characters,
+/// left-aligned, no centering) to the 7-segment timecode display.
+///
+/// Returns one message per device ID (0x14 and 0x15, as
+/// [`XTouchDriver::set_seven_segment_text`] sends), as raw MIDI byte
+/// sequences ready for `send_raw()` or the LED channel.
+pub fn build_seven_segment_sysex(text: &str, dots: u16) -> Vec<Vec<u8>> {
+    [0x14, 0x15]
+        .into_iter()
+        .map(|device_id| {
+            let mut msg = vec![0xF0];
+            msg.extend(seven_segment_data(text, dots, device_id));
+            msg.push(0xF7);
+            msg
+        })
+        .collect()
+}
+
 /// Port discovery utilities
 pub mod discovery {
     use super::*;
@@ -846,4 +955,60 @@ mod tests {
         let _ = discovery::discover_output_ports();
         let _ = discovery::find_xtouch_ports();
     }
+
+    #[test]
+    fn seven_segment_sysex_pads_and_sets_dots() {
+        let msgs = build_seven_segment_sysex("12", 0b1_0000_0001);
+        assert_eq!(msgs.len(), 2);
+        let msg = &msgs[1];
+        assert_eq!(&msg[..6], &[0xF0, 0x00, 0x20, 0x32, 0x15, 0x37]);
+        assert_eq!(&msg[6..8], &[0x06, 0x5B]);
+        assert!(msg[8..18].iter().all(|&b| b == 0));
+        assert_eq!(&msg[18..], &[0x01, 0x02, 0xF7]);
+    }
+
+    #[test]
+    fn ctrl_lcd_sysex_sets_strip_color_and_text() {
+        let msg = build_ctrl_lcd_sysex(2, 3, b"Mic    -12dB  ");
+        assert_eq!(msg.len(), 23);
+        assert_eq!(&msg[..8], &[0xF0, 0x00, 0x20, 0x32, 0x14, 0x4C, 2, 3]);
+        assert_eq!(&msg[8..22], b"Mic    -12dB  ");
+        assert_eq!(msg[22], 0xF7);
+    }
+
+    #[test]
+    fn displays_follow_device() {
+        let (text, _) = build_lcd_strip_sysex(0, "Mic", "");
+        let colors = build_lcd_colors_sysex(&[1; 8]);
+        let led = [0x90, 16, 127];
+        assert!(device_can_show(DeviceProfile::Xtouch, &colors));
+        assert!(device_can_show(DeviceProfile::Mcu, &text));
+        assert!(!device_can_show(DeviceProfile::Mcu, &colors));
+        assert!(!device_can_show(DeviceProfile::XtouchCompact, &text));
+        assert!(device_can_show(DeviceProfile::PlatformM, &led));
+    }
+
+    #[test]
+    fn ring_cc_encodes_mode_position_and_center() {
+        assert_eq!(
+            build_ring_cc(0, RingMode::Dot, 0.0, false),
+            vec![0xB0, 48, 0x01]
+        );
+        assert_eq!(
+            build_ring_cc(2, RingMode::BoostCut, 0.5, false),
+            vec![0xB0, 50, 0x16]
+        );
+        assert_eq!(
+            build_ring_cc(7, RingMode::Wrap, 0.0, true),
+            vec![0xB0, 55, 0x60]
+        );
+        assert_eq!(
+            build_ring_cc(7, RingMode::Wrap, 1.0, false),
+            vec![0xB0, 55, 0x2B]
+        );
+        assert_eq!(
+            build_ring_cc(3, RingMode::Spread, 1.0, false),
+            vec![0xB0, 51, 0x36]
+        );
+    }
 }
@@ -12,6 +12,8 @@
 //!   per-channel worker `send().await`s, so a brief consumer stall back-pressures
 //!   just that channel instead of dropping the final (last-wins) value.
 //! - **Debounced application**: 90 ms default, 0 ms for the 0/16383 extremes.
+//! - **Touch lock**: while a fader is touched nothing is applied; the latest
+//!   setpoint is applied on release, so the motor never fights the hand.
 //!
 //! ## Why this shape
 //!
@@ -59,6 +61,10 @@ struct ChannelState {
     /// falls back to the extreme-aware default. Last-write-wins like
     /// `desired14`, so the most recent `schedule` for the channel decides.
     override_delay_ms: Option<u64>,
+    /// Fader touched: setpoints are held until release.
+    touched: bool,
+    /// A setpoint may have been held back by the touch: apply on release.
+    held: bool,
 }
 
 /// Resident worker for a single channel. Owns a `Notify` used to re-arm work
@@ -147,6 +153,7 @@ impl FaderSetpoint {
             // retries fell back to the 0/90 ms default — effectively immediate
             // for the 0/16383 extremes during a device failure.
             state.override_delay_ms = delay_ms;
+            state.held |= state.touched;
             state.epoch
         };
 
@@ -161,6 +168,50 @@ impl FaderSetpoint {
         self.ensure_worker(channel).notify_one();
     }
 
+    /// Record a touch (or release) of `channel`'s fader.
+    ///
+    /// Touching cancels any queued apply (epoch bump); releasing applies the
+    /// latest setpoint at once, whatever arrived in between (app feedback or
+    /// the user's own moves).
+    pub fn set_touched(&self, channel: u8, touched: bool) {
+        if !(1..=9).contains(&channel) {
+            return;
+        }
+        let release_apply = {
+            let mut channels = self.channels.write().unwrap();
+            // Never scheduled: no setpoint to hold yet
+            let scheduled = channels.contains_key(&channel);
+            let state = channels.entry(channel).or_default();
+            if state.touched == touched {
+                return;
+            }
+            state.touched = touched;
+            // Cancel any queued apply; it is held for the release
+            state.epoch += 1;
+            if touched {
+                state.held = scheduled;
+                false
+            } else if std::mem::take(&mut state.held) {
+                state.override_delay_ms = Some(0);
+                true
+            } else {
+                false
+            }
+        };
+
+        trace!("FaderSetpoint touch: ch={} touched={}", channel, touched);
+
+        if release_apply {
+            self.ensure_worker(channel).notify_one();
+        }
+    }
+
+    /// Whether `channel`'s fader is touched
+    pub fn is_touched(&self, channel: u8) -> bool {
+        let channels = self.channels.read().unwrap();
+        channels.get(&channel).is_some_and(|state| state.touched)
+    }
+
     /// Lazily create the resident worker for `channel` and return its Notify.
     /// The per-call delay override travels via `ChannelState::override_delay_ms`
     /// (set in `schedule`), so this no longer needs a delay argument.
@@ -205,6 +256,10 @@ impl FaderSetpoint {
                     let Some(state) = write.get_mut(&channel) else {
                         continue;
                     };
+                    // Held until release, which wakes the worker again
+                    if state.touched {
+                        continue;
+                    }
                     let is_extreme = state.desired14 == 0 || state.desired14 == 16383;
                     let delay = state.override_delay_ms.take().unwrap_or(if is_extreme {
                         0
@@ -224,11 +279,11 @@ impl FaderSetpoint {
                 let still_current = {
                     let read = channels.read().unwrap();
                     read.get(&channel)
-                        .is_some_and(|s| s.epoch == snapshot_epoch)
+                        .is_some_and(|s| s.epoch == snapshot_epoch && !s.touched)
                 };
                 if !still_current {
                     trace!(
-                        "FaderSetpoint apply SKIPPED (obsolete): ch={} epoch={}",
+                        "FaderSetpoint apply SKIPPED (obsolete or touched): ch={} epoch={}",
                         channel,
                         snapshot_epoch
                     );
@@ -396,6 +451,33 @@ mod tests {
         while rx.try_recv().is_ok() {}
     }
 
+    #[tokio::test]
+    async fn test_touch_holds_setpoints_until_release() {
+        let (setpoint, mut rx) = FaderSetpoint::new();
+
+        // Untouched faders that were never scheduled apply nothing on release.
+        setpoint.set_touched(2, true);
+        setpoint.set_touched(2, false);
+
+        setpoint.schedule(1, 100, Some(0));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(rx.try_recv().unwrap().value14, 100);
+
+        setpoint.set_touched(1, true);
+        assert!(setpoint.is_touched(1));
+        setpoint.schedule(1, 200, Some(0));
+        setpoint.schedule(1, 300, Some(0));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(rx.try_recv().is_err(), "no motor move while touched");
+
+        setpoint.set_touched(1, false);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let cmd = rx.try_recv().expect("latest setpoint applied on release");
+        assert_eq!((cmd.channel, cmd.value14), (1, 300));
+        assert!(setpoint.is_epoch_current(1, cmd.epoch));
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_worker_aborts_on_drop() {
         // Resident workers must not outlive their `FaderSetpoint`. We can't
@@ -0,0 +1,248 @@
+//! Output shadow of the X-Touch surface state
+//!
+//! Remembers what was last written to every LCD character cell, the strip
+//! colors, the button LEDs and the V-Pot rings. A write that changes nothing
+//! is dropped, and LCD text writes are narrowed to the changed cells with the
+//! MCU per-offset SysEx, so page flips and reloads only transmit what
+//! differs. Everything is unknown after a (re)connect, so the first write of
+//! each cell always goes through. Faders are never filtered: the user moves
+//! them, so their last written position says nothing about the surface.
+
+use super::STRIP_CHARS;
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+/// `F0 00 00 66 14 12 <offset> <chars...> F7`: LCD text from `offset`.
+pub(crate) const LCD_TEXT_HEADER: [u8; 6] = [0xF0, 0x00, 0x00, 0x66, 0x14, 0x12];
+
+/// `F0 00 00 66 14 72 <8 colors> F7`: LCD strip colors.
+pub(crate) const LCD_COLORS_HEADER: [u8; 6] = [0xF0, 0x00, 0x00, 0x66, 0x14, 0x72];
+
+/// LCD character cells: 2 lines of 56 (8 strips × 7).
+const LCD_CELLS: usize = 112;
+
+/// V-Pot LED ring CCs (channel 1).
+const RING_CCS: RangeInclusive<u8> = 48..=55;
+
+/// Last values written to the surface (`None`: unknown).
+#[derive(Debug, Clone)]
+pub struct OutputCache {
+    lcd: [Option<u8>; LCD_CELLS],
+    colors: Option<[u8; 8]>,
+    /// Button LED velocity per note (channel 1).
+    leds: [Option<u8>; 128],
+    rings: [Option<u8>; 8],
+}
+
+impl Default for OutputCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputCache {
+    /// Create a cache where everything is unknown
+    pub fn new() -> Self {
+        Self {
+            lcd: [None; LCD_CELLS],
+            colors: None,
+            leds: [None; 128],
+            rings: [None; 8],
+        }
+    }
+
+    /// Forget everything (the surface may have been reset or replaced)
+    pub fn invalidate(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The part of `data` that still has to be sent, recording it as shown.
+    ///
+    /// `None` when the surface already shows it. LCD text comes back
+    /// narrowed to the changed cells; anything the cache doesn't track is
+    /// returned unchanged.
+    pub fn filter<'a>(&mut self, data: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+        match *data {
+            // NoteOn / NoteOff: button LEDs (NoteOff turns them off)
+            [status @ (0x90 | 0x80), note, velocity] if note < 0x80 => {
+                let velocity = if status == 0x80 { 0 } else { velocity };
+                Self::update(&mut self.leds[usize::from(note)], velocity)
+                    .then_some(Cow::Borrowed(data))
+            },
+            [0xB0, cc, value] if RING_CCS.contains(&cc) => {
+                let ring = usize::from(cc - RING_CCS.start());
+                Self::update(&mut self.rings[ring], value).then_some(Cow::Borrowed(data))
+            },
+            _ if data.len() == 15 && data.starts_with(&LCD_COLORS_HEADER) && data[14] == 0xF7 => {
+                let mut colors = [0; 8];
+                colors.copy_from_slice(&data[6..14]);
+                if self.colors == Some(colors) {
+                    return None;
+                }
+                self.colors = Some(colors);
+                Some(Cow::Borrowed(data))
+            },
+            _ if data.len() > 8
+                && data.starts_with(&LCD_TEXT_HEADER)
+                && data.ends_with(&[0xF7]) =>
+            {
+                self.filter_lcd_text(data)
+            },
+            _ => Some(Cow::Borrowed(data)),
+        }
+    }
+
+    /// Record `value` in `slot`; whether it changed.
+    fn update(slot: &mut Option<u8>, value: u8) -> bool {
+        let changed = *slot != Some(value);
+        *slot = Some(value);
+        changed
+    }
+
+    fn filter_lcd_text<'a>(&mut self, data: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+        let offset = usize::from(data[6]);
+        let chars = &data[7..data.len() - 1];
+        if offset + chars.len() > LCD_CELLS {
+            // Not a layout we know: send it, and stop trusting the LCD
+            self.lcd = [None; LCD_CELLS];
+            return Some(Cow::Borrowed(data));
+        }
+
+        let cells = &mut self.lcd[offset..offset + chars.len()];
+        let changed = |(cell, &ch): (&Option<u8>, &u8)| *cell != Some(ch);
+        let first = cells.iter().zip(chars).position(changed)?;
+        let last = chars.len() - 1 - cells.iter().zip(chars).rev().position(changed)?;
+        for (cell, &ch) in cells.iter_mut().zip(chars) {
+            *cell = Some(ch);
+        }
+
+        if first == 0 && last == chars.len() - 1 {
+            return Some(Cow::Borrowed(data));
+        }
+        let mut msg = Vec::with_capacity(LCD_TEXT_HEADER.len() + last - first + 3);
+        msg.extend_from_slice(&LCD_TEXT_HEADER);
+        msg.push((offset + first) as u8);
+        msg.extend_from_slice(&chars[first..=last]);
+        msg.push(0xF7);
+        Some(Cow::Owned(msg))
+    }
+
+    /// Strips an LCD text or color message writes to; `None` for anything
+    /// else.
+    pub fn lcd_strips(data: &[u8]) -> Option<RangeInclusive<u8>> {
+        if !data.ends_with(&[0xF7]) {
+            return None;
+        }
+        if data.starts_with(&LCD_COLORS_HEADER) {
+            return Some(0..=7);
+        }
+        if !data.starts_with(&LCD_TEXT_HEADER) || data.len() < 9 {
+            return None;
+        }
+        let offset = usize::from(data[6]);
+        let cells = offset..(offset + data.len() - 8).min(LCD_CELLS);
+        let strips = cells.map(|cell| (cell % (LCD_CELLS / 2) / STRIP_CHARS) as u8);
+        Some(strips.clone().min()?..=strips.max()?)
+    }
+
+    /// What strip `strip` (0-7) shows: its color (white when unknown) and
+    /// its upper then lower line (blank where unknown).
+    pub fn lcd_strip(&self, strip: u8) -> (u8, [u8; 2 * STRIP_CHARS]) {
+        let color = self
+            .colors
+            .map_or(7, |colors| colors[usize::from(strip) % 8]);
+        let start = usize::from(strip) * STRIP_CHARS;
+        let mut text = [b' '; 2 * STRIP_CHARS];
+        for (line, chars) in text.chunks_mut(STRIP_CHARS).enumerate() {
+            let cells = start + line * LCD_CELLS / 2;
+            for (ch, cell) in chars.iter_mut().zip(&self.lcd[cells..cells + STRIP_CHARS]) {
+                *ch = cell.unwrap_or(b' ');
+            }
+        }
+        (color, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xtouch::{build_lcd_colors_sysex, build_lcd_strip_sysex};
+
+    #[test]
+    fn lcd_writes_map_to_strips() {
+        let mut cache = OutputCache::new();
+        let (upper, lower) = build_lcd_strip_sysex(2, "Mic", "-12dB");
+        assert_eq!(OutputCache::lcd_strips(&upper), Some(2..=2));
+        assert_eq!(OutputCache::lcd_strips(&lower), Some(2..=2));
+        let colors = build_lcd_colors_sysex(&[1, 2, 3, 4, 5, 6, 7, 0]);
+        assert_eq!(OutputCache::lcd_strips(&colors), Some(0..=7));
+        assert_eq!(OutputCache::lcd_strips(&[0x90, 8, 127]), None);
+
+        cache.filter(&upper);
+        assert_eq!(cache.lcd_strip(2), (7, *b"Mic           "));
+        cache.filter(&lower);
+        cache.filter(&colors);
+        assert_eq!(cache.lcd_strip(2), (3, *b"Mic    -12dB  "));
+    }
+
+    #[test]
+    fn unchanged_leds_and_rings_are_dropped() {
+        let mut cache = OutputCache::new();
+        assert!(cache.filter(&[0x90, 8, 127]).is_some());
+        assert!(cache.filter(&[0x90, 8, 127]).is_none());
+        assert!(cache.filter(&[0x80, 8, 0]).is_some());
+        assert!(cache.filter(&[0x90, 8, 0]).is_none());
+
+        assert!(cache.filter(&[0xB0, 48, 5]).is_some());
+        assert!(cache.filter(&[0xB0, 48, 5]).is_none());
+        // Fader CCs (CTRL mode) and pitch bends always go through
+        assert!(cache.filter(&[0xB0, 70, 5]).is_some());
+        assert!(cache.filter(&[0xB0, 70, 5]).is_some());
+        assert!(cache.filter(&[0xE0, 0, 64]).is_some());
+        assert!(cache.filter(&[0xE0, 0, 64]).is_some());
+
+        cache.invalidate();
+        assert!(cache.filter(&[0x90, 8, 0]).is_some());
+    }
+
+    #[test]
+    fn lcd_text_is_narrowed_to_changed_cells() {
+        let mut cache = OutputCache::new();
+        let (upper, lower) = build_lcd_strip_sysex(2, "Mic", "-12dB");
+        assert_eq!(cache.filter(&upper).as_deref(), Some(&upper[..]));
+        assert_eq!(cache.filter(&lower).as_deref(), Some(&lower[..]));
+        assert!(cache.filter(&upper).is_none());
+
+        // "-12dB" -> "-9dB": only cells 1..=4 of strip 2's lower line change
+        let (_, lower) = build_lcd_strip_sysex(2, "Mic", "-9dB");
+        let sent = cache.filter(&lower).unwrap();
+        assert_eq!(
+            &sent[..],
+            &[
+                0xF0,
+                0x00,
+                0x00,
+                0x66,
+                0x14,
+                0x12,
+                0x38 + 14 + 1,
+                b'9',
+                b'd',
+                b'B',
+                b' ',
+                0xF7
+            ]
+        );
+        assert!(cache.filter(&lower).is_none());
+    }
+
+    #[test]
+    fn lcd_colors_are_sent_on_change() {
+        let mut cache = OutputCache::new();
+        let colors = build_lcd_colors_sysex(&[1, 2, 3, 4, 5, 6, 7, 0]);
+        assert!(cache.filter(&colors).is_some());
+        assert!(cache.filter(&colors).is_none());
+        let colors = build_lcd_colors_sysex(&[1, 2, 3, 4, 5, 6, 7, 7]);
+        assert!(cache.filter(&colors).is_some());
+    }
+}
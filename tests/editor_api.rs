@@ -213,6 +213,27 @@ async fn validate_accepts_minimal_config() {
     assert_eq!(body["ok"], true);
 }
 
+#[tokio::test]
+async fn validate_rejects_process_allowlist_in_profile() {
+    let f = fx();
+    let body_yaml = format!(
+        "{}process:\n  commands:\n    shell:\n      program: /bin/sh\n",
+        SAMPLE_YAML
+    );
+    let (s, body) = send(
+        &f.router,
+        json_req(
+            Method::POST,
+            "/api/validate",
+            serde_json::json!({ "body": body_yaml }),
+        ),
+    )
+    .await;
+    assert_eq!(s, StatusCode::OK);
+    assert_eq!(body["ok"], false);
+    assert_eq!(body["errors"][0]["field_path"], "process");
+}
+
 /// Build a fixture with a populated driver catalog (no live OBS).
 fn fx_with_catalog() -> Fx {
     let tmp = tempfile::tempdir().unwrap();